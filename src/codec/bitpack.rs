@@ -0,0 +1,217 @@
+use std::cmp;
+use crate::set::{Set, SetBuf};
+use super::varint::{read_varint, write_varint};
+use super::{Integer, DecodeError, apply_delta};
+
+/// The number of deltas packed with the same number of bits.
+pub(super) const BLOCK_LEN: usize = 128;
+
+/// Encode the set using delta encoding and bit-packed blocks, appending the bytes to `output`.
+///
+/// The number of elements and the first element are written as varints, the deltas are then
+/// split into blocks of 128, each block starts with the number of bits used by its deltas.
+///
+/// # Examples
+/// ```
+/// # use sdset::codec::DecodeError;
+/// # fn try_main() -> Result<(), DecodeError> {
+/// use sdset::codec::{encode_bitpacked, decode_bitpacked};
+/// use sdset::{Set, SetBuf};
+///
+/// let vec: Vec<u64> = (0..1000).map(|x| x * 3).collect();
+/// let set = Set::new(&vec)?;
+///
+/// let mut bytes = Vec::new();
+/// encode_bitpacked(set, &mut bytes);
+/// assert!(bytes.len() < 300);
+///
+/// let decoded: SetBuf<u64> = decode_bitpacked(&bytes)?;
+/// assert_eq!(decoded.as_set(), set);
+/// # Ok(()) }
+/// # try_main().unwrap();
+/// ```
+pub fn encode_bitpacked<T: Integer>(set: &Set<T>, output: &mut Vec<u8>) {
+    write_varint(set.len() as u64, output);
+
+    let (first, others) = match set.split_first() {
+        Some(split) => split,
+        None => return,
+    };
+
+    let mut previous = first.to_u64();
+    write_varint(previous, output);
+
+    let mut deltas = [0; BLOCK_LEN];
+    for block in others.chunks(BLOCK_LEN) {
+        for (delta, x) in deltas.iter_mut().zip(block) {
+            let x = x.to_u64();
+            *delta = x - previous;
+            previous = x;
+        }
+        pack_block(&deltas[..block.len()], output);
+    }
+}
+
+/// Decode a set encoded with [`encode_bitpacked`].
+///
+/// The bytes must contain exactly one encoded set.
+pub fn decode_bitpacked<T: Integer>(mut bytes: &[u8]) -> Result<SetBuf<T>, DecodeError> {
+    let bytes = &mut bytes;
+    let len = read_varint(bytes)?;
+
+    if len == 0 {
+        return if bytes.is_empty() { Ok(SetBuf::default()) } else { Err(DecodeError::Malformed) };
+    }
+
+    // every delta takes at least one bit, do not trust the length to allocate
+    let capacity = cmp::min(len, (bytes.len() as u64).saturating_mul(8)) as usize;
+    let mut vec = Vec::with_capacity(capacity);
+
+    let mut previous = read_varint(bytes)?;
+    vec.push(T::from_u64(previous).ok_or(DecodeError::Malformed)?);
+
+    let mut remaining = len - 1;
+    let mut deltas = [0; BLOCK_LEN];
+    while remaining != 0 {
+        let count = cmp::min(remaining, BLOCK_LEN as u64) as usize;
+        unpack_block(bytes, &mut deltas[..count])?;
+
+        for &delta in &deltas[..count] {
+            let (x, y) = apply_delta(previous, delta)?;
+            vec.push(y);
+            previous = x;
+        }
+
+        remaining -= count as u64;
+    }
+
+    if !bytes.is_empty() {
+        return Err(DecodeError::Malformed);
+    }
+
+    Ok(SetBuf::new_unchecked(vec))
+}
+
+/// Write the number of bits of the biggest delta followed by the deltas packed using it.
+pub(super) fn pack_block(deltas: &[u64], output: &mut Vec<u8>) {
+    let width = deltas.iter().map(|d| 64 - d.leading_zeros()).max().unwrap_or(0);
+    output.push(width as u8);
+
+    let mut acc: u128 = 0;
+    let mut bits = 0;
+    for &delta in deltas {
+        acc |= u128::from(delta) << bits;
+        bits += width;
+        while bits >= 8 {
+            output.push(acc as u8);
+            acc >>= 8;
+            bits -= 8;
+        }
+    }
+
+    if bits != 0 {
+        output.push(acc as u8);
+    }
+}
+
+/// Read a block written by [`pack_block`], the number of deltas is the length of `deltas`.
+pub(super) fn unpack_block(bytes: &mut &[u8], deltas: &mut [u64]) -> Result<(), DecodeError> {
+    let (&width, rest) = bytes.split_first().ok_or(DecodeError::UnexpectedEnd)?;
+    if width > 64 {
+        return Err(DecodeError::Malformed);
+    }
+
+    let width = u32::from(width);
+    let len = (deltas.len() * width as usize + 7) / 8;
+    if rest.len() < len {
+        return Err(DecodeError::UnexpectedEnd);
+    }
+
+    let (packed, rest) = rest.split_at(len);
+    *bytes = rest;
+
+    let mask = if width == 64 { u64::MAX } else { (1 << width) - 1 };
+    let mut packed = packed.iter();
+    let mut acc: u128 = 0;
+    let mut bits = 0;
+    for delta in deltas {
+        while bits < width {
+            // the packed length has been computed from the number of deltas
+            acc |= u128::from(*packed.next().unwrap()) << bits;
+            bits += 8;
+        }
+        *delta = acc as u64 & mask;
+        acc >>= width;
+        bits -= width;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::set::{sort_dedup_vec, Error};
+
+    #[test]
+    fn blocks() {
+        for &width in &[1, 7, 8, 13, 63, 64] {
+            let max = if width == 64 { u64::MAX } else { (1 << width) - 1 };
+            let deltas: Vec<u64> = (0..BLOCK_LEN as u64).map(|i| max >> (i % 3)).collect();
+
+            let mut bytes = Vec::new();
+            pack_block(&deltas, &mut bytes);
+            assert_eq!(bytes[0], width);
+
+            let mut slice = &bytes[..];
+            let mut unpacked = [0; BLOCK_LEN];
+            unpack_block(&mut slice, &mut unpacked).unwrap();
+            assert_eq!(&unpacked[..], &deltas[..]);
+            assert!(slice.is_empty());
+        }
+    }
+
+    #[test]
+    fn corrupted() {
+        // 3 elements: 5, then two deltas of 1 bit: 1 and 0
+        assert_eq!(decode_bitpacked::<u32>(&[3, 5, 1, 0b01]), Err(DecodeError::Set(Error::NotDedup)));
+        // 2 elements: 250, then a delta of 4 bits: 10 does not fit in an u8
+        assert_eq!(decode_bitpacked::<u8>(&[2, 0xfa, 0x01, 4, 10]), Err(DecodeError::Set(Error::NotSort)));
+        assert_eq!(decode_bitpacked::<u32>(&[3, 5, 8, 1]), Err(DecodeError::UnexpectedEnd));
+        assert_eq!(decode_bitpacked::<u32>(&[2, 5, 65, 1]), Err(DecodeError::Malformed));
+        assert_eq!(decode_bitpacked::<u32>(&[1, 5, 0]), Err(DecodeError::Malformed));
+    }
+
+    quickcheck! {
+        fn qc_bitpacked_u32(xs: Vec<u32>) -> bool {
+            let mut xs = xs;
+            sort_dedup_vec(&mut xs);
+
+            let mut bytes = Vec::new();
+            encode_bitpacked(Set::new_unchecked(&xs), &mut bytes);
+
+            decode_bitpacked::<u32>(&bytes).map(SetBuf::into_vec) == Ok(xs)
+        }
+
+        fn qc_bitpacked_u64(xs: Vec<u64>) -> bool {
+            // spread the values over the whole range
+            let mut xs: Vec<_> = xs.into_iter().map(|x| x.wrapping_mul(0x9E37_79B9_7F4A_7C15)).collect();
+            sort_dedup_vec(&mut xs);
+
+            let mut bytes = Vec::new();
+            encode_bitpacked(Set::new_unchecked(&xs), &mut bytes);
+
+            decode_bitpacked::<u64>(&bytes).map(SetBuf::into_vec) == Ok(xs)
+        }
+
+        fn qc_bitpacked_dense(len: u16, step: u8) -> bool {
+            let step = u32::from(step) + 1;
+            let xs: Vec<u32> = (0..u32::from(len)).map(|x| x * step).collect();
+
+            let mut bytes = Vec::new();
+            encode_bitpacked(Set::new_unchecked(&xs), &mut bytes);
+
+            decode_bitpacked::<u32>(&bytes).map(SetBuf::into_vec) == Ok(xs)
+        }
+    }
+}
@@ -0,0 +1,278 @@
+use std::cmp;
+use crate::set::{Set, SetBuf};
+use crate::{exponential_offset_ge, exponential_offset_ge_by_key, SeekableIterator};
+use super::{CompressedSet, Integer};
+use super::bitpack::{pack_block, unpack_block, BLOCK_LEN};
+
+/// Represent a set of integers compressed by blocks of 128 elements,
+/// that can be used in set operations without being entirely decoded.
+///
+/// The elements of a block are stored as bit-packed deltas, every block has a skip header
+/// holding its first element and the offset of its deltas in the compressed bytes.
+/// Seeking an element gallops through the headers and only decodes the block it lands in,
+/// the [`duo`](crate::duo) and [`multi`](crate::multi) compressed operations never decode
+/// the blocks they skip.
+///
+/// # Examples
+/// ```
+/// # use sdset::Error;
+/// # fn try_main() -> Result<(), Error> {
+/// use sdset::codec::BlockSet;
+/// use sdset::{SeekableIterator, Set, SetBuf};
+///
+/// let vec: Vec<u32> = (0..10_000).map(|x| x * 2).collect();
+/// let set = BlockSet::new(Set::new(&vec)?);
+/// assert_eq!(set.len(), 10_000);
+///
+/// let mut iter = set.iter();
+/// iter.advance_to(&15_001);
+/// assert_eq!(iter.next(), Some(15_002));
+///
+/// let decoded: SetBuf<u32> = set.to_set_buf();
+/// assert_eq!(decoded.as_slice(), &vec[..]);
+/// # Ok(()) }
+/// # try_main().unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockSet<T> {
+    headers: Vec<BlockHeader<T>>,
+    bytes: Vec<u8>,
+    len: usize,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct BlockHeader<T> {
+    first: T,
+    offset: usize,
+}
+
+impl<T: Integer> BlockSet<T> {
+    /// Compress the given set.
+    pub fn new(set: &Set<T>) -> Self {
+        let mut headers = Vec::with_capacity((set.len() + BLOCK_LEN - 1) / BLOCK_LEN);
+        let mut bytes = Vec::new();
+        let mut deltas = [0; BLOCK_LEN];
+
+        for block in set.chunks(BLOCK_LEN) {
+            headers.push(BlockHeader { first: block[0], offset: bytes.len() });
+
+            for (delta, pair) in deltas.iter_mut().zip(block.windows(2)) {
+                *delta = pair[1].to_u64() - pair[0].to_u64();
+            }
+            pack_block(&deltas[..block.len() - 1], &mut bytes);
+        }
+
+        BlockSet { headers, bytes, len: set.len() }
+    }
+
+    /// Returns the number of elements in the set.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the set contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns an iterator decoding the blocks as it reaches them.
+    pub fn iter(&self) -> BlockSetIter<'_, T> {
+        BlockSetIter {
+            set: self,
+            block: Vec::new(),
+            pos: 0,
+            next_block: 0,
+        }
+    }
+
+    /// Decode the whole set.
+    pub fn to_set_buf(&self) -> SetBuf<T> {
+        let mut vec = Vec::with_capacity(self.len);
+        let mut block = Vec::with_capacity(BLOCK_LEN);
+        for index in 0..self.headers.len() {
+            self.decode_block(index, &mut block);
+            vec.extend_from_slice(&block);
+        }
+        SetBuf::new_unchecked(vec)
+    }
+
+    fn decode_block(&self, index: usize, output: &mut Vec<T>) {
+        let header = &self.headers[index];
+        let count = cmp::min(BLOCK_LEN, self.len - index * BLOCK_LEN);
+
+        let mut deltas = [0; BLOCK_LEN];
+        let mut bytes = &self.bytes[header.offset..];
+        // the blocks have been encoded by this type from a valid set
+        unpack_block(&mut bytes, &mut deltas[..count - 1]).unwrap();
+
+        output.clear();
+        output.push(header.first);
+
+        let mut previous = header.first.to_u64();
+        for delta in &deltas[..count - 1] {
+            previous += delta;
+            output.push(T::from_u64(previous).unwrap());
+        }
+    }
+}
+
+impl<'a, T: Integer> From<&'a Set<T>> for BlockSet<T> {
+    fn from(set: &'a Set<T>) -> Self {
+        BlockSet::new(set)
+    }
+}
+
+impl<T: Integer> CompressedSet for BlockSet<T> {
+    type Item = T;
+    type Iter<'a> = BlockSetIter<'a, T> where Self: 'a;
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        BlockSet::iter(self)
+    }
+}
+
+impl<'a, T: Integer> IntoIterator for &'a BlockSet<T> {
+    type Item = T;
+    type IntoIter = BlockSetIter<'a, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the elements of a [`BlockSet`], the blocks are decoded one at a time.
+pub struct BlockSetIter<'a, T> {
+    set: &'a BlockSet<T>,
+    block: Vec<T>,
+    pos: usize,
+    next_block: usize,
+}
+
+impl<'a, T: Integer> BlockSetIter<'a, T> {
+    fn load(&mut self, index: usize) {
+        self.set.decode_block(index, &mut self.block);
+        self.pos = 0;
+        self.next_block = index + 1;
+    }
+
+    /// Returns the next element without consuming it, decoding the next block if needed.
+    fn peek(&mut self) -> Option<&T> {
+        if self.pos == self.block.len() {
+            if self.next_block == self.set.headers.len() {
+                return None;
+            }
+            self.load(self.next_block);
+        }
+        self.block.get(self.pos)
+    }
+}
+
+impl<'a, T: Integer> Iterator for BlockSetIter<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let x = *self.peek()?;
+        self.pos += 1;
+        Some(x)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let decoded = cmp::min(self.next_block * BLOCK_LEN, self.set.len);
+        let len = self.block.len() - self.pos + self.set.len - decoded;
+        (len, Some(len))
+    }
+}
+
+impl<'a, T: Integer> ExactSizeIterator for BlockSetIter<'a, T> { }
+
+impl<'a, T: Integer> SeekableIterator<T> for BlockSetIter<'a, T> {
+    fn advance_to(&mut self, target: &T) {
+        // the target is in the decoded block
+        if self.block.last().map_or(false, |last| last >= target) {
+            let remaining = exponential_offset_ge(&self.block[self.pos..], target);
+            self.pos = self.block.len() - remaining.len();
+            return;
+        }
+
+        // the decoded block is skipped, find the block that can contain the target
+        self.pos = self.block.len();
+        let headers = &self.set.headers[self.next_block..];
+        let after = exponential_offset_ge_by_key(headers, target, |h| h.first);
+        let mut index = headers.len() - after.len();
+        if after.first().map_or(true, |h| h.first != *target) {
+            // the next block starts after the target
+            if index == 0 {
+                return;
+            }
+            index -= 1;
+        }
+
+        self.load(self.next_block + index);
+        let remaining = exponential_offset_ge(&self.block, target);
+        self.pos = self.block.len() - remaining.len();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::set::sort_dedup_vec;
+
+    #[test]
+    fn empty() {
+        let set = BlockSet::<u32>::new(Set::new_unchecked(&[]));
+        assert!(set.is_empty());
+        assert_eq!(set.iter().next(), None);
+        assert!(set.to_set_buf().is_empty());
+    }
+
+    #[test]
+    fn skipped_blocks_are_not_decoded() {
+        let vec: Vec<u32> = (0..10 * BLOCK_LEN as u32).collect();
+        let set = BlockSet::new(Set::new_unchecked(&vec));
+
+        let mut iter = set.iter();
+        iter.advance_to(&(7 * BLOCK_LEN as u32 + 3));
+        assert_eq!(iter.next_block, 8);
+        assert_eq!(iter.next(), Some(7 * BLOCK_LEN as u32 + 3));
+        assert_eq!(iter.len(), 3 * BLOCK_LEN - 4);
+    }
+
+    quickcheck! {
+        fn qc_roundtrip(xs: Vec<u64>) -> bool {
+            let mut xs: Vec<_> = xs.into_iter().map(|x| x.wrapping_mul(0x9E37_79B9_7F4A_7C15)).collect();
+            sort_dedup_vec(&mut xs);
+
+            let set = BlockSet::new(Set::new_unchecked(&xs));
+            let iterated: Vec<_> = set.iter().collect();
+
+            set.len() == xs.len() && set.to_set_buf().as_slice() == &xs[..] && iterated == xs
+        }
+
+        fn qc_advance_to(xs: Vec<u32>, targets: Vec<u32>) -> bool {
+            let mut xs = xs;
+            let mut targets = targets;
+            // spread the elements over multiple blocks
+            xs.extend(xs.clone().into_iter().flat_map(|x| (0..50).map(move |i| x.wrapping_mul(50) + i)));
+            sort_dedup_vec(&mut xs);
+            sort_dedup_vec(&mut targets);
+
+            let set = BlockSet::new(Set::new_unchecked(&xs));
+            let mut iter = set.iter();
+            let mut slice = xs.iter();
+
+            for target in &targets {
+                iter.advance_to(target);
+                slice.advance_to(target);
+                if iter.len() != slice.len() || iter.next() != slice.next().cloned() {
+                    return false;
+                }
+            }
+
+            iter.eq(slice.cloned())
+        }
+    }
+}
@@ -0,0 +1,449 @@
+use std::cmp;
+use std::ops::{Bound, Range, RangeBounds};
+use crate::set::{Set, SetBuf};
+use crate::SeekableIterator;
+use super::CompressedSet;
+
+/// The number of ones (and zeros) of the upper bits between two sampled positions.
+const SAMPLE: usize = 256;
+
+/// Represent a set of `u64` stored using the Elias-Fano encoding.
+///
+/// Every element is split into its low bits, stored in a packed array, and its high bits,
+/// stored in unary in a bitvector. The set takes less than `2 + log2(max / len)` bits per
+/// element, plus a sampling of the bitvector used to answer [`select`](EliasFanoSet::select)
+/// and [`rank`](EliasFanoSet::rank) queries without scanning it entirely.
+///
+/// # Examples
+/// ```
+/// # use sdset::Error;
+/// # fn try_main() -> Result<(), Error> {
+/// use sdset::codec::EliasFanoSet;
+/// use sdset::{Set, SetBuf};
+///
+/// let vec: Vec<u64> = (0..1000).map(|x| x * 3).collect();
+/// let set = EliasFanoSet::new(Set::new(&vec)?);
+///
+/// assert!(set.contains(300));
+/// assert!(!set.contains(301));
+/// assert_eq!(set.rank(301), 101);
+/// assert_eq!(set.select(101), Some(303));
+///
+/// let range: Vec<u64> = set.range(10..20).collect();
+/// assert_eq!(&range[..], &[12, 15, 18]);
+///
+/// let decoded: SetBuf<u64> = set.to_set_buf();
+/// assert_eq!(decoded.as_slice(), &vec[..]);
+/// # Ok(()) }
+/// # try_main().unwrap();
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct EliasFanoSet {
+    len: usize,
+    low_bits: u32,
+    lower: Vec<u64>,
+    upper: Vec<u64>,
+    upper_len: usize,
+    // the positions of every SAMPLE-th one and zero of the upper bits
+    ones: Vec<usize>,
+    zeros: Vec<usize>,
+}
+
+impl EliasFanoSet {
+    /// Encode the given set.
+    pub fn new(set: &Set<u64>) -> Self {
+        let len = set.len();
+        let last = match set.last() {
+            Some(&last) => last,
+            None => return EliasFanoSet::default(),
+        };
+
+        // the high parts of the elements are then less than two times the length
+        let ratio = last / len as u64;
+        let low_bits = if ratio == 0 { 0 } else { 63 - ratio.leading_zeros() };
+        let upper_len = len + (last >> low_bits) as usize + 1;
+
+        let mut lower = vec![0; (len * low_bits as usize + 63) / 64];
+        let mut upper = vec![0; (upper_len + 63) / 64];
+
+        for (i, &x) in set.iter().enumerate() {
+            if low_bits != 0 {
+                write_bits(&mut lower, i * low_bits as usize, low_bits, x & low_mask(low_bits));
+            }
+            let pos = (x >> low_bits) as usize + i;
+            upper[pos / 64] |= 1 << (pos % 64);
+        }
+
+        let mut ones = Vec::with_capacity(len / SAMPLE + 1);
+        let mut zeros = Vec::with_capacity((upper_len - len) / SAMPLE + 1);
+        let (mut count_ones, mut count_zeros) = (0, 0);
+        for pos in 0..upper_len {
+            if upper[pos / 64] & (1 << (pos % 64)) != 0 {
+                if count_ones % SAMPLE == 0 { ones.push(pos) }
+                count_ones += 1;
+            } else {
+                if count_zeros % SAMPLE == 0 { zeros.push(pos) }
+                count_zeros += 1;
+            }
+        }
+
+        EliasFanoSet { len, low_bits, lower, upper, upper_len, ones, zeros }
+    }
+
+    /// Returns the number of elements in the set.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the set contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns `true` if the set contains the element.
+    pub fn contains(&self, x: u64) -> bool {
+        self.select(self.rank(x)) == Some(x)
+    }
+
+    /// Returns the number of elements of the set that are less than `x`.
+    pub fn rank(&self, x: u64) -> usize {
+        if self.len == 0 {
+            return 0;
+        }
+
+        let high = x >> self.low_bits;
+        let max_high = (self.upper_len - self.len - 1) as u64;
+        if high > max_high {
+            return self.len;
+        }
+
+        // the elements sharing the same high part are consecutive ones in the upper bits,
+        // a skewed set can put most of them in one bucket, their low parts are searched
+        let low = x & low_mask(self.low_bits);
+        let Range { mut start, mut end } = self.bucket(high as usize);
+        while start < end {
+            let mid = start + (end - start) / 2;
+            if self.low(mid) < low {
+                start = mid + 1;
+            } else {
+                end = mid;
+            }
+        }
+        start
+    }
+
+    /// Returns the element at the given index, the elements being sorted.
+    pub fn select(&self, index: usize) -> Option<u64> {
+        if index >= self.len {
+            return None;
+        }
+        let pos = self.select_one(index);
+        Some(self.element(index, pos))
+    }
+
+    /// Returns an iterator over the elements of the set that are in the range.
+    pub fn range<R: RangeBounds<u64>>(&self, range: R) -> EliasFanoIter<'_> {
+        let start = match range.start_bound() {
+            Bound::Included(&x) => self.rank(x),
+            Bound::Excluded(&x) => x.checked_add(1).map_or(self.len, |x| self.rank(x)),
+            Bound::Unbounded => 0,
+        };
+
+        let end = match range.end_bound() {
+            Bound::Included(&x) => x.checked_add(1).map_or(self.len, |x| self.rank(x)),
+            Bound::Excluded(&x) => self.rank(x),
+            Bound::Unbounded => self.len,
+        };
+
+        EliasFanoIter::new(self, start, cmp::max(start, end))
+    }
+
+    /// Returns an iterator over the elements of the set.
+    pub fn iter(&self) -> EliasFanoIter<'_> {
+        EliasFanoIter::new(self, 0, self.len)
+    }
+
+    /// Decode the whole set.
+    pub fn to_set_buf(&self) -> SetBuf<u64> {
+        SetBuf::new_unchecked(self.iter().collect())
+    }
+
+    fn element(&self, index: usize, pos: usize) -> u64 {
+        ((pos - index) as u64) << self.low_bits | self.low(index)
+    }
+
+    fn low(&self, index: usize) -> u64 {
+        if self.low_bits == 0 {
+            return 0;
+        }
+        read_bits(&self.lower, index * self.low_bits as usize, self.low_bits)
+    }
+
+    /// Returns the indexes of the elements with the given high part,
+    /// the bucket is terminated by the zero of this high part in the upper bits.
+    fn bucket(&self, high: usize) -> Range<usize> {
+        let start = if high == 0 { 0 } else { self.select_zero(high - 1) + 1 - high };
+        let end = self.select_zero(high) - high;
+        start..end
+    }
+
+    fn select_one(&self, k: usize) -> usize {
+        select(&self.upper, &self.ones, k, |word| word)
+    }
+
+    fn select_zero(&self, k: usize) -> usize {
+        select(&self.upper, &self.zeros, k, |word| !word)
+    }
+}
+
+impl CompressedSet for EliasFanoSet {
+    type Item = u64;
+    type Iter<'a> = EliasFanoIter<'a>;
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        EliasFanoSet::iter(self)
+    }
+}
+
+impl<'a> From<&'a Set<u64>> for EliasFanoSet {
+    fn from(set: &'a Set<u64>) -> Self {
+        EliasFanoSet::new(set)
+    }
+}
+
+impl<'a> IntoIterator for &'a EliasFanoSet {
+    type Item = u64;
+    type IntoIter = EliasFanoIter<'a>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the elements of an [`EliasFanoSet`].
+pub struct EliasFanoIter<'a> {
+    set: &'a EliasFanoSet,
+    index: usize,
+    end: usize,
+    pos: usize,
+}
+
+impl<'a> EliasFanoIter<'a> {
+    fn new(set: &'a EliasFanoSet, index: usize, end: usize) -> Self {
+        let pos = if index < end { set.select_one(index) } else { 0 };
+        EliasFanoIter { set, index, end, pos }
+    }
+}
+
+impl<'a> Iterator for EliasFanoIter<'a> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index == self.end {
+            return None;
+        }
+
+        // there is always a one after the position of the previous element
+        let upper = &self.set.upper;
+        let mut w = self.pos / 64;
+        let mut word = upper[w] & (!0 << (self.pos % 64));
+        while word == 0 {
+            w += 1;
+            word = upper[w];
+        }
+
+        let pos = w * 64 + word.trailing_zeros() as usize;
+        let x = self.set.element(self.index, pos);
+        self.index += 1;
+        self.pos = pos + 1;
+
+        Some(x)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.index;
+        (len, Some(len))
+    }
+}
+
+impl<'a> ExactSizeIterator for EliasFanoIter<'a> { }
+
+impl<'a> SeekableIterator<u64> for EliasFanoIter<'a> {
+    fn advance_to(&mut self, target: &u64) {
+        let rank = self.set.rank(*target);
+        if rank > self.index {
+            self.index = cmp::min(rank, self.end);
+            if self.index < self.end {
+                self.pos = self.set.select_one(self.index);
+            }
+        }
+    }
+}
+
+fn low_mask(bits: u32) -> u64 {
+    (1 << bits) - 1
+}
+
+fn write_bits(words: &mut [u64], start: usize, width: u32, value: u64) {
+    let (w, shift) = (start / 64, (start % 64) as u32);
+    words[w] |= value << shift;
+    if shift + width > 64 {
+        words[w + 1] |= value >> (64 - shift);
+    }
+}
+
+fn read_bits(words: &[u64], start: usize, width: u32) -> u64 {
+    let (w, shift) = (start / 64, (start % 64) as u32);
+    let mut value = words[w] >> shift;
+    if shift + width > 64 {
+        value |= words[w + 1] << (64 - shift);
+    }
+    value & low_mask(width)
+}
+
+/// Returns the position of the `k`-th set bit of the words transformed by `f`,
+/// starting the search from the closest sampled position.
+fn select<F: Fn(u64) -> u64>(words: &[u64], samples: &[usize], k: usize, f: F) -> usize {
+    let pos = samples[k / SAMPLE];
+    let mut remaining = k % SAMPLE;
+
+    let mut w = pos / 64;
+    let mut word = f(words[w]) & (!0 << (pos % 64));
+    loop {
+        let count = word.count_ones() as usize;
+        if remaining < count {
+            for _ in 0..remaining {
+                word &= word - 1;
+            }
+            return w * 64 + word.trailing_zeros() as usize;
+        }
+        remaining -= count;
+        w += 1;
+        word = f(words[w]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::set::sort_dedup_vec;
+
+    #[test]
+    fn empty() {
+        let set = EliasFanoSet::new(Set::new_unchecked(&[]));
+        assert!(set.is_empty());
+        assert!(!set.contains(0));
+        assert_eq!(set.rank(42), 0);
+        assert_eq!(set.select(0), None);
+        assert_eq!(set.iter().next(), None);
+    }
+
+    #[test]
+    fn extremes() {
+        let vec = vec![0, 1, u64::MAX - 1, u64::MAX];
+        let set = EliasFanoSet::new(Set::new_unchecked(&vec));
+        assert!(set.contains(u64::MAX));
+        assert_eq!(set.rank(u64::MAX), 3);
+        assert_eq!(set.range(1..=u64::MAX).count(), 3);
+        assert_eq!(set.to_set_buf().as_slice(), &vec[..]);
+    }
+
+    #[test]
+    fn compact() {
+        let vec: Vec<u64> = (0..100_000).map(|x| x * 37 + x % 7).collect();
+        let set = EliasFanoSet::new(Set::new_unchecked(&vec));
+
+        // the lower bits take 5 bits per element and the upper bits close to 2
+        let bits = (set.lower.len() + set.upper.len()) * 64;
+        assert!(bits <= vec.len() * 8);
+        assert_eq!(set.to_set_buf().as_slice(), &vec[..]);
+    }
+
+    #[test]
+    fn skewed() {
+        // the dense elements share the same high part
+        let mut vec: Vec<u64> = (0..200_000).collect();
+        vec.push(1 << 40);
+        let set = EliasFanoSet::new(Set::new_unchecked(&vec));
+        assert_eq!(set.rank(199_999), 199_999);
+        assert_eq!(set.rank(200_000), 200_000);
+
+        let mut iter = set.iter();
+        for target in (0..200_000).step_by(3) {
+            iter.advance_to(&target);
+            assert_eq!(iter.next(), Some(target));
+        }
+        iter.advance_to(&200_000);
+        assert_eq!(iter.next(), Some(1 << 40));
+        assert_eq!(iter.next(), None);
+    }
+
+    quickcheck! {
+        fn qc_rank_select(xs: Vec<u64>, ys: Vec<u64>) -> bool {
+            let mut xs = xs;
+            sort_dedup_vec(&mut xs);
+            let set = EliasFanoSet::new(Set::new_unchecked(&xs));
+
+            let ranks = ys.iter().chain(&xs).all(|&y| {
+                let rank = xs.iter().filter(|&&x| x < y).count();
+                set.rank(y) == rank && set.contains(y) == xs.contains(&y)
+            });
+
+            let selects = xs.iter().enumerate().all(|(i, &x)| set.select(i) == Some(x));
+
+            ranks && selects && set.select(xs.len()).is_none()
+        }
+
+        fn qc_range(xs: Vec<u64>, start: u64, end: u64) -> bool {
+            use std::collections::BTreeSet;
+            use std::iter::FromIterator;
+
+            // spread the values to have both dense and sparse sets
+            let mut xs: Vec<_> = xs.into_iter().map(|x| x.wrapping_mul(start % 5 + 1)).collect();
+            sort_dedup_vec(&mut xs);
+            let set = EliasFanoSet::new(Set::new_unchecked(&xs));
+
+            let (start, end) = (cmp::min(start, end), cmp::max(start, end));
+            let x: Vec<_> = set.range(start..end).collect();
+
+            let btree = BTreeSet::from_iter(xs);
+            let y: Vec<_> = btree.range(start..end).cloned().collect();
+
+            x == y
+        }
+
+        fn qc_advance_to(xs: Vec<u64>, targets: Vec<u64>) -> bool {
+            let mut xs = xs;
+            let mut targets = targets;
+            // spread the elements over multiple samples
+            xs.extend(xs.clone().into_iter().flat_map(|x| (0..50).map(move |i| x.wrapping_mul(1000).wrapping_add(i * 3))));
+            sort_dedup_vec(&mut xs);
+            sort_dedup_vec(&mut targets);
+
+            let set = EliasFanoSet::new(Set::new_unchecked(&xs));
+            let mut iter = set.iter();
+            let mut slice = xs.iter();
+
+            for target in &targets {
+                iter.advance_to(target);
+                slice.advance_to(target);
+                if iter.len() != slice.len() || iter.next() != slice.next().cloned() {
+                    return false;
+                }
+            }
+
+            iter.eq(slice.cloned())
+        }
+
+        fn qc_roundtrip(xs: Vec<u64>) -> bool {
+            let mut xs: Vec<_> = xs.into_iter().map(|x| x.wrapping_mul(0x9E37_79B9_7F4A_7C15)).collect();
+            sort_dedup_vec(&mut xs);
+
+            let set = EliasFanoSet::new(Set::new_unchecked(&xs));
+            set.len() == xs.len() && set.to_set_buf().as_slice() == &xs[..]
+        }
+    }
+}
@@ -0,0 +1,160 @@
+//! Contains the functions to encode sets of integers in a compact binary form.
+//!
+//! The elements of a set being sorted and deduplicated, the differences between
+//! consecutive elements (the deltas) are small positive numbers that are stored using:
+//!   - [LEB128 varints](encode_varint), where each delta takes a number of bytes depending on its size.
+//!   - [bit-packed blocks](encode_bitpacked), where the deltas of a block of 128 elements
+//!     are stored using the number of bits of the biggest one.
+//!
+//! The decoders check that the decoded elements are sorted and deduplicated.
+//!
+//! The [`BlockSet`], [`EliasFanoSet`] and [`RoaringSet`] types keep the set compressed in memory,
+//! they implement the [`CompressedSet`] trait and can be given to the
+//! [`duo`](crate::duo) and [`multi`](crate::multi) compressed operations.
+//!
+//! # Examples
+//! ```
+//! # use sdset::codec::DecodeError;
+//! # fn try_main() -> Result<(), DecodeError> {
+//! use sdset::codec::{encode_varint, decode_varint};
+//! use sdset::{Set, SetBuf};
+//!
+//! let set = Set::new(&[1u32, 2, 4, 300, 301, 70_000])?;
+//!
+//! let mut bytes = Vec::new();
+//! encode_varint(set, &mut bytes);
+//! assert_eq!(bytes.len(), 10);
+//!
+//! let decoded: SetBuf<u32> = decode_varint(&bytes)?;
+//! assert_eq!(decoded.as_set(), set);
+//! # Ok(()) }
+//! # try_main().unwrap();
+//! ```
+
+use std::{error, fmt};
+use crate::set::Error;
+use crate::SeekableIterator;
+
+mod bitpack;
+mod block;
+mod elias_fano;
+mod roaring;
+mod varint;
+
+pub use self::bitpack::{encode_bitpacked, decode_bitpacked};
+pub use self::block::{BlockSet, BlockSetIter};
+pub use self::elias_fano::{EliasFanoSet, EliasFanoIter};
+pub use self::roaring::{RoaringSet, RoaringIter};
+pub use self::varint::{encode_varint, decode_varint};
+pub(crate) use self::varint::{write_varint_array, MAX_VARINT_LEN};
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for u8 {}
+    impl Sealed for u16 {}
+    impl Sealed for u32 {}
+    impl Sealed for u64 {}
+}
+
+/// The primitive integers that can be encoded.
+///
+/// This trait is sealed and cannot be implemented outside of this crate.
+pub trait Integer: Ord + Copy + sealed::Sealed {
+    /// Widen the integer to an `u64`.
+    #[doc(hidden)]
+    fn to_u64(self) -> u64;
+
+    /// Narrow an `u64` to the integer type if it fits.
+    #[doc(hidden)]
+    fn from_u64(x: u64) -> Option<Self>;
+}
+
+macro_rules! impl_integer {
+    ($($t:ty),*) => {$(
+        impl Integer for $t {
+            fn to_u64(self) -> u64 {
+                self as u64
+            }
+
+            fn from_u64(x: u64) -> Option<Self> {
+                if x <= <$t>::MAX as u64 { Some(x as $t) } else { None }
+            }
+        }
+    )*}
+}
+
+impl_integer!(u8, u16, u32, u64);
+
+/// Represent a set stored in a compressed form that can be iterated
+/// in order and seeked without being entirely decoded.
+///
+/// The compressed sets can be used as inputs of the [`duo`](crate::duo)
+/// and [`multi`](crate::multi) compressed set operations.
+pub trait CompressedSet {
+    /// The type of the elements of the set.
+    type Item: Ord + Copy;
+
+    /// The iterator decoding the elements in order.
+    type Iter<'a>: SeekableIterator<Self::Item, Item = Self::Item> where Self: 'a;
+
+    /// Returns the number of elements in the set.
+    fn len(&self) -> usize;
+
+    /// Returns `true` if the set contains no elements.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns an iterator over the elements of the set.
+    fn iter(&self) -> Self::Iter<'_>;
+}
+
+/// Represent the possible errors when decoding a set.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum DecodeError {
+    /// Define that the decoded elements are not sorted or not deduplicated.
+    Set(Error),
+    /// Define that the bytes ended before the end of the set.
+    UnexpectedEnd,
+    /// Define that the bytes are not a valid encoding, e.g. an overlong varint or trailing bytes.
+    Malformed,
+}
+
+impl From<Error> for DecodeError {
+    fn from(error: Error) -> DecodeError {
+        DecodeError::Set(error)
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            DecodeError::Set(e) => fmt::Display::fmt(e, f),
+            DecodeError::UnexpectedEnd => f.write_str("unexpected end of the encoded set."),
+            DecodeError::Malformed => f.write_str("malformed encoded set."),
+        }
+    }
+}
+
+impl error::Error for DecodeError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            DecodeError::Set(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Add a delta to the previous element, a null delta means a duplicate
+/// and a delta overflowing the integer type means that the elements were not sorted.
+#[inline]
+fn apply_delta<T: Integer>(previous: u64, delta: u64) -> Result<(u64, T), DecodeError> {
+    if delta == 0 {
+        return Err(DecodeError::Set(Error::NotDedup));
+    }
+
+    let x = previous.checked_add(delta).ok_or(DecodeError::Set(Error::NotSort))?;
+    let y = T::from_u64(x).ok_or(DecodeError::Set(Error::NotSort))?;
+    Ok((x, y))
+}
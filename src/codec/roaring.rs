@@ -0,0 +1,625 @@
+use std::cmp::{self, Ordering};
+use crate::duo::OpBuilder;
+use crate::set::{Set, SetBuf};
+use crate::{exponential_offset_ge, exponential_offset_ge_by_key, SeekableIterator, SetOperation};
+use super::CompressedSet;
+
+/// The number of words of a bitmap container, one bit for every 16 bits integer.
+const BITMAP_WORDS: usize = 1024;
+
+/// The size in bytes of a bitmap container.
+const BITMAP_BYTES: usize = BITMAP_WORDS * 8;
+
+/// Represent a set of `u32` split by the high 16 bits of its elements
+/// into containers holding the low 16 bits.
+///
+/// Every container uses the smallest representation for its elements:
+///   - an array of sorted and deduplicated `u16`, the set operations between two arrays
+///     are made with the [`duo`](crate::duo) slice algorithms.
+///   - a bitmap of 65536 bits, when the container holds more than 4096 elements.
+///   - a list of runs of consecutive elements, when the container holds dense ranges.
+///
+/// The _union_, _intersection_, _difference_ and _symmetric difference_ are computed
+/// container by container and follow the semantics of [`duo::OpBuilder`](crate::duo::OpBuilder).
+///
+/// # Examples
+/// ```
+/// # use sdset::Error;
+/// # fn try_main() -> Result<(), Error> {
+/// use sdset::codec::RoaringSet;
+/// use sdset::{Set, SetBuf};
+///
+/// let vec: Vec<u32> = (0..100_000).chain(200_000..200_010).collect();
+/// let a = RoaringSet::new(Set::new(&vec)?);
+/// let b = RoaringSet::new(Set::new(&[5, 99_999, 100_000, 200_005])?);
+///
+/// assert_eq!(a.len(), 100_010);
+/// assert!(a.contains(200_003));
+///
+/// let res: SetBuf<u32> = a.intersection(&b).to_set_buf();
+/// assert_eq!(&res[..], &[5, 99_999, 200_005]);
+///
+/// let res: SetBuf<u32> = b.difference(&a).to_set_buf();
+/// assert_eq!(&res[..], &[100_000]);
+/// # Ok(()) }
+/// # try_main().unwrap();
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RoaringSet {
+    keys: Vec<u16>,
+    containers: Vec<Container>,
+    len: usize,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Operation {
+    Union,
+    Intersection,
+    Difference,
+    SymmetricDifference,
+}
+
+impl RoaringSet {
+    /// Split the given set into containers.
+    pub fn new(set: &Set<u32>) -> Self {
+        let mut keys = Vec::new();
+        let mut containers = Vec::new();
+
+        let mut slice = set.as_slice();
+        while let Some(&first) = slice.first() {
+            let high = (first >> 16) as u16;
+            let (group, next) = match high.checked_add(1) {
+                Some(next_high) => {
+                    let next = exponential_offset_ge(slice, &((next_high as u32) << 16));
+                    slice.split_at(slice.len() - next.len())
+                },
+                None => (slice, &[][..]),
+            };
+
+            keys.push(high);
+            containers.push(Container::Array(group.iter().map(|&x| x as u16).collect()).optimize());
+            slice = next;
+        }
+
+        RoaringSet { keys, containers, len: set.len() }
+    }
+
+    /// Returns the number of elements in the set.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the set contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns `true` if the set contains the element.
+    pub fn contains(&self, x: u32) -> bool {
+        match self.keys.binary_search(&((x >> 16) as u16)) {
+            Ok(index) => self.containers[index].contains(x as u16),
+            Err(_) => false,
+        }
+    }
+
+    /// Returns an iterator over the elements of the set.
+    pub fn iter(&self) -> RoaringIter<'_> {
+        RoaringIter {
+            keys: &self.keys,
+            containers: &self.containers,
+            current: None,
+        }
+    }
+
+    /// Decode the whole set.
+    pub fn to_set_buf(&self) -> SetBuf<u32> {
+        let mut vec = Vec::with_capacity(self.len);
+        vec.extend(self.iter());
+        SetBuf::new_unchecked(vec)
+    }
+
+    /// Returns the _union_ of the two sets.
+    pub fn union(&self, other: &RoaringSet) -> RoaringSet {
+        self.operation(other, Operation::Union)
+    }
+
+    /// Returns the _intersection_ of the two sets.
+    pub fn intersection(&self, other: &RoaringSet) -> RoaringSet {
+        self.operation(other, Operation::Intersection)
+    }
+
+    /// Returns the _difference_ of the two sets.
+    pub fn difference(&self, other: &RoaringSet) -> RoaringSet {
+        self.operation(other, Operation::Difference)
+    }
+
+    /// Returns the _symmetric difference_ of the two sets.
+    pub fn symmetric_difference(&self, other: &RoaringSet) -> RoaringSet {
+        self.operation(other, Operation::SymmetricDifference)
+    }
+
+    fn operation(&self, other: &RoaringSet, op: Operation) -> RoaringSet {
+        let keep_a = op != Operation::Intersection;
+        let keep_b = op == Operation::Union || op == Operation::SymmetricDifference;
+
+        let mut result = RoaringSet::default();
+        let (mut i, mut j) = (0, 0);
+
+        while i < self.keys.len() && j < other.keys.len() {
+            match self.keys[i].cmp(&other.keys[j]) {
+                Ordering::Less => {
+                    if keep_a { result.push(self.keys[i], self.containers[i].clone()) }
+                    i += 1;
+                },
+                Ordering::Greater => {
+                    if keep_b { result.push(other.keys[j], other.containers[j].clone()) }
+                    j += 1;
+                },
+                Ordering::Equal => {
+                    let container = self.containers[i].operation(&other.containers[j], op);
+                    result.push(self.keys[i], container);
+                    i += 1;
+                    j += 1;
+                },
+            }
+        }
+
+        if keep_a {
+            for (&key, container) in self.keys[i..].iter().zip(&self.containers[i..]) {
+                result.push(key, container.clone());
+            }
+        }
+
+        if keep_b {
+            for (&key, container) in other.keys[j..].iter().zip(&other.containers[j..]) {
+                result.push(key, container.clone());
+            }
+        }
+
+        result
+    }
+
+    fn push(&mut self, key: u16, container: Container) {
+        let len = container.len();
+        if len != 0 {
+            self.keys.push(key);
+            self.containers.push(container);
+            self.len += len;
+        }
+    }
+}
+
+impl CompressedSet for RoaringSet {
+    type Item = u32;
+    type Iter<'a> = RoaringIter<'a>;
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        RoaringSet::iter(self)
+    }
+}
+
+impl<'a> From<&'a Set<u32>> for RoaringSet {
+    fn from(set: &'a Set<u32>) -> Self {
+        RoaringSet::new(set)
+    }
+}
+
+impl<'a> IntoIterator for &'a RoaringSet {
+    type Item = u32;
+    type IntoIter = RoaringIter<'a>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// A run of consecutive elements, both bounds are inclusive.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Run {
+    start: u16,
+    last: u16,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Container {
+    Array(Vec<u16>),
+    Bitmap(Box<[u64; BITMAP_WORDS]>, usize),
+    Run(Vec<Run>),
+}
+
+impl Container {
+    fn len(&self) -> usize {
+        match self {
+            Container::Array(array) => array.len(),
+            Container::Bitmap(_, len) => *len,
+            Container::Run(runs) => runs.iter().map(|r| (r.last - r.start) as usize + 1).sum(),
+        }
+    }
+
+    fn contains(&self, x: u16) -> bool {
+        match self {
+            Container::Array(array) => array.binary_search(&x).is_ok(),
+            Container::Bitmap(words, _) => words[x as usize / 64] & (1 << (x % 64)) != 0,
+            Container::Run(runs) => {
+                runs.binary_search_by(|r| {
+                    if r.last < x { Ordering::Less }
+                    else if r.start > x { Ordering::Greater }
+                    else { Ordering::Equal }
+                }).is_ok()
+            },
+        }
+    }
+
+    fn iter(&self) -> ContainerIter<'_> {
+        match self {
+            Container::Array(array) => ContainerIter::Array(array.iter()),
+            Container::Bitmap(words, _) => ContainerIter::Bitmap { words, index: 0, word: words[0] },
+            Container::Run(runs) => {
+                let next = runs.first().map_or(0, |r| r.start as u32);
+                ContainerIter::Run { runs, next }
+            },
+        }
+    }
+
+    /// Returns the number of runs of consecutive elements.
+    fn runs_count(&self) -> usize {
+        match self {
+            Container::Array(array) => {
+                let breaks = array.windows(2).filter(|w| w[0] + 1 != w[1]).count();
+                if array.is_empty() { 0 } else { breaks + 1 }
+            },
+            Container::Bitmap(words, _) => {
+                // count the set bits that do not follow another set bit
+                let mut carry = 0;
+                words.iter().map(|&word| {
+                    let starts = word & !(word << 1 | carry);
+                    carry = word >> 63;
+                    starts.count_ones() as usize
+                }).sum()
+            },
+            Container::Run(runs) => runs.len(),
+        }
+    }
+
+    /// Convert the container to the representation taking the least space.
+    fn optimize(self) -> Container {
+        let len = self.len();
+        let runs_size = self.runs_count() * 4;
+        let array_size = len * 2;
+
+        if runs_size < cmp::min(array_size, BITMAP_BYTES) {
+            self.into_runs()
+        } else if array_size <= BITMAP_BYTES {
+            self.into_array()
+        } else {
+            self.into_bitmap()
+        }
+    }
+
+    fn into_array(self) -> Container {
+        match self {
+            Container::Array(array) => Container::Array(array),
+            other => Container::Array(other.iter().collect()),
+        }
+    }
+
+    fn into_bitmap(self) -> Container {
+        match self {
+            Container::Bitmap(words, len) => Container::Bitmap(words, len),
+            other => Container::Bitmap(other.to_words(), other.len()),
+        }
+    }
+
+    fn into_runs(self) -> Container {
+        if let Container::Run(runs) = self {
+            return Container::Run(runs);
+        }
+
+        let mut runs: Vec<Run> = Vec::new();
+        for x in self.iter() {
+            match runs.last_mut() {
+                Some(run) if run.last + 1 == x => run.last = x,
+                _ => runs.push(Run { start: x, last: x }),
+            }
+        }
+        Container::Run(runs)
+    }
+
+    fn to_words(&self) -> Box<[u64; BITMAP_WORDS]> {
+        if let Container::Bitmap(words, _) = self {
+            return words.clone();
+        }
+
+        let mut words = Box::new([0; BITMAP_WORDS]);
+        for x in self.iter() {
+            words[x as usize / 64] |= 1 << (x % 64);
+        }
+        words
+    }
+
+    fn operation(&self, other: &Container, op: Operation) -> Container {
+        let container = match (self, other, op) {
+            (Container::Array(a), Container::Array(b), op) => {
+                Container::Array(array_operation(a, b, op))
+            },
+            (Container::Array(a), b, Operation::Intersection) => {
+                Container::Array(a.iter().cloned().filter(|&x| b.contains(x)).collect())
+            },
+            (a, Container::Array(b), Operation::Intersection) => {
+                Container::Array(b.iter().cloned().filter(|&x| a.contains(x)).collect())
+            },
+            (Container::Array(a), b, Operation::Difference) => {
+                Container::Array(a.iter().cloned().filter(|&x| !b.contains(x)).collect())
+            },
+            (a, b, op) => {
+                let mut words = a.to_words();
+                let other = b.to_words();
+                let mut len = 0;
+                for (a, &b) in words.iter_mut().zip(other.iter()) {
+                    *a = match op {
+                        Operation::Union => *a | b,
+                        Operation::Intersection => *a & b,
+                        Operation::Difference => *a & !b,
+                        Operation::SymmetricDifference => *a ^ b,
+                    };
+                    len += a.count_ones() as usize;
+                }
+                Container::Bitmap(words, len)
+            },
+        };
+
+        container.optimize()
+    }
+}
+
+/// Make the set operation on two arrays using the slice algorithms.
+fn array_operation(a: &[u16], b: &[u16], op: Operation) -> Vec<u16> {
+    let builder = OpBuilder::new(Set::new_unchecked(a), Set::new_unchecked(b));
+    let set = match op {
+        Operation::Union => builder.union().into_set_buf(),
+        Operation::Intersection => builder.intersection().into_set_buf(),
+        Operation::Difference => builder.difference().into_set_buf(),
+        Operation::SymmetricDifference => builder.symmetric_difference().into_set_buf(),
+    };
+    set.into_vec()
+}
+
+enum ContainerIter<'a> {
+    Array(std::slice::Iter<'a, u16>),
+    Bitmap { words: &'a [u64; BITMAP_WORDS], index: usize, word: u64 },
+    // the next element is always in the first run
+    Run { runs: &'a [Run], next: u32 },
+}
+
+impl<'a> Iterator for ContainerIter<'a> {
+    type Item = u16;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            ContainerIter::Array(iter) => iter.next().cloned(),
+            ContainerIter::Bitmap { words, index, word } => {
+                while *word == 0 {
+                    if *index + 1 == BITMAP_WORDS {
+                        return None;
+                    }
+                    *index += 1;
+                    *word = words[*index];
+                }
+                let x = *index * 64 + word.trailing_zeros() as usize;
+                *word &= *word - 1;
+                Some(x as u16)
+            },
+            ContainerIter::Run { runs, next } => {
+                let run = runs.first()?;
+                let x = *next;
+                if x == run.last as u32 {
+                    *runs = &runs[1..];
+                    *next = runs.first().map_or(0, |r| r.start as u32);
+                } else {
+                    *next += 1;
+                }
+                Some(x as u16)
+            },
+        }
+    }
+}
+
+impl<'a> SeekableIterator<u16> for ContainerIter<'a> {
+    fn advance_to(&mut self, target: &u16) {
+        match self {
+            ContainerIter::Array(iter) => iter.advance_to(target),
+            ContainerIter::Bitmap { words, index, word } => {
+                let (target_index, mask) = (*target as usize / 64, !0 << (target % 64));
+                match target_index.cmp(index) {
+                    Ordering::Greater => {
+                        *index = target_index;
+                        *word = words[target_index] & mask;
+                    },
+                    Ordering::Equal => *word &= mask,
+                    Ordering::Less => (),
+                }
+            },
+            ContainerIter::Run { runs, next } => {
+                *runs = exponential_offset_ge_by_key(runs, target, |r| r.last);
+                if let Some(run) = runs.first() {
+                    // the next element was before the run if it has been skipped
+                    *next = cmp::max(*next, cmp::max(run.start, *target) as u32);
+                }
+            },
+        }
+    }
+}
+
+/// An iterator over the elements of a [`RoaringSet`].
+pub struct RoaringIter<'a> {
+    keys: &'a [u16],
+    containers: &'a [Container],
+    current: Option<(u32, ContainerIter<'a>)>,
+}
+
+impl<'a> RoaringIter<'a> {
+    fn load_next(&mut self) -> Option<&mut (u32, ContainerIter<'a>)> {
+        let (&key, keys) = self.keys.split_first()?;
+        let (container, containers) = self.containers.split_first()?;
+        self.keys = keys;
+        self.containers = containers;
+        self.current = Some((key as u32, container.iter()));
+        self.current.as_mut()
+    }
+}
+
+impl<'a> Iterator for RoaringIter<'a> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some((high, iter)) = &mut self.current {
+            if let Some(low) = iter.next() {
+                return Some(*high << 16 | low as u32);
+            }
+        }
+
+        // containers are never empty
+        let (high, iter) = self.load_next()?;
+        iter.next().map(|low| *high << 16 | low as u32)
+    }
+}
+
+impl<'a> SeekableIterator<u32> for RoaringIter<'a> {
+    fn advance_to(&mut self, target: &u32) {
+        let (high, low) = (*target >> 16, *target as u16);
+
+        if let Some((current, iter)) = &mut self.current {
+            match (*current).cmp(&high) {
+                Ordering::Equal => {
+                    iter.advance_to(&low);
+                    return;
+                },
+                Ordering::Greater => return,
+                Ordering::Less => (),
+            }
+        }
+
+        // the containers before the target one are skipped without being read
+        let after = exponential_offset_ge(self.keys, &(high as u16));
+        let skipped = self.keys.len() - after.len();
+        self.keys = after;
+        self.containers = &self.containers[skipped..];
+        self.current = None;
+
+        if self.keys.first() == Some(&(high as u16)) {
+            if let Some((_, iter)) = self.load_next() {
+                iter.advance_to(&low);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::set::sort_dedup_vec;
+
+    fn kinds(set: &RoaringSet) -> Vec<&'static str> {
+        set.containers.iter().map(|c| match c {
+            Container::Array(_) => "array",
+            Container::Bitmap(..) => "bitmap",
+            Container::Run(_) => "run",
+        }).collect()
+    }
+
+    #[test]
+    fn empty() {
+        let set = RoaringSet::new(Set::new_unchecked(&[]));
+        assert!(set.is_empty());
+        assert!(!set.contains(0));
+        assert_eq!(set.iter().next(), None);
+        assert!(set.to_set_buf().is_empty());
+    }
+
+    #[test]
+    fn containers_kinds() {
+        let mut vec: Vec<u32> = vec![1, 5, 9];
+        vec.extend((1 << 16..2 << 16).step_by(3));
+        vec.extend((2 << 16) + 100..(2 << 16) + 50_000);
+        vec.extend(&[u32::MAX - 1, u32::MAX]);
+
+        let set = RoaringSet::new(Set::new_unchecked(&vec));
+        assert_eq!(kinds(&set), &["array", "bitmap", "run", "array"]);
+        assert_eq!(set.to_set_buf().as_slice(), &vec[..]);
+
+        // the intersection of runs with a sparse bitmap has no more runs to compress
+        let other: Vec<u32> = (0..(3 << 16)).filter(|x| x % 3 == 1).collect();
+        let expected: SetBuf<u32> = OpBuilder::new(Set::new_unchecked(&vec), Set::new_unchecked(&other)).intersection().into_set_buf();
+        let other = RoaringSet::new(Set::new_unchecked(&other));
+        let res = set.intersection(&other);
+        assert_eq!(kinds(&res), &["array", "bitmap", "bitmap"]);
+        assert_eq!(res.len(), expected.len());
+        assert_eq!(res.to_set_buf(), expected);
+    }
+
+    quickcheck! {
+        fn qc_roundtrip(xs: Vec<u32>, dense: Vec<u16>) -> bool {
+            let mut xs = xs;
+            // add dense ranges to have bitmaps and runs
+            xs.extend(dense.into_iter().take(4).flat_map(|x| (0..5000).map(move |i| (x as u32) << 14 | i)));
+            sort_dedup_vec(&mut xs);
+
+            let set = RoaringSet::new(Set::new_unchecked(&xs));
+            let iterated: Vec<_> = set.iter().collect();
+
+            set.len() == xs.len()
+                && set.to_set_buf().as_slice() == &xs[..]
+                && iterated == xs
+                && xs.iter().all(|&x| set.contains(x))
+        }
+
+        fn qc_operations(a: Vec<u32>, b: Vec<u32>, dense: Vec<u16>) -> bool {
+            let mut a: Vec<_> = a.into_iter().map(|x| x % (1 << 18)).collect();
+            let mut b: Vec<_> = b.into_iter().map(|x| x % (1 << 18)).collect();
+            for (i, x) in dense.into_iter().take(4).enumerate() {
+                let range = (0..6000).map(move |i| ((x as u32 % 4) << 16) | (i * (x as u32 % 3 + 1)));
+                if i % 2 == 0 { a.extend(range) } else { b.extend(range) }
+            }
+            sort_dedup_vec(&mut a);
+            sort_dedup_vec(&mut b);
+
+            let (sa, sb) = (Set::new_unchecked(&a[..]), Set::new_unchecked(&b[..]));
+            let (ra, rb) = (RoaringSet::new(sa), RoaringSet::new(sb));
+            let builder = OpBuilder::new(sa, sb);
+
+            let intersection: SetBuf<u32> = builder.intersection().into_set_buf();
+
+            ra.union(&rb).to_set_buf() == builder.union().into_set_buf()
+                && ra.intersection(&rb).to_set_buf() == intersection
+                && ra.intersection(&rb).len() == intersection.len()
+                && ra.difference(&rb).to_set_buf() == builder.difference().into_set_buf()
+                && ra.symmetric_difference(&rb).to_set_buf() == builder.symmetric_difference().into_set_buf()
+        }
+
+        fn qc_advance_to(xs: Vec<u32>, targets: Vec<u32>) -> bool {
+            let mut xs: Vec<_> = xs.into_iter().map(|x| x % (1 << 19)).collect();
+            let mut targets: Vec<_> = targets.into_iter().map(|x| x % (1 << 19)).collect();
+            // spread dense ranges and isolated elements over multiple containers
+            xs.extend(xs.clone().into_iter().flat_map(|x| (0..50).map(move |i| x.wrapping_mul(7) + i)));
+            sort_dedup_vec(&mut xs);
+            sort_dedup_vec(&mut targets);
+
+            let set = RoaringSet::new(Set::new_unchecked(&xs));
+            let mut iter = set.iter();
+            let mut slice = xs.iter();
+
+            for target in &targets {
+                iter.advance_to(target);
+                slice.advance_to(target);
+                if iter.next() != slice.next().cloned() {
+                    return false;
+                }
+            }
+
+            iter.eq(slice.cloned())
+        }
+    }
+}
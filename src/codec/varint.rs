@@ -0,0 +1,160 @@
+use std::cmp;
+use crate::set::{Set, SetBuf};
+use super::{Integer, DecodeError, apply_delta};
+
+/// Encode the set using delta encoding and LEB128 varints, appending the bytes to `output`.
+///
+/// The number of elements is written first, followed by the first element and the deltas.
+pub fn encode_varint<T: Integer>(set: &Set<T>, output: &mut Vec<u8>) {
+    write_varint(set.len() as u64, output);
+
+    let (first, others) = match set.split_first() {
+        Some(split) => split,
+        None => return,
+    };
+
+    let mut previous = first.to_u64();
+    write_varint(previous, output);
+
+    for x in others {
+        let x = x.to_u64();
+        write_varint(x - previous, output);
+        previous = x;
+    }
+}
+
+/// Decode a set encoded with [`encode_varint`].
+///
+/// The bytes must contain exactly one encoded set.
+pub fn decode_varint<T: Integer>(mut bytes: &[u8]) -> Result<SetBuf<T>, DecodeError> {
+    let bytes = &mut bytes;
+    let len = read_varint(bytes)?;
+
+    // every element takes at least one byte, do not trust the length to allocate
+    let mut vec = Vec::with_capacity(cmp::min(len, bytes.len() as u64) as usize);
+
+    if len != 0 {
+        let mut previous = read_varint(bytes)?;
+        vec.push(T::from_u64(previous).ok_or(DecodeError::Malformed)?);
+
+        for _ in 1..len {
+            let delta = read_varint(bytes)?;
+            let (x, y) = apply_delta(previous, delta)?;
+            vec.push(y);
+            previous = x;
+        }
+    }
+
+    if !bytes.is_empty() {
+        return Err(DecodeError::Malformed);
+    }
+
+    Ok(SetBuf::new_unchecked(vec))
+}
+
+/// The maximum number of bytes of an encoded `u64`.
+pub(crate) const MAX_VARINT_LEN: usize = 10;
+
+pub(crate) fn write_varint(x: u64, output: &mut Vec<u8>) {
+    let mut bytes = [0; MAX_VARINT_LEN];
+    let len = write_varint_array(x, &mut bytes);
+    output.extend_from_slice(&bytes[..len]);
+}
+
+/// Write the varint at the start of the array and returns the number of bytes written.
+pub(crate) fn write_varint_array(mut x: u64, output: &mut [u8; MAX_VARINT_LEN]) -> usize {
+    let mut len = 0;
+    while x >= 0x80 {
+        output[len] = x as u8 | 0x80;
+        x >>= 7;
+        len += 1;
+    }
+    output[len] = x as u8;
+    len + 1
+}
+
+pub(super) fn read_varint(bytes: &mut &[u8]) -> Result<u64, DecodeError> {
+    let mut x = 0;
+    for shift in (0..64).step_by(7) {
+        let (&byte, rest) = bytes.split_first().ok_or(DecodeError::UnexpectedEnd)?;
+        *bytes = rest;
+
+        let low = u64::from(byte & 0x7f);
+        // the tenth byte can only hold the last bit of an u64
+        if shift == 63 && low > 1 {
+            return Err(DecodeError::Malformed);
+        }
+        // a final zero after a continuation byte is an overlong encoding
+        if byte == 0 && shift != 0 {
+            return Err(DecodeError::Malformed);
+        }
+
+        x |= low << shift;
+        if byte & 0x80 == 0 {
+            return Ok(x);
+        }
+    }
+    Err(DecodeError::Malformed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::set::{sort_dedup_vec, Error};
+
+    #[test]
+    fn varints() {
+        for &x in &[0, 1, 127, 128, 300, u64::from(u32::MAX), u64::MAX] {
+            let mut bytes = Vec::new();
+            write_varint(x, &mut bytes);
+            let mut slice = &bytes[..];
+            assert_eq!(read_varint(&mut slice), Ok(x));
+            assert!(slice.is_empty());
+        }
+
+        let mut bytes = [0; MAX_VARINT_LEN];
+        assert_eq!(write_varint_array(u64::MAX, &mut bytes), MAX_VARINT_LEN);
+        assert_eq!(write_varint_array(300, &mut bytes), 2);
+        assert_eq!(&bytes[..2], &[0xac, 0x02]);
+
+        let overlong = &[0xff; 11][..];
+        assert_eq!(read_varint(&mut &overlong[..]), Err(DecodeError::Malformed));
+    }
+
+    #[test]
+    fn corrupted() {
+        // 3 elements: 5, 5 + 0, ...
+        assert_eq!(decode_varint::<u32>(&[3, 5, 0, 1]), Err(DecodeError::Set(Error::NotDedup)));
+        // 2 elements: 250, 250 + 10 does not fit in an u8
+        assert_eq!(decode_varint::<u8>(&[2, 0xfa, 0x01, 10]), Err(DecodeError::Set(Error::NotSort)));
+        assert_eq!(decode_varint::<u32>(&[3, 5, 1]), Err(DecodeError::UnexpectedEnd));
+        assert_eq!(decode_varint::<u32>(&[1, 5, 1]), Err(DecodeError::Malformed));
+        assert_eq!(decode_varint::<u32>(&[]), Err(DecodeError::UnexpectedEnd));
+        // overlong encodings of the length and of an element
+        assert_eq!(decode_varint::<u32>(&[0x81, 0x00, 5]), Err(DecodeError::Malformed));
+        assert_eq!(decode_varint::<u32>(&[1, 0x85, 0x80, 0x00]), Err(DecodeError::Malformed));
+    }
+
+    quickcheck! {
+        fn qc_varint_u32(xs: Vec<u32>) -> bool {
+            let mut xs = xs;
+            sort_dedup_vec(&mut xs);
+
+            let mut bytes = Vec::new();
+            encode_varint(Set::new_unchecked(&xs), &mut bytes);
+
+            decode_varint::<u32>(&bytes).map(SetBuf::into_vec) == Ok(xs)
+        }
+
+        fn qc_varint_u64(xs: Vec<u64>) -> bool {
+            // spread the values over the whole range
+            let mut xs: Vec<_> = xs.into_iter().map(|x| x.wrapping_mul(0x9E37_79B9_7F4A_7C15)).collect();
+            sort_dedup_vec(&mut xs);
+
+            let mut bytes = Vec::new();
+            encode_varint(Set::new_unchecked(&xs), &mut bytes);
+
+            decode_varint::<u64>(&bytes).map(SetBuf::into_vec) == Ok(xs)
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use std::hash::Hash;
 use std::collections::{HashSet, BTreeSet};
-use std::marker;
+use std::{cmp, error, fmt, marker};
 use std::convert::Infallible;
 
 /// This trait is meant to abstract any kind of collection
@@ -10,6 +10,9 @@ use std::convert::Infallible;
 /// when inserting elements, the [`Counter`] struct is a good example
 /// of a custom implementation of the [`Collection`] trait, it is used to only
 /// count the number of elements of a set operation.
+///
+/// The errors returned by the collection stop the set operation, they are used by the
+/// [`WriteCollection`](crate::write::WriteCollection) to report the [`io::Error`](std::io::Error)s.
 pub trait Collection<T> {
 
     /// Error type associated with the [`Collection`].
@@ -134,7 +137,6 @@ impl<T: Ord> Collection<T> for BTreeSet<T> {
 /// # Ok(()) }
 /// # try_main().unwrap();
 /// ```
-
 pub struct Counter<T> {
     count: usize,
     _phantom: marker::PhantomData<T>,
@@ -184,3 +186,268 @@ impl<T> Collection<T> for Counter<T> {
         Ok(())
     }
 }
+
+/// A [`Collection`] that keeps the first `limit` elements given to it
+/// and stops the set operation once it has them.
+///
+/// The set operations being sorted, these are the `limit` smallest elements of the result.
+/// A [`LimitError::Reached`] error is returned to stop the set operation as soon as the
+/// limit is reached, it can be ignored using [`LimitError::into_result`].
+///
+/// Wrapped in an [`Offset`], it gives a page of the result without computing the following elements.
+///
+/// ```
+/// # use sdset::Error;
+/// # fn try_main() -> Result<(), Error> {
+/// use sdset::duo::OpBuilder;
+/// use sdset::{SetOperation, Set, Limit, LimitError, Offset};
+///
+/// let a = Set::new(&[1, 2, 4, 6, 7, 8, 9])?;
+/// let b = Set::new(&[2, 3, 4, 5, 6, 7, 9])?;
+///
+/// // skip 2 elements and take 3
+/// let mut page = Offset::new(2, Limit::new(3, Vec::new()));
+/// let op = OpBuilder::new(a, b).intersection();
+/// op.extend_collection(&mut page).or_else(LimitError::into_result).unwrap();
+///
+/// let res: Vec<&i32> = page.into_inner().into_inner();
+/// assert_eq!(res, &[&6, &7, &9]);
+/// # Ok(()) }
+/// # try_main().unwrap();
+/// ```
+pub struct Limit<C> {
+    remaining: usize,
+    inner: C,
+}
+
+impl<C> Limit<C> {
+    /// Create a new [`Limit`] keeping at most `limit` elements in the collection.
+    pub fn new(limit: usize, inner: C) -> Self {
+        Limit { remaining: limit, inner }
+    }
+
+    /// Returns the number of elements that can still be inserted.
+    pub fn remaining(&self) -> usize {
+        self.remaining
+    }
+
+    /// Returns a reference to the underlying collection.
+    pub fn get_ref(&self) -> &C {
+        &self.inner
+    }
+
+    /// Returns the underlying collection.
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+
+    fn check_remaining<E>(&self) -> Result<(), LimitError<E>> {
+        if self.remaining == 0 { Err(LimitError::Reached) } else { Ok(()) }
+    }
+}
+
+impl<T, C: Collection<T>> Collection<T> for Limit<C> {
+
+    type Error = LimitError<C::Error>;
+
+    fn push(&mut self, elem: T) -> Result<(), Self::Error> {
+        self.check_remaining()?;
+        self.inner.push(elem).map_err(LimitError::Collection)?;
+        self.remaining -= 1;
+        self.check_remaining()
+    }
+
+    fn extend_from_slice(&mut self, elems: &[T]) -> Result<(), Self::Error>
+    where T: Clone
+    {
+        if elems.is_empty() {
+            return Ok(());
+        }
+        self.check_remaining()?;
+
+        let len = cmp::min(elems.len(), self.remaining);
+        self.inner.extend_from_slice(&elems[..len]).map_err(LimitError::Collection)?;
+        self.remaining -= len;
+        self.check_remaining()
+    }
+
+    fn extend<I>(&mut self, elems: I) -> Result<(), Self::Error>
+    where I: IntoIterator<Item=T>
+    {
+        // the elements are pulled one by one to not compute the ones after the limit
+        elems.into_iter().try_for_each(|elem| self.push(elem))
+    }
+
+    fn reserve(&mut self, size: usize) -> Result<(), Self::Error> {
+        self.check_remaining()?;
+        self.inner.reserve(cmp::min(size, self.remaining)).map_err(LimitError::Collection)
+    }
+}
+
+/// Represent the errors returned by a [`Limit`] collection.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum LimitError<E> {
+    /// Define that the limit has been reached and that the set operation must stop.
+    Reached,
+    /// Define that the underlying collection returned an error.
+    Collection(E),
+}
+
+impl<E> LimitError<E> {
+    /// Convert the [`Reached`](LimitError::Reached) error into a success, to be used with
+    /// [`Result::or_else`] on the result of [`SetOperation::extend_collection`](crate::SetOperation::extend_collection).
+    pub fn into_result(self) -> Result<(), E> {
+        match self {
+            LimitError::Reached => Ok(()),
+            LimitError::Collection(e) => Err(e),
+        }
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for LimitError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            LimitError::Reached => f.write_str("the limit of the collection has been reached."),
+            LimitError::Collection(e) => fmt::Display::fmt(e, f),
+        }
+    }
+}
+
+impl<E: error::Error + 'static> error::Error for LimitError<E> {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            LimitError::Reached => None,
+            LimitError::Collection(e) => Some(e),
+        }
+    }
+}
+
+/// A [`Collection`] that skips the first `offset` elements given to it.
+///
+/// The errors of the underlying collection are returned as is,
+/// a [`Limit`] can be wrapped to stop the set operation.
+///
+/// ```
+/// # use sdset::Error;
+/// # fn try_main() -> Result<(), Error> {
+/// use sdset::multi::OpBuilder;
+/// use sdset::{SetOperation, Set, Offset};
+///
+/// let a = Set::new(&[1, 2, 4])?;
+/// let b = Set::new(&[2, 3, 5, 7])?;
+///
+/// let mut output = Offset::new(4, Vec::new());
+/// SetOperation::<i32>::extend_collection(OpBuilder::from_vec(vec![a, b]).union(), &mut output).unwrap();
+///
+/// assert_eq!(output.into_inner(), &[5, 7]);
+/// # Ok(()) }
+/// # try_main().unwrap();
+/// ```
+pub struct Offset<C> {
+    skip: usize,
+    inner: C,
+}
+
+impl<C> Offset<C> {
+    /// Create a new [`Offset`] skipping the first `offset` elements.
+    pub fn new(offset: usize, inner: C) -> Self {
+        Offset { skip: offset, inner }
+    }
+
+    /// Returns a reference to the underlying collection.
+    pub fn get_ref(&self) -> &C {
+        &self.inner
+    }
+
+    /// Returns the underlying collection.
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+}
+
+impl<T, C: Collection<T>> Collection<T> for Offset<C> {
+
+    type Error = C::Error;
+
+    fn push(&mut self, elem: T) -> Result<(), Self::Error> {
+        if self.skip == 0 {
+            self.inner.push(elem)
+        } else {
+            self.skip -= 1;
+            Ok(())
+        }
+    }
+
+    fn extend_from_slice(&mut self, elems: &[T]) -> Result<(), Self::Error>
+    where T: Clone
+    {
+        let skipped = cmp::min(elems.len(), self.skip);
+        self.skip -= skipped;
+
+        match &elems[skipped..] {
+            [] => Ok(()),
+            elems => self.inner.extend_from_slice(elems),
+        }
+    }
+
+    fn extend<I>(&mut self, elems: I) -> Result<(), Self::Error>
+    where I: IntoIterator<Item=T>
+    {
+        let mut elems = elems.into_iter();
+        while self.skip != 0 {
+            if elems.next().is_none() {
+                return Ok(());
+            }
+            self.skip -= 1;
+        }
+        self.inner.extend(elems)
+    }
+
+    fn reserve(&mut self, size: usize) -> Result<(), Self::Error> {
+        self.inner.reserve(size.saturating_sub(self.skip))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::set::{Set, SetBuf, sort_dedup_vec};
+    use crate::{duo, multi, SetOperation};
+
+    #[test]
+    fn limit_stops_operation() {
+        let a: Vec<i32> = (0..1000).collect();
+        let b: Vec<i32> = (500..1500).collect();
+        let op = duo::OpBuilder::new(Set::new_unchecked(&a), Set::new_unchecked(&b)).union();
+
+        let mut output = Limit::new(10, Counter::new());
+        let result = SetOperation::<i32>::extend_collection(op, &mut output);
+
+        assert_eq!(result, Err(LimitError::Reached));
+        assert_eq!(output.get_ref().get(), 10);
+
+        let mut output = Limit::new(0, Vec::new());
+        assert_eq!(SetOperation::<i32>::extend_collection(op, &mut output), Err(LimitError::Reached));
+        assert!(output.into_inner().is_empty());
+    }
+
+    quickcheck! {
+        fn qc_page(xss: Vec<Vec<i32>>, offset: usize, limit: usize) -> bool {
+            let (offset, limit) = (offset % 50, limit % 50);
+            let mut xss = xss;
+            xss.iter_mut().for_each(sort_dedup_vec);
+            let sets: Vec<_> = xss.iter().map(|xs| Set::new_unchecked(&xs[..])).collect();
+
+            let page = |op: multi::Union<i32>| {
+                let mut output = Offset::new(offset, Limit::new(limit, Vec::new()));
+                SetOperation::<i32>::extend_collection(op, &mut output).or_else(LimitError::into_result).unwrap();
+                output.into_inner().into_inner()
+            };
+
+            let union: SetBuf<i32> = multi::OpBuilder::from_vec(sets.clone()).union().into_set_buf();
+            let expected: Vec<_> = union.into_iter().skip(offset).take(limit).collect();
+
+            page(multi::OpBuilder::from_vec(sets).union()) == expected
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use std::borrow::Borrow;
+use crate::SeekableIterator;
+
+/// An iterator with its next element pulled ahead, to be compared
+/// with the next element of another input.
+///
+/// The first element is only pulled when it is needed, constructing an operation
+/// over an iterator that blocks, e.g. reading a channel or a socket, does not block.
+/// When the debug assertions are enabled it panics if the elements are not sorted and deduplicated.
+pub(crate) struct Cursor<I: Iterator> {
+    iter: I,
+    head: Option<I::Item>,
+    started: bool,
+}
+
+impl<I: Iterator> Cursor<I> {
+    pub(crate) fn new(iter: I) -> Self {
+        Cursor { iter, head: None, started: false }
+    }
+}
+
+impl<I: Iterator> Cursor<I> where I::Item: Ord {
+    fn start(&mut self) {
+        if !self.started {
+            self.started = true;
+            self.head = self.iter.next();
+        }
+    }
+
+    /// Returns the next element without consuming it.
+    pub(crate) fn peek(&mut self) -> Option<&I::Item> {
+        self.start();
+        self.head.as_ref()
+    }
+
+    /// Returns the head and pull the next element.
+    pub(crate) fn bump(&mut self) -> Option<I::Item> {
+        self.start();
+        let head = self.head.take()?;
+        self.head = self.iter.next();
+        if let Some(next) = &self.head {
+            debug_assert!(&head < next, "an input iterator is not sorted and deduplicated");
+        }
+        Some(head)
+    }
+
+    /// Skip the elements less than `target`.
+    pub(crate) fn advance_to<T: Ord>(&mut self, target: &T)
+    where I: SeekableIterator<T>,
+          I::Item: Borrow<T>,
+    {
+        if !self.started {
+            // the head will be pulled after the skipped elements
+            self.iter.advance_to(target);
+        } else if self.head.as_ref().map_or(false, |head| head.borrow() < target) {
+            self.iter.advance_to(target);
+            self.head = self.iter.next();
+        }
+    }
+}
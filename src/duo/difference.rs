@@ -1,5 +1,6 @@
 use crate::set::Set;
-use crate::{exponential_offset_ge, SetOperation, Collection};
+use crate::{exponential_offset_ge, exponential_offset_le, SetOperation, SeekableIterator, Collection, Cardinality};
+use super::{Intersection, Strategy};
 
 /// Represent the _difference_ set operation that will be applied to two slices.
 ///
@@ -24,6 +25,7 @@ use crate::{exponential_offset_ge, SetOperation, Collection};
 pub struct Difference<'a, T: 'a> {
     a: &'a [T],
     b: &'a [T],
+    strategy: Strategy,
 }
 
 impl<'a, T> Difference<'a, T> {
@@ -32,13 +34,60 @@ impl<'a, T> Difference<'a, T> {
         Self {
             a: a.as_slice(),
             b: b.as_slice(),
+            strategy: Strategy::Auto,
         }
     }
+
+    /// Force the algorithm used to find the common elements.
+    pub fn strategy(mut self, strategy: Strategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
 }
 
 impl<'a, T: Ord> Difference<'a, T> {
     #[inline]
-    fn extend_collection<C, U, F>(mut self, output: &mut C, extend: F) -> Result<(), C::Error>
+    fn extend_collection<C, U, F>(self, output: &mut C, extend: F) -> Result<(), C::Error>
+    where C: Collection<U>,
+          F: Fn(&mut C, &'a [T]) -> Result<(), C::Error>,
+    {
+        match self.strategy.select(self.a.len(), self.b.len()) {
+            Strategy::Linear => self.linear(output, extend),
+            Strategy::BinarySearch => self.binary_search(output, extend),
+            Strategy::Galloping | Strategy::Auto => self.galloping(output, extend),
+        }
+    }
+
+    fn linear<C, U, F>(self, output: &mut C, extend: F) -> Result<(), C::Error>
+    where C: Collection<U>,
+          F: Fn(&mut C, &'a [T]) -> Result<(), C::Error>,
+    {
+        // the elements of the first slice less than the second slice are emitted at once
+        let mut start = 0;
+        let mut i = match self.b.first() {
+            Some(first) => self.a.len() - exponential_offset_ge(self.a, first).len(),
+            None => self.a.len(),
+        };
+        let mut j = 0;
+
+        while i < self.a.len() && j < self.b.len() {
+            let (x, y) = (&self.a[i], &self.b[j]);
+            if x == y {
+                extend(output, &self.a[start..i])?;
+                let off = self.a[i..].iter().zip(&self.b[j..]).take_while(|(a, b)| a == b).count();
+                i += off;
+                j += off;
+                start = i;
+            } else {
+                i += (x < y) as usize;
+                j += (y < x) as usize;
+            }
+        }
+
+        extend(output, &self.a[start..])
+    }
+
+    fn galloping<C, U, F>(mut self, output: &mut C, extend: F) -> Result<(), C::Error>
     where C: Collection<U>,
           F: Fn(&mut C, &'a [T]) -> Result<(), C::Error>,
     {
@@ -66,6 +115,42 @@ impl<'a, T: Ord> Difference<'a, T> {
         Ok(())
     }
 
+    fn binary_search<C, U, F>(mut self, output: &mut C, extend: F) -> Result<(), C::Error>
+    where C: Collection<U>,
+          F: Fn(&mut C, &'a [T]) -> Result<(), C::Error>,
+    {
+        if self.a.len() <= self.b.len() {
+            let mut start = 0;
+            for (i, x) in self.a.iter().enumerate() {
+                match self.b.binary_search(x) {
+                    Ok(pos) => {
+                        extend(output, &self.a[start..i])?;
+                        start = i + 1;
+                        self.b = &self.b[pos + 1..];
+                    },
+                    Err(pos) => self.b = &self.b[pos..],
+                }
+                if self.b.is_empty() { break }
+            }
+            extend(output, &self.a[start..])
+        } else {
+            for y in self.b {
+                match self.a.binary_search(y) {
+                    Ok(pos) => {
+                        extend(output, &self.a[..pos])?;
+                        self.a = &self.a[pos + 1..];
+                    },
+                    Err(pos) => {
+                        extend(output, &self.a[..pos])?;
+                        self.a = &self.a[pos..];
+                    },
+                }
+                if self.a.is_empty() { break }
+            }
+            extend(output, self.a)
+        }
+    }
+
     fn iter(&self) -> DifferenceIter<'a, T>
     {
         DifferenceIter {
@@ -91,6 +176,15 @@ impl<'a, T: Ord> SetOperation<&'a T> for Difference<'a, T> {
     }
 }
 
+impl<'a, T: Ord> Cardinality for Difference<'a, T> {
+    fn count(self) -> usize {
+        let a = Set::new_unchecked(self.a);
+        let b = Set::new_unchecked(self.b);
+        let intersection = Intersection::new(a, b).count();
+        self.a.len() - intersection
+    }
+}
+
 impl<'a, T: Ord> IntoIterator for Difference<'a, T> {
     type Item = &'a T;
     type IntoIter = DifferenceIter<'a, T>;
@@ -138,7 +232,29 @@ impl<'a, T: Ord> Iterator for DifferenceIter<'a, T> {
     }
 }
 
+impl<'a, T: Ord> DoubleEndedIterator for DifferenceIter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        loop {
+            let (last_a, a) = self.a.split_last()?;
+            self.a = a;
+            self.b = exponential_offset_le(self.b, last_a);
+            match self.b.split_last() {
+                Some((last_b, b)) if last_b == last_a => self.b = b,
+                _ => return Some(last_a),
+            }
+        }
+    }
+}
+
+impl<'a, T: Ord> SeekableIterator<T> for DifferenceIter<'a, T> {
+    fn advance_to(&mut self, target: &T) {
+        // the other slice is skipped when searching for the next element
+        self.a = exponential_offset_ge(self.a, target);
+    }
+}
+
 #[cfg(test)]
+#[allow(clippy::redundant_field_names)]
 mod tests {
     mod set_to_set {
         use super::super::*;
@@ -148,7 +264,7 @@ mod tests {
             let a = &[1, 2, 3];
             let b = &[2, 4];
 
-            let diff: SetBuf<i32> = Difference { a: a, b: b }.into_set_buf();
+            let diff: SetBuf<i32> = Difference { a: a, b: b, strategy: Strategy::Auto }.into_set_buf();
             assert_eq!(&diff[..], &[1, 3]);
         }
 
@@ -157,11 +273,49 @@ mod tests {
             let a = &[1, 2, 3];
             let b = &[3];
 
-            let diff: SetBuf<i32> = Difference { a: a, b: b }.into_set_buf();
+            let diff: SetBuf<i32> = Difference { a: a, b: b, strategy: Strategy::Auto }.into_set_buf();
             assert_eq!(&diff[..], &[1, 2]);
         }
 
         quickcheck! {
+            fn qc_strategies(a: Vec<i32>, b: Vec<i32>) -> bool {
+                use std::collections::BTreeSet;
+                use std::iter::FromIterator;
+
+                let mut a = a;
+                let mut b = b;
+
+                sort_dedup_vec(&mut a);
+                sort_dedup_vec(&mut b);
+
+                let y: Vec<_> = {
+                    let a = BTreeSet::from_iter(a.iter().cloned());
+                    let b = BTreeSet::from_iter(b.iter().cloned());
+                    a.difference(&b).cloned().collect()
+                };
+
+                let strategies = [Strategy::Linear, Strategy::Galloping, Strategy::BinarySearch];
+                strategies.iter().all(|&strategy| {
+                    let x: SetBuf<i32> = Difference { a: &a, b: &b, strategy }.into_set_buf();
+                    x.as_slice() == y.as_slice()
+                })
+            }
+
+            fn qc_count(a: Vec<i32>, b: Vec<i32>) -> bool {
+                use crate::{Cardinality, Counter};
+
+                let mut a = a;
+                let mut b = b;
+
+                sort_dedup_vec(&mut a);
+                sort_dedup_vec(&mut b);
+
+                let mut counter = Counter::<i32>::new();
+                SetOperation::extend_collection(Difference { a: &a, b: &b, strategy: Strategy::Auto }, &mut counter).unwrap();
+
+                Difference { a: &a, b: &b, strategy: Strategy::Auto }.count() == counter.get()
+            }
+
             fn qc_difference(a: Vec<i32>, b: Vec<i32>) -> bool {
                 use std::collections::BTreeSet;
                 use std::iter::FromIterator;
@@ -172,7 +326,7 @@ mod tests {
                 sort_dedup_vec(&mut a);
                 sort_dedup_vec(&mut b);
 
-                let x: SetBuf<i32> = Difference { a: &a, b: &b }.into_set_buf();
+                let x: SetBuf<i32> = Difference { a: &a, b: &b, strategy: Strategy::Auto }.into_set_buf();
 
                 let a = BTreeSet::from_iter(a);
                 let b = BTreeSet::from_iter(b);
@@ -192,7 +346,7 @@ mod tests {
             let a = &[1, 2, 3];
             let b = &[2, 4];
 
-            let diff: Vec<i32> = Difference { a: a, b: b }.into_iter().cloned().collect();
+            let diff: Vec<i32> = Difference { a: a, b: b, strategy: Strategy::Auto }.into_iter().cloned().collect();
             assert_eq!(&diff[..], &[1, 3]);
         }
 
@@ -201,7 +355,7 @@ mod tests {
             let a = &[1, 2, 3];
             let b = &[3];
 
-            let diff: Vec<i32> = Difference { a: a, b: b }.into_iter().cloned().collect();
+            let diff: Vec<i32> = Difference { a: a, b: b, strategy: Strategy::Auto }.into_iter().cloned().collect();
             assert_eq!(&diff[..], &[1, 2]);
         }
 
@@ -216,7 +370,7 @@ mod tests {
                 sort_dedup_vec(&mut a);
                 sort_dedup_vec(&mut b);
 
-                let x: Vec<i32> = Difference { a: &a, b: &b }.into_iter().cloned().collect();
+                let x: Vec<i32> = Difference { a: &a, b: &b, strategy: Strategy::Auto }.into_iter().cloned().collect();
 
                 let a = BTreeSet::from_iter(a);
                 let b = BTreeSet::from_iter(b);
@@ -225,6 +379,49 @@ mod tests {
 
                 x.as_slice() == y.as_slice()
             }
+
+            fn qc_rev_difference(a: Vec<i32>, b: Vec<i32>) -> bool {
+                use std::collections::BTreeSet;
+                use std::iter::FromIterator;
+
+                let mut a = a;
+                let mut b = b;
+
+                sort_dedup_vec(&mut a);
+                sort_dedup_vec(&mut b);
+
+                let x: Vec<i32> = Difference { a: &a, b: &b, strategy: Strategy::Auto }.into_iter().rev().cloned().collect();
+
+                let a = BTreeSet::from_iter(a);
+                let b = BTreeSet::from_iter(b);
+                let y = a.difference(&b);
+                let y: Vec<_> = y.cloned().collect();
+
+                x.iter().eq(y.iter().rev())
+            }
+
+            fn qc_advance_to(a: Vec<i32>, b: Vec<i32>, target: i32) -> bool {
+                use std::collections::BTreeSet;
+                use std::iter::FromIterator;
+                use crate::SeekableIterator;
+
+                let mut a = a;
+                let mut b = b;
+
+                sort_dedup_vec(&mut a);
+                sort_dedup_vec(&mut b);
+
+                let mut iter = Difference { a: &a, b: &b, strategy: Strategy::Auto }.into_iter();
+                iter.advance_to(&target);
+                let x: Vec<i32> = iter.cloned().collect();
+
+                let a = BTreeSet::from_iter(a);
+                let b = BTreeSet::from_iter(b);
+                let y = a.difference(&b);
+                let y: Vec<_> = y.cloned().filter(|&y| y >= target).collect();
+
+                x.as_slice() == y.as_slice()
+            }
         }
     }
 }
@@ -242,7 +439,7 @@ mod bench {
         let b: Vec<_> = (1..101).collect();
 
         bench.iter(|| {
-            let difference_: SetBuf<i32> = Difference { a: &a, b: &b }.into_set_buf();
+            let difference_: SetBuf<i32> = Difference { a: &a, b: &b, strategy: Strategy::Auto }.into_set_buf();
             test::black_box(|| difference_);
         });
     }
@@ -253,7 +450,7 @@ mod bench {
         let b: Vec<_> = (51..151).collect();
 
         bench.iter(|| {
-            let difference_: SetBuf<i32> = Difference { a: &a, b: &b }.into_set_buf();
+            let difference_: SetBuf<i32> = Difference { a: &a, b: &b, strategy: Strategy::Auto }.into_set_buf();
             test::black_box(|| difference_);
         });
     }
@@ -264,8 +461,50 @@ mod bench {
         let b: Vec<_> = (100..200).collect();
 
         bench.iter(|| {
-            let difference_: SetBuf<i32> = Difference { a: &a, b: &b }.into_set_buf();
+            let difference_: SetBuf<i32> = Difference { a: &a, b: &b, strategy: Strategy::Auto }.into_set_buf();
             test::black_box(|| difference_);
         });
     }
+
+    fn strategy_bench(bench: &mut Bencher, a: &[i32], b: &[i32], strategy: Strategy) {
+        bench.iter(|| {
+            let difference_: SetBuf<i32> = Difference { a, b, strategy }.into_set_buf();
+            test::black_box(|| difference_);
+        });
+    }
+
+    fn random_set(seed: u64, len: usize, max: u64) -> Vec<i32> {
+        let mut x = seed;
+        let mut v: Vec<i32> = (0..len).map(|_| {
+            x = x.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            ((x >> 33) % max) as i32
+        }).collect();
+        v.sort_unstable();
+        v.dedup();
+        v
+    }
+
+    #[bench]
+    fn random_linear(bench: &mut Bencher) {
+        let (a, b) = (random_set(1, 10_000, 20_000), random_set(2, 10_000, 20_000));
+        strategy_bench(bench, &a, &b, Strategy::Linear);
+    }
+
+    #[bench]
+    fn random_galloping(bench: &mut Bencher) {
+        let (a, b) = (random_set(1, 10_000, 20_000), random_set(2, 10_000, 20_000));
+        strategy_bench(bench, &a, &b, Strategy::Galloping);
+    }
+
+    #[bench]
+    fn random_skewed_galloping(bench: &mut Bencher) {
+        let (a, b) = (random_set(1, 1_000, 2_000_000), random_set(2, 1_000_000, 2_000_000));
+        strategy_bench(bench, &a, &b, Strategy::Galloping);
+    }
+
+    #[bench]
+    fn random_skewed_binary_search(bench: &mut Bencher) {
+        let (a, b) = (random_set(1, 1_000, 2_000_000), random_set(2, 1_000_000, 2_000_000));
+        strategy_bench(bench, &a, &b, Strategy::BinarySearch);
+    }
 }
@@ -1,5 +1,5 @@
 use crate::set::Set;
-use crate::{exponential_offset_ge_by_key, SetOperation, Collection};
+use crate::{exponential_offset_ge_by_key, exponential_offset_le_by_key, SetOperation, SeekableIterator, Collection};
 
 /// Represent the _difference_ set operation that will be applied to two slices of different types.
 ///
@@ -64,8 +64,8 @@ where
         Self {
             a: a.as_slice(),
             b: b.as_slice(),
-            f: f,
-            g: g,
+            f,
+            g,
         }
     }
 }
@@ -203,6 +203,33 @@ where
     }
 }
 
+impl<'a, T, U, F, G, K> DoubleEndedIterator for DifferenceByKeyIter<'a, T, U, F, G, K>
+where
+    T: 'a,
+    U: 'a,
+    F: Fn(&T) -> K,
+    G: Fn(&U) -> K,
+    K: Ord,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        next_back_by_key(&mut self.a, &mut self.b, self.f, self.g)
+    }
+}
+
+impl<'a, T, U, F, G, K> SeekableIterator<K> for DifferenceByKeyIter<'a, T, U, F, G, K>
+where
+    T: 'a,
+    U: 'a,
+    F: Fn(&T) -> K,
+    G: Fn(&U) -> K,
+    K: Ord,
+{
+    fn advance_to(&mut self, target: &K) {
+        self.a = exponential_offset_ge_by_key(self.a, target, self.f);
+        self.b = exponential_offset_ge_by_key(self.b, target, self.g);
+    }
+}
+
 // This version of IntoIterator moves the contents of self into the iterator.
 // Therefore the iterator owns the functions (f/g).
 // The separate structs are required to not break the public API which takes the functions
@@ -273,6 +300,51 @@ where
     }
 }
 
+impl<'a, T, U, F, G, K> DoubleEndedIterator for DifferenceByKeyIterOwning<'a, T, U, F, G, K>
+where
+    T: 'a,
+    U: 'a,
+    F: Fn(&T) -> K,
+    G: Fn(&U) -> K,
+    K: Ord,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        next_back_by_key(&mut self.a, &mut self.b, &self.f, &self.g)
+    }
+}
+
+impl<'a, T, U, F, G, K> SeekableIterator<K> for DifferenceByKeyIterOwning<'a, T, U, F, G, K>
+where
+    T: 'a,
+    U: 'a,
+    F: Fn(&T) -> K,
+    G: Fn(&U) -> K,
+    K: Ord,
+{
+    fn advance_to(&mut self, target: &K) {
+        self.a = exponential_offset_ge_by_key(self.a, target, &self.f);
+        self.b = exponential_offset_ge_by_key(self.b, target, &self.g);
+    }
+}
+
+#[inline]
+fn next_back_by_key<'a, T, U, F, G, K>(a: &mut &'a [T], b: &mut &'a [U], f: &F, g: &G) -> Option<&'a T>
+where F: Fn(&T) -> K,
+      G: Fn(&U) -> K,
+      K: Ord,
+{
+    loop {
+        let last_a = a.last()?;
+        let key = f(last_a);
+        *b = exponential_offset_le_by_key(b, &key, g);
+        *a = &a[..a.len() - 1];
+        // cannot shrink b since we support duplicate relations
+        if b.last().map(g) != Some(key) {
+            return Some(last_a);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     mod set_to_set {
@@ -438,6 +510,43 @@ mod tests {
         }
 
         quickcheck! {
+            fn qc_advance_to(a: Vec<(i8, i8)>, b: Vec<i8>, target: i8) -> bool {
+                let mut a = a;
+                let mut b = b;
+
+                sort_dedup_vec(&mut a);
+                sort_dedup_vec(&mut b);
+
+                let op = DifferenceByKey { a: &a, b: &b, f: |x: &(i8, i8)| x.0, g: |&x: &i8| x };
+                let expected: Vec<_> = op.iter().filter(|x| x.0 >= target).collect();
+
+                let mut iter = op.iter();
+                iter.advance_to(&target);
+                let x: Vec<_> = iter.collect();
+
+                let mut iter = op.into_iter();
+                iter.advance_to(&target);
+                let y: Vec<_> = iter.collect();
+
+                x == expected && y == expected
+            }
+
+            fn qc_next_and_next_back(a: Vec<(i8, i8)>, b: Vec<i8>, sides: Vec<bool>) -> bool {
+                use crate::collect_from_both_ends;
+
+                let mut a = a;
+                let mut b = b;
+
+                sort_dedup_vec(&mut a);
+                sort_dedup_vec(&mut b);
+
+                let op = DifferenceByKey { a: &a, b: &b, f: |x: &(i8, i8)| x.0, g: |&x: &i8| x };
+                let expected: Vec<_> = op.iter().collect();
+
+                collect_from_both_ends(op.iter(), &sides) == expected
+                    && collect_from_both_ends(op.into_iter(), &sides) == expected
+            }
+
             fn qc_difference(a: Vec<i32>, b: Vec<i64>) -> bool {
                 use std::collections::BTreeSet;
                 use std::iter::FromIterator;
@@ -0,0 +1,167 @@
+use crate::codec::CompressedSet;
+use crate::cursor::Cursor;
+use crate::{SetOperation, SeekableIterator, Collection};
+
+/// Represent the _difference_ set operation that will be applied to two compressed sets.
+///
+/// The second set is galloped through, only the parts that may contain
+/// elements of the first one are decoded.
+///
+/// # Examples
+/// ```
+/// # use sdset::Error;
+/// # fn try_main() -> Result<(), Error> {
+/// use sdset::codec::BlockSet;
+/// use sdset::duo::OpBuilderCompressed;
+/// use sdset::{SetOperation, Set, SetBuf};
+///
+/// let a = BlockSet::new(Set::new(&[1u32, 2, 4, 6, 7])?);
+/// let b = BlockSet::new(Set::new(&[2u32, 3, 4, 5, 6, 7])?);
+///
+/// let op = OpBuilderCompressed::new(&a, &b).difference();
+///
+/// let res: SetBuf<u32> = op.into_set_buf();
+/// assert_eq!(&res[..], &[1]);
+/// # Ok(()) }
+/// # try_main().unwrap();
+/// ```
+#[derive(Copy, Clone)]
+pub struct DifferenceCompressed<'a, A: 'a, B: 'a> {
+    a: &'a A,
+    b: &'a B,
+}
+
+impl<'a, A, B> DifferenceCompressed<'a, A, B> {
+    /// Construct one with two compressed sets.
+    pub fn new(a: &'a A, b: &'a B) -> Self {
+        Self { a, b }
+    }
+}
+
+impl<'a, A, B> DifferenceCompressed<'a, A, B>
+where A: CompressedSet,
+      B: CompressedSet<Item = A::Item>,
+{
+    fn iter(&self) -> DifferenceCompressedIter<'a, A, B> {
+        DifferenceCompressedIter {
+            a: Cursor::new(self.a.iter()),
+            b: Cursor::new(self.b.iter()),
+        }
+    }
+}
+
+impl<'a, A, B> SetOperation<A::Item> for DifferenceCompressed<'a, A, B>
+where A: CompressedSet,
+      B: CompressedSet<Item = A::Item>,
+{
+    fn extend_collection<C>(self, output: &mut C) -> Result<(), C::Error>
+    where C: Collection<A::Item>,
+    {
+        output.extend(self)
+    }
+}
+
+impl<'a, A, B> IntoIterator for DifferenceCompressed<'a, A, B>
+where A: CompressedSet,
+      B: CompressedSet<Item = A::Item>,
+{
+    type Item = A::Item;
+    type IntoIter = DifferenceCompressedIter<'a, A, B>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, A, B> IntoIterator for &'a DifferenceCompressed<'a, A, B>
+where A: CompressedSet,
+      B: CompressedSet<Item = A::Item>,
+{
+    type Item = A::Item;
+    type IntoIter = DifferenceCompressedIter<'a, A, B>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct DifferenceCompressedIter<'a, A: CompressedSet + 'a, B: CompressedSet + 'a> {
+    a: Cursor<A::Iter<'a>>,
+    b: Cursor<B::Iter<'a>>,
+}
+
+impl<'a, A, B> Iterator for DifferenceCompressedIter<'a, A, B>
+where A: CompressedSet,
+      B: CompressedSet<Item = A::Item>,
+{
+    type Item = A::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let a = self.a.bump()?;
+            self.b.advance_to(&a);
+            if self.b.peek() != Some(&a) {
+                return Some(a);
+            }
+        }
+    }
+}
+
+impl<'a, A, B> SeekableIterator<A::Item> for DifferenceCompressedIter<'a, A, B>
+where A: CompressedSet,
+      B: CompressedSet<Item = A::Item>,
+{
+    fn advance_to(&mut self, target: &A::Item) {
+        // the other set is skipped when searching for the next element
+        self.a.advance_to(target);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec::{BlockSet, EliasFanoSet};
+    use crate::set::{sort_dedup_vec, Set};
+
+    quickcheck! {
+        fn qc_difference(a: Vec<u32>, b: Vec<u32>) -> bool {
+            use std::collections::BTreeSet;
+            use std::iter::FromIterator;
+
+            let mut a = a;
+            let mut b = b;
+
+            sort_dedup_vec(&mut a);
+            sort_dedup_vec(&mut b);
+
+            let x: Vec<u32> = {
+                let a = BlockSet::new(Set::new_unchecked(&a));
+                let b = BlockSet::new(Set::new_unchecked(&b));
+                DifferenceCompressed::new(&a, &b).into_set_buf().into_vec()
+            };
+
+            let a = BTreeSet::from_iter(a);
+            let b = BTreeSet::from_iter(b);
+            let y: Vec<_> = a.difference(&b).cloned().collect();
+
+            x.as_slice() == y.as_slice()
+        }
+
+        fn qc_difference_blocks(a: Vec<u16>, b: Vec<u16>) -> bool {
+            // dense sets spanning multiple blocks
+            let mut a: Vec<u64> = a.into_iter().map(|x| u64::from(x) * 7).collect();
+            let mut b: Vec<u64> = b.into_iter().flat_map(|x| (0..20).map(move |i| u64::from(x) * 20 + i)).collect();
+
+            sort_dedup_vec(&mut a);
+            sort_dedup_vec(&mut b);
+
+            let x: Vec<u64> = {
+                let a = EliasFanoSet::new(Set::new_unchecked(&a));
+                let b = BlockSet::new(Set::new_unchecked(&b));
+                DifferenceCompressed::new(&a, &b).into_iter().collect()
+            };
+
+            let y = crate::duo::Difference::new(Set::new_unchecked(&a), Set::new_unchecked(&b)).into_set_buf();
+
+            x.as_slice() == y.as_slice()
+        }
+    }
+}
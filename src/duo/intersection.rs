@@ -1,5 +1,7 @@
+use std::cmp::Ordering;
 use crate::set::Set;
-use crate::{exponential_offset_ge, SetOperation, Collection};
+use crate::{exponential_offset_ge, exponential_offset_le, SetOperation, SeekableIterator, Collection, Cardinality, Counter};
+use super::Strategy;
 
 /// Represent the _intersection_ set operation that will be applied to two slices.
 ///
@@ -24,6 +26,7 @@ use crate::{exponential_offset_ge, SetOperation, Collection};
 pub struct Intersection<'a, T: 'a> {
     a: &'a [T],
     b: &'a [T],
+    strategy: Strategy,
 }
 
 impl<'a, T> Intersection<'a, T> {
@@ -32,13 +35,58 @@ impl<'a, T> Intersection<'a, T> {
         Self {
             a: a.as_slice(),
             b: b.as_slice(),
+            strategy: Strategy::Auto,
         }
     }
+
+    /// Force the algorithm used to find the common elements.
+    pub fn strategy(mut self, strategy: Strategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
 }
 
 impl<'a, T: Ord> Intersection<'a, T> {
     #[inline]
-    fn extend_collection<C, U, F>(mut self, output: &mut C, extend: F) -> Result<(), C::Error>
+    pub(super) fn extend_collection<C, U, F>(self, output: &mut C, extend: F) -> Result<(), C::Error>
+    where C: Collection<U>,
+          F: Fn(&mut C, &'a [T]) -> Result<(), C::Error>,
+    {
+        match self.strategy.select(self.a.len(), self.b.len()) {
+            Strategy::Linear => self.linear(output, extend),
+            Strategy::BinarySearch => self.binary_search(output, extend),
+            Strategy::Galloping | Strategy::Auto => self.galloping(output, extend),
+        }
+    }
+
+    fn linear<C, U, F>(mut self, output: &mut C, extend: F) -> Result<(), C::Error>
+    where C: Collection<U>,
+          F: Fn(&mut C, &'a [T]) -> Result<(), C::Error>,
+    {
+        // only merge the parts of the slices that overlap
+        if let (Some(first_a), Some(first_b)) = (self.a.first(), self.b.first()) {
+            self.a = exponential_offset_ge(self.a, first_b);
+            self.b = exponential_offset_ge(self.b, first_a);
+        }
+
+        let (mut i, mut j) = (0, 0);
+
+        while i < self.a.len() && j < self.b.len() {
+            let (x, y) = (&self.a[i], &self.b[j]);
+            if x == y {
+                let off = self.a[i..].iter().zip(&self.b[j..]).take_while(|(a, b)| a == b).count();
+                extend(output, &self.a[i..i + off])?;
+                i += off;
+                j += off;
+            } else {
+                i += (x < y) as usize;
+                j += (y < x) as usize;
+            }
+        }
+        Ok(())
+    }
+
+    fn galloping<C, U, F>(mut self, output: &mut C, extend: F) -> Result<(), C::Error>
     where C: Collection<U>,
           F: Fn(&mut C, &'a [T]) -> Result<(), C::Error>,
     {
@@ -63,7 +111,38 @@ impl<'a, T: Ord> Intersection<'a, T> {
         Ok(())
     }
 
-    fn iter(&self) -> IntersectionIter<'a, T>
+    fn binary_search<C, U, F>(mut self, output: &mut C, extend: F) -> Result<(), C::Error>
+    where C: Collection<U>,
+          F: Fn(&mut C, &'a [T]) -> Result<(), C::Error>,
+    {
+        // the elements emitted are always the ones of the first slice
+        if self.a.len() <= self.b.len() {
+            for (i, x) in self.a.iter().enumerate() {
+                match self.b.binary_search(x) {
+                    Ok(pos) => {
+                        extend(output, &self.a[i..=i])?;
+                        self.b = &self.b[pos + 1..];
+                    },
+                    Err(pos) => self.b = &self.b[pos..],
+                }
+                if self.b.is_empty() { break }
+            }
+        } else {
+            for y in self.b {
+                match self.a.binary_search(y) {
+                    Ok(pos) => {
+                        extend(output, &self.a[pos..=pos])?;
+                        self.a = &self.a[pos + 1..];
+                    },
+                    Err(pos) => self.a = &self.a[pos..],
+                }
+                if self.a.is_empty() { break }
+            }
+        }
+        Ok(())
+    }
+
+    pub(super) fn iter(&self) -> IntersectionIter<'a, T>
     {
         IntersectionIter {
             a: self.a,
@@ -88,6 +167,14 @@ impl<'a, T: Ord> SetOperation<&'a T> for Intersection<'a, T> {
     }
 }
 
+impl<'a, T: Ord> Cardinality for Intersection<'a, T> {
+    fn count(self) -> usize {
+        let mut counter = Counter::<&T>::new();
+        self.extend_collection(&mut counter, Collection::extend).unwrap();
+        counter.get()
+    }
+}
+
 impl<'a, T: Ord> IntoIterator for Intersection<'a, T> {
     type Item = &'a T;
     type IntoIter = IntersectionIter<'a, T>;
@@ -132,7 +219,33 @@ impl<'a, T: Ord> Iterator for IntersectionIter<'a, T> {
     }
 }
 
+impl<'a, T: Ord> DoubleEndedIterator for IntersectionIter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        loop {
+            let (last_a, a) = self.a.split_last()?;
+            let (last_b, b) = self.b.split_last()?;
+            match last_a.cmp(last_b) {
+                Ordering::Less => self.b = exponential_offset_le(self.b, last_a),
+                Ordering::Equal => {
+                    self.a = a;
+                    self.b = b;
+                    return Some(last_a);
+                },
+                Ordering::Greater => self.a = exponential_offset_le(self.a, last_b),
+            }
+        }
+    }
+}
+
+impl<'a, T: Ord> SeekableIterator<T> for IntersectionIter<'a, T> {
+    fn advance_to(&mut self, target: &T) {
+        self.a = exponential_offset_ge(self.a, target);
+        self.b = exponential_offset_ge(self.b, target);
+    }
+}
+
 #[cfg(test)]
+#[allow(clippy::redundant_field_names)]
 mod tests {
     mod set_to_set {
         use super::super::*;
@@ -143,11 +256,49 @@ mod tests {
             let a = &[1, 2, 3];
             let b = &[2, 3, 4];
 
-            let intersection_: SetBuf<i32> = Intersection { a: a, b: b }.into_set_buf();
+            let intersection_: SetBuf<i32> = Intersection { a: a, b: b, strategy: Strategy::Auto }.into_set_buf();
             assert_eq!(&intersection_[..], &[2, 3]);
         }
 
         quickcheck! {
+            fn qc_strategies(a: Vec<i32>, b: Vec<i32>) -> bool {
+                use std::collections::BTreeSet;
+                use std::iter::FromIterator;
+
+                let mut a = a;
+                let mut b = b;
+
+                sort_dedup_vec(&mut a);
+                sort_dedup_vec(&mut b);
+
+                let y: Vec<_> = {
+                    let a = BTreeSet::from_iter(a.iter().cloned());
+                    let b = BTreeSet::from_iter(b.iter().cloned());
+                    a.intersection(&b).cloned().collect()
+                };
+
+                let strategies = [Strategy::Linear, Strategy::Galloping, Strategy::BinarySearch];
+                strategies.iter().all(|&strategy| {
+                    let x: SetBuf<i32> = Intersection { a: &a, b: &b, strategy }.into_set_buf();
+                    x.as_slice() == y.as_slice()
+                })
+            }
+
+            fn qc_count(a: Vec<i32>, b: Vec<i32>) -> bool {
+                use crate::{Cardinality, Counter};
+
+                let mut a = a;
+                let mut b = b;
+
+                sort_dedup_vec(&mut a);
+                sort_dedup_vec(&mut b);
+
+                let mut counter = Counter::<i32>::new();
+                SetOperation::extend_collection(Intersection { a: &a, b: &b, strategy: Strategy::Auto }, &mut counter).unwrap();
+
+                Intersection { a: &a, b: &b, strategy: Strategy::Auto }.count() == counter.get()
+            }
+
             fn qc_intersection(a: Vec<i32>, b: Vec<i32>) -> bool {
                 use std::collections::BTreeSet;
                 use std::iter::FromIterator;
@@ -158,7 +309,7 @@ mod tests {
                 sort_dedup_vec(&mut a);
                 sort_dedup_vec(&mut b);
 
-                let x: SetBuf<i32> = Intersection { a: &a, b: &b }.into_set_buf();
+                let x: SetBuf<i32> = Intersection { a: &a, b: &b, strategy: Strategy::Auto }.into_set_buf();
 
                 let a = BTreeSet::from_iter(a);
                 let b = BTreeSet::from_iter(b);
@@ -179,7 +330,7 @@ mod tests {
             let a = &[1, 2, 3];
             let b = &[2, 3, 4];
 
-            let intersection_: Vec<i32> = Intersection { a: a, b: b }.into_iter().cloned().collect();
+            let intersection_: Vec<i32> = Intersection { a: a, b: b, strategy: Strategy::Auto }.into_iter().cloned().collect();
             assert_eq!(&intersection_[..], &[2, 3]);
         }
 
@@ -194,13 +345,56 @@ mod tests {
                 sort_dedup_vec(&mut a);
                 sort_dedup_vec(&mut b);
 
-                let x: Vec<i32> = Intersection { a: &a, b: &b }.into_iter().cloned().collect();
+                let x: Vec<i32> = Intersection { a: &a, b: &b, strategy: Strategy::Auto }.into_iter().cloned().collect();
+
+                let a = BTreeSet::from_iter(a);
+                let b = BTreeSet::from_iter(b);
+                let y = a.intersection(&b);
+                let y: Vec<_> = y.cloned().collect();
+
+                x.as_slice() == y.as_slice()
+            }
+
+            fn qc_rev_intersection(a: Vec<i32>, b: Vec<i32>) -> bool {
+                use std::collections::BTreeSet;
+                use std::iter::FromIterator;
+
+                let mut a = a;
+                let mut b = b;
+
+                sort_dedup_vec(&mut a);
+                sort_dedup_vec(&mut b);
+
+                let x: Vec<i32> = Intersection { a: &a, b: &b, strategy: Strategy::Auto }.into_iter().rev().cloned().collect();
 
                 let a = BTreeSet::from_iter(a);
                 let b = BTreeSet::from_iter(b);
                 let y = a.intersection(&b);
                 let y: Vec<_> = y.cloned().collect();
 
+                x.iter().eq(y.iter().rev())
+            }
+
+            fn qc_advance_to(a: Vec<i32>, b: Vec<i32>, target: i32) -> bool {
+                use std::collections::BTreeSet;
+                use std::iter::FromIterator;
+                use crate::SeekableIterator;
+
+                let mut a = a;
+                let mut b = b;
+
+                sort_dedup_vec(&mut a);
+                sort_dedup_vec(&mut b);
+
+                let mut iter = Intersection { a: &a, b: &b, strategy: Strategy::Auto }.into_iter();
+                iter.advance_to(&target);
+                let x: Vec<i32> = iter.cloned().collect();
+
+                let a = BTreeSet::from_iter(a);
+                let b = BTreeSet::from_iter(b);
+                let y = a.intersection(&b);
+                let y: Vec<_> = y.cloned().filter(|&y| y >= target).collect();
+
                 x.as_slice() == y.as_slice()
             }
         }
@@ -220,7 +414,7 @@ mod bench {
         let b: Vec<_> = (1..101).collect();
 
         bench.iter(|| {
-            let intersection_: SetBuf<i32> = Intersection { a: &a, b: &b }.into_set_buf();
+            let intersection_: SetBuf<i32> = Intersection { a: &a, b: &b, strategy: Strategy::Auto }.into_set_buf();
             test::black_box(|| intersection_);
         });
     }
@@ -231,7 +425,7 @@ mod bench {
         let b: Vec<_> = (51..151).collect();
 
         bench.iter(|| {
-            let intersection_: SetBuf<i32> = Intersection { a: &a, b: &b }.into_set_buf();
+            let intersection_: SetBuf<i32> = Intersection { a: &a, b: &b, strategy: Strategy::Auto }.into_set_buf();
             test::black_box(|| intersection_);
         });
     }
@@ -242,8 +436,103 @@ mod bench {
         let b: Vec<_> = (100..200).collect();
 
         bench.iter(|| {
-            let intersection_: SetBuf<i32> = Intersection { a: &a, b: &b }.into_set_buf();
+            let intersection_: SetBuf<i32> = Intersection { a: &a, b: &b, strategy: Strategy::Auto }.into_set_buf();
             test::black_box(|| intersection_);
         });
     }
+
+    #[bench]
+    fn two_slices_big_counter(bench: &mut Bencher) {
+        use crate::Counter;
+
+        let a: Vec<_> = (0..100).collect();
+        let b: Vec<_> = (51..151).collect();
+
+        bench.iter(|| {
+            let mut counter = Counter::<i32>::new();
+            SetOperation::extend_collection(Intersection { a: &a, b: &b, strategy: Strategy::Auto }, &mut counter).unwrap();
+            test::black_box(|| counter.get());
+        });
+    }
+
+    #[bench]
+    fn two_slices_big_count(bench: &mut Bencher) {
+        use crate::Cardinality;
+
+        let a: Vec<_> = (0..100).collect();
+        let b: Vec<_> = (51..151).collect();
+
+        bench.iter(|| {
+            let intersection_ = Intersection { a: &a, b: &b, strategy: Strategy::Auto }.count();
+            test::black_box(|| intersection_);
+        });
+    }
+
+    fn strategy_bench(bench: &mut Bencher, a: &[i32], b: &[i32], strategy: Strategy) {
+        bench.iter(|| {
+            let intersection_: SetBuf<i32> = Intersection { a, b, strategy }.into_set_buf();
+            test::black_box(|| intersection_);
+        });
+    }
+
+    #[bench]
+    fn interleaved_linear(bench: &mut Bencher) {
+        let a: Vec<_> = (0..10_000).filter(|x| x % 2 == 0).collect();
+        let b: Vec<_> = (0..10_000).filter(|x| x % 3 == 0).collect();
+        strategy_bench(bench, &a, &b, Strategy::Linear);
+    }
+
+    #[bench]
+    fn interleaved_galloping(bench: &mut Bencher) {
+        let a: Vec<_> = (0..10_000).filter(|x| x % 2 == 0).collect();
+        let b: Vec<_> = (0..10_000).filter(|x| x % 3 == 0).collect();
+        strategy_bench(bench, &a, &b, Strategy::Galloping);
+    }
+
+    fn random_set(seed: u64, len: usize, max: u64) -> Vec<i32> {
+        let mut x = seed;
+        let mut v: Vec<i32> = (0..len).map(|_| {
+            x = x.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            ((x >> 33) % max) as i32
+        }).collect();
+        v.sort_unstable();
+        v.dedup();
+        v
+    }
+
+    #[bench]
+    fn random_linear(bench: &mut Bencher) {
+        let (a, b) = (random_set(1, 10_000, 200_000), random_set(2, 10_000, 200_000));
+        strategy_bench(bench, &a, &b, Strategy::Linear);
+    }
+
+    #[bench]
+    fn random_galloping(bench: &mut Bencher) {
+        let (a, b) = (random_set(1, 10_000, 200_000), random_set(2, 10_000, 200_000));
+        strategy_bench(bench, &a, &b, Strategy::Galloping);
+    }
+
+    #[bench]
+    fn random_skewed_galloping(bench: &mut Bencher) {
+        let (a, b) = (random_set(1, 100_000, 200_000), random_set(2, 1_000, 200_000));
+        strategy_bench(bench, &a, &b, Strategy::Galloping);
+    }
+
+    #[bench]
+    fn random_skewed_linear(bench: &mut Bencher) {
+        let (a, b) = (random_set(1, 100_000, 200_000), random_set(2, 1_000, 200_000));
+        strategy_bench(bench, &a, &b, Strategy::Linear);
+    }
+
+    #[bench]
+    fn random_very_skewed_binary_search(bench: &mut Bencher) {
+        let (a, b) = (random_set(1, 1_000_000, 2_000_000), random_set(2, 20, 2_000_000));
+        strategy_bench(bench, &a, &b, Strategy::BinarySearch);
+    }
+
+    #[bench]
+    fn random_very_skewed_galloping(bench: &mut Bencher) {
+        let (a, b) = (random_set(1, 1_000_000, 2_000_000), random_set(2, 20, 2_000_000));
+        strategy_bench(bench, &a, &b, Strategy::Galloping);
+    }
 }
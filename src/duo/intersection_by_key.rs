@@ -0,0 +1,529 @@
+use crate::set::Set;
+use crate::{exponential_offset_ge_by_key, exponential_offset_le_by_key, SetOperation, SeekableIterator, Collection};
+
+/// Represent the _intersection_ set operation that will be applied to two slices of different types.
+///
+/// The elements emitted are the ones of the first slice (`a`) which have a key
+/// that can also be found in the second slice (`b`), elements of `b` are never emitted.
+///
+/// # Examples
+/// ```
+/// # use sdset::Error;
+/// # fn try_main() -> Result<(), Error> {
+/// use sdset::duo::OpBuilderByKey;
+/// use sdset::{SetOperation, Set, SetBuf};
+///
+/// #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+/// struct Foo { a: i32, b: u8 }
+///
+/// let a = Set::new(&[
+///     Foo{ a: 1, b: 6 },
+///     Foo{ a: 1, b: 7 },
+///     Foo{ a: 1, b: 8 },
+///     Foo{ a: 2, b: 9 },
+///     Foo{ a: 2, b: 10 },
+///     Foo{ a: 3, b: 10 },
+/// ])?;
+/// let b = Set::new(&[1, 3, 4, 5]).unwrap();
+///
+/// // Return the field of Foo that will be used for comparison
+/// let f = |x: &Foo| x.a;
+///
+/// // directly use the i32 for comparison
+/// let g = |x: &i32| *x;
+///
+/// let op = OpBuilderByKey::new(a, b, f, g).intersection();
+/// let res: SetBuf<Foo> = op.into_set_buf();
+///
+/// assert_eq!(res.as_slice(), &[
+///     Foo{ a: 1, b: 6 },
+///     Foo{ a: 1, b: 7 },
+///     Foo{ a: 1, b: 8 },
+///     Foo{ a: 3, b: 10 },
+/// ][..]);
+/// # Ok(()) }
+/// # try_main().unwrap();
+/// ```
+#[derive(Copy, Clone)]
+pub struct IntersectionByKey<'a, T, U, F, G, K>
+where
+    T: 'a,
+    U: 'a,
+    F: Fn(&T) -> K,
+    G: Fn(&U) -> K,
+    K: Ord,
+{
+    a: &'a [T],
+    b: &'a [U],
+    f: F,
+    g: G,
+}
+
+impl<'a, T, U, F, G, K> IntersectionByKey<'a, T, U, F, G, K>
+where
+    T: 'a,
+    U: 'a,
+    F: Fn(&T) -> K,
+    G: Fn(&U) -> K,
+    K: Ord,
+{
+    /// Construct one with slices checked to be sorted and deduplicated.
+    pub fn new(a: &'a Set<T>, b: &'a Set<U>, f: F, g: G) -> Self {
+        Self {
+            a: a.as_slice(),
+            b: b.as_slice(),
+            f,
+            g,
+        }
+    }
+}
+
+impl<'a, T, U, F, G, K> IntersectionByKey<'a, T, U, F, G, K>
+where F: Fn(&T) -> K,
+      G: Fn(&U) -> K,
+      K: Ord,
+{
+    fn extend_collection<C, X, E>(mut self, output: &mut C, extend: E) -> Result<(), C::Error>
+    where C: Collection<X>,
+          E: Fn(&mut C, &'a [T]) -> Result<(), C::Error>,
+    {
+        while let Some(first_a) = self.a.first().map(|x| (self.f)(x)) {
+            self.b = exponential_offset_ge_by_key(self.b, &first_a, &self.g);
+
+            match self.b.first().map(|x| (self.g)(x)) {
+                Some(min) => {
+                    if min == first_a {
+                        let off = self.a.iter().take_while(|&x| (self.f)(x) == min).count();
+                        extend(output, &self.a[..off])?;
+
+                        self.a = &self.a[off..];
+                        // cannot advance b since we support duplicate relations
+                    } else {
+                        self.a = exponential_offset_ge_by_key(self.a, &min, &self.f);
+                    }
+                },
+                None => break,
+            }
+        }
+        Ok(())
+    }
+
+    fn iter(&'a self) -> IntersectionByKeyIter<'a, T, U, F, G, K>
+    {
+        IntersectionByKeyIter {
+            a: self.a,
+            b: self.b,
+            f: &self.f,
+            g: &self.g,
+        }
+    }
+}
+
+impl<'a, T, U, F, G, K> SetOperation<T> for IntersectionByKey<'a, T, U, F, G, K>
+where T: Clone,
+      F: Fn(&T) -> K,
+      G: Fn(&U) -> K,
+      K: Ord,
+{
+    fn extend_collection<C>(self, output: &mut C) -> Result<(), C::Error>
+    where C: Collection<T>,
+    {
+        self.extend_collection(output, Collection::extend_from_slice)
+    }
+}
+
+impl<'a, T, U, F, G, K> SetOperation<&'a T> for IntersectionByKey<'a, T, U, F, G, K>
+where F: Fn(&T) -> K,
+      G: Fn(&U) -> K,
+      K: Ord,
+{
+    fn extend_collection<C>(self, output: &mut C) -> Result<(), C::Error>
+    where C: Collection<&'a T>,
+    {
+        self.extend_collection(output, Collection::extend)
+    }
+}
+
+// This version of IntoIterator takes references to the functions (f/g).
+// See DifferenceByKey for the reason of the separate iterator structs.
+impl<'a, T, U, F, G, K> IntoIterator for &'a IntersectionByKey<'a, T, U, F, G, K>
+where F: Fn(&T) -> K,
+      G: Fn(&U) -> K,
+      K: Ord,
+{
+    type Item = &'a T;
+    type IntoIter = IntersectionByKeyIter<'a, T, U, F, G, K>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct IntersectionByKeyIter<'a, T, U, F, G, K>
+where
+    T: 'a,
+    U: 'a,
+    F: Fn(&T) -> K,
+    G: Fn(&U) -> K,
+    K: Ord,
+{
+    a: &'a [T],
+    b: &'a [U],
+    f: &'a F,
+    g: &'a G,
+}
+
+impl<'a, T, U, F, G, K> Iterator for IntersectionByKeyIter<'a, T, U, F, G, K>
+where
+    T: 'a,
+    U: 'a,
+    F: Fn(&T) -> K,
+    G: Fn(&U) -> K,
+    K: Ord,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        next_by_key(&mut self.a, &mut self.b, self.f, self.g)
+    }
+}
+
+impl<'a, T, U, F, G, K> DoubleEndedIterator for IntersectionByKeyIter<'a, T, U, F, G, K>
+where
+    T: 'a,
+    U: 'a,
+    F: Fn(&T) -> K,
+    G: Fn(&U) -> K,
+    K: Ord,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        next_back_by_key(&mut self.a, &mut self.b, self.f, self.g)
+    }
+}
+
+impl<'a, T, U, F, G, K> SeekableIterator<K> for IntersectionByKeyIter<'a, T, U, F, G, K>
+where
+    T: 'a,
+    U: 'a,
+    F: Fn(&T) -> K,
+    G: Fn(&U) -> K,
+    K: Ord,
+{
+    fn advance_to(&mut self, target: &K) {
+        self.a = exponential_offset_ge_by_key(self.a, target, self.f);
+        self.b = exponential_offset_ge_by_key(self.b, target, self.g);
+    }
+}
+
+// This version of IntoIterator moves the contents of self into the iterator.
+// Therefore the iterator owns the functions (f/g).
+impl<'a, T, U, F, G, K> IntoIterator for IntersectionByKey<'a, T, U, F, G, K>
+where F: Fn(&T) -> K + 'a,
+      G: Fn(&U) -> K + 'a,
+      K: Ord + 'a,
+{
+    type Item = &'a T;
+    type IntoIter = IntersectionByKeyIterOwning<'a, T, U, F, G, K>;
+    fn into_iter(self) -> Self::IntoIter {
+        Self::IntoIter {
+            a: self.a,
+            b: self.b,
+            f: self.f,
+            g: self.g,
+        }
+    }
+}
+
+pub struct IntersectionByKeyIterOwning<'a, T, U, F, G, K>
+where
+    T: 'a,
+    U: 'a,
+    F: Fn(&T) -> K,
+    G: Fn(&U) -> K,
+    K: Ord,
+{
+    a: &'a [T],
+    b: &'a [U],
+    f: F,
+    g: G,
+}
+
+impl<'a, T, U, F, G, K> Iterator for IntersectionByKeyIterOwning<'a, T, U, F, G, K>
+where
+    T: 'a,
+    U: 'a,
+    F: Fn(&T) -> K,
+    G: Fn(&U) -> K,
+    K: Ord,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        next_by_key(&mut self.a, &mut self.b, &self.f, &self.g)
+    }
+}
+
+impl<'a, T, U, F, G, K> DoubleEndedIterator for IntersectionByKeyIterOwning<'a, T, U, F, G, K>
+where
+    T: 'a,
+    U: 'a,
+    F: Fn(&T) -> K,
+    G: Fn(&U) -> K,
+    K: Ord,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        next_back_by_key(&mut self.a, &mut self.b, &self.f, &self.g)
+    }
+}
+
+impl<'a, T, U, F, G, K> SeekableIterator<K> for IntersectionByKeyIterOwning<'a, T, U, F, G, K>
+where
+    T: 'a,
+    U: 'a,
+    F: Fn(&T) -> K,
+    G: Fn(&U) -> K,
+    K: Ord,
+{
+    fn advance_to(&mut self, target: &K) {
+        self.a = exponential_offset_ge_by_key(self.a, target, &self.f);
+        self.b = exponential_offset_ge_by_key(self.b, target, &self.g);
+    }
+}
+
+#[inline]
+fn next_by_key<'a, T, U, F, G, K>(a: &mut &'a [T], b: &mut &'a [U], f: &F, g: &G) -> Option<&'a T>
+where F: Fn(&T) -> K,
+      G: Fn(&U) -> K,
+      K: Ord,
+{
+    loop {
+        let first_a = f(a.first()?);
+        *b = exponential_offset_ge_by_key(b, &first_a, g);
+        match b.first().map(g) {
+            Some(min) if min == first_a => {
+                let result = &a[0];
+                *a = &a[1..];
+                // cannot advance b since we support duplicate relations
+                return Some(result);
+            },
+            Some(min) => {
+                *a = exponential_offset_ge_by_key(a, &min, f);
+            },
+            None => {
+                *a = &[];
+                return None;
+            },
+        }
+    }
+}
+
+#[inline]
+fn next_back_by_key<'a, T, U, F, G, K>(a: &mut &'a [T], b: &mut &'a [U], f: &F, g: &G) -> Option<&'a T>
+where F: Fn(&T) -> K,
+      G: Fn(&U) -> K,
+      K: Ord,
+{
+    loop {
+        let last_a = f(a.last()?);
+        *b = exponential_offset_le_by_key(b, &last_a, g);
+        match b.last().map(g) {
+            Some(max) if max == last_a => {
+                let result = &a[a.len() - 1];
+                *a = &a[..a.len() - 1];
+                // cannot shrink b since we support duplicate relations
+                return Some(result);
+            },
+            Some(max) => {
+                *a = exponential_offset_le_by_key(a, &max, f);
+            },
+            None => {
+                *a = &[];
+                return None;
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod set_to_set {
+        use super::super::*;
+        use crate::set::{sort_dedup_vec, SetBuf};
+
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        struct Foo {
+            a: i32,
+            b: i8,
+        }
+
+        #[test]
+        fn intersection_empty_no_relations() {
+            let a = Set::new_unchecked(&[
+                Foo{ a: 1, b: 8 },
+                Foo{ a: 3, b: 10 },
+                Foo{ a: 5, b: 12 },
+            ]);
+            let b = Set::new(&[2, 4, 6]).unwrap();
+
+            let intersection: SetBuf<Foo> = IntersectionByKey::new(a, b, |x| x.a, |&x| x).into_set_buf();
+
+            assert!(intersection.is_empty());
+        }
+
+        #[test]
+        fn intersection_duplicate_relations() {
+            let a = Set::new_unchecked(&[
+                Foo{ a: 1, b: 6 },
+                Foo{ a: 1, b: 7 },
+                Foo{ a: 1, b: 8 },
+                Foo{ a: 2, b: 9 },
+                Foo{ a: 2, b: 10 },
+                Foo{ a: 4, b: 11 },
+            ]);
+            let b = Set::new(&[1, 3, 4, 5]).unwrap();
+
+            let intersection: SetBuf<Foo> = IntersectionByKey::new(a, b, |x| x.a, |&x| x).into_set_buf();
+
+            assert_eq!(intersection.as_slice(), &[
+                Foo{ a: 1, b: 6 },
+                Foo{ a: 1, b: 7 },
+                Foo{ a: 1, b: 8 },
+                Foo{ a: 4, b: 11 },
+            ][..]);
+        }
+
+        #[test]
+        fn intersection_duplicate_keys_in_base() {
+            let a: Vec<_> = (0..33).map(|x| (x, 0)).chain((0..40).map(|i| (100, i))).collect();
+            let b = Set::new(&[100]).unwrap();
+
+            let intersection: SetBuf<(i32, i32)> = IntersectionByKey::new(Set::new_unchecked(&a), b, |x| x.0, |&x| x).into_set_buf();
+
+            assert_eq!(intersection.as_slice(), &a[33..]);
+        }
+
+        quickcheck! {
+            fn qc_intersection(a: Vec<i32>, b: Vec<i64>) -> bool {
+                use std::collections::BTreeSet;
+                use std::iter::FromIterator;
+
+                let mut a = a;
+                let mut b = b;
+
+                sort_dedup_vec(&mut a);
+                sort_dedup_vec(&mut b);
+
+                let x: SetBuf<i32> = {
+                    let intersection = IntersectionByKey { a: &a, b: &b, f: |&x| x, g: |&x| x as i32 };
+                    intersection.into_set_buf()
+                };
+
+                let a = BTreeSet::from_iter(a);
+                let b = BTreeSet::from_iter(b.into_iter().map(|x| x as i32));
+                let y = a.intersection(&b);
+                let y: Vec<_> = y.cloned().collect();
+
+                x.as_slice() == y.as_slice()
+            }
+        }
+    }
+
+    mod set_to_iter {
+        use super::super::*;
+        use crate::set::sort_dedup_vec;
+
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        struct Foo {
+            a: i32,
+            b: i8,
+        }
+
+        #[test]
+        fn intersection_duplicate_relations() {
+            let a = Set::new_unchecked(&[
+                Foo{ a: 1, b: 6 },
+                Foo{ a: 1, b: 7 },
+                Foo{ a: 1, b: 8 },
+                Foo{ a: 2, b: 9 },
+                Foo{ a: 2, b: 10 },
+                Foo{ a: 4, b: 11 },
+            ]);
+            let b = Set::new(&[1, 3, 4, 5]).unwrap();
+
+            let intersection = IntersectionByKey::new(a, b, |x| x.a, |&x| x);
+            let expected = &[
+                Foo{ a: 1, b: 6 },
+                Foo{ a: 1, b: 7 },
+                Foo{ a: 1, b: 8 },
+                Foo{ a: 4, b: 11 },
+            ][..];
+
+            let inter_ref: Vec<Foo> = intersection.iter().cloned().collect();
+            assert_eq!(inter_ref.as_slice(), expected);
+
+            let inter_own: Vec<Foo> = intersection.into_iter().cloned().collect();
+            assert_eq!(inter_own.as_slice(), expected);
+        }
+
+        quickcheck! {
+            fn qc_advance_to(a: Vec<(i8, i8)>, b: Vec<i8>, target: i8) -> bool {
+                let mut a = a;
+                let mut b = b;
+
+                sort_dedup_vec(&mut a);
+                sort_dedup_vec(&mut b);
+
+                let op = IntersectionByKey { a: &a, b: &b, f: |x: &(i8, i8)| x.0, g: |&x: &i8| x };
+                let expected: Vec<_> = op.iter().filter(|x| x.0 >= target).collect();
+
+                let mut iter = op.iter();
+                iter.advance_to(&target);
+                let x: Vec<_> = iter.collect();
+
+                let mut iter = op.into_iter();
+                iter.advance_to(&target);
+                let y: Vec<_> = iter.collect();
+
+                x == expected && y == expected
+            }
+
+            fn qc_next_and_next_back(a: Vec<(i8, i8)>, b: Vec<i8>, sides: Vec<bool>) -> bool {
+                use crate::collect_from_both_ends;
+
+                let mut a = a;
+                let mut b = b;
+
+                sort_dedup_vec(&mut a);
+                sort_dedup_vec(&mut b);
+
+                let op = IntersectionByKey { a: &a, b: &b, f: |x: &(i8, i8)| x.0, g: |&x: &i8| x };
+                let expected: Vec<_> = op.iter().collect();
+
+                collect_from_both_ends(op.iter(), &sides) == expected
+                    && collect_from_both_ends(op.into_iter(), &sides) == expected
+            }
+
+            fn qc_intersection(a: Vec<i32>, b: Vec<i64>) -> bool {
+                use std::collections::BTreeSet;
+                use std::iter::FromIterator;
+
+                let mut a = a;
+                let mut b = b;
+
+                sort_dedup_vec(&mut a);
+                sort_dedup_vec(&mut b);
+
+                let x: Vec<i32> = {
+                    let intersection = IntersectionByKey { a: &a, b: &b, f: |&x| x, g: |&x| x as i32 };
+                    intersection.into_iter().cloned().collect()
+                };
+
+                let a = BTreeSet::from_iter(a);
+                let b = BTreeSet::from_iter(b.into_iter().map(|x| x as i32));
+                let y = a.intersection(&b);
+                let y: Vec<_> = y.cloned().collect();
+
+                x.as_slice() == y.as_slice()
+            }
+        }
+    }
+}
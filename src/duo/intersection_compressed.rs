@@ -0,0 +1,175 @@
+use std::cmp::Ordering;
+use crate::codec::CompressedSet;
+use crate::cursor::Cursor;
+use crate::{SetOperation, SeekableIterator, Collection};
+
+/// Represent the _intersection_ set operation that will be applied to two compressed sets.
+///
+/// The sets are galloped through, only the parts that may contain common elements are decoded.
+///
+/// # Examples
+/// ```
+/// # use sdset::Error;
+/// # fn try_main() -> Result<(), Error> {
+/// use sdset::codec::{BlockSet, EliasFanoSet};
+/// use sdset::duo::OpBuilderCompressed;
+/// use sdset::{SetOperation, Set, SetBuf};
+///
+/// let a: Vec<u64> = (0..10_000).collect();
+/// let a = EliasFanoSet::new(Set::new(&a)?);
+/// let b = BlockSet::new(Set::new(&[3u64, 5_000, 9_999, 20_000])?);
+///
+/// let op = OpBuilderCompressed::new(&a, &b).intersection();
+///
+/// let res: SetBuf<u64> = op.into_set_buf();
+/// assert_eq!(&res[..], &[3, 5_000, 9_999]);
+/// # Ok(()) }
+/// # try_main().unwrap();
+/// ```
+#[derive(Copy, Clone)]
+pub struct IntersectionCompressed<'a, A: 'a, B: 'a> {
+    a: &'a A,
+    b: &'a B,
+}
+
+impl<'a, A, B> IntersectionCompressed<'a, A, B> {
+    /// Construct one with two compressed sets.
+    pub fn new(a: &'a A, b: &'a B) -> Self {
+        Self { a, b }
+    }
+}
+
+impl<'a, A, B> IntersectionCompressed<'a, A, B>
+where A: CompressedSet,
+      B: CompressedSet<Item = A::Item>,
+{
+    fn iter(&self) -> IntersectionCompressedIter<'a, A, B> {
+        IntersectionCompressedIter {
+            a: Cursor::new(self.a.iter()),
+            b: Cursor::new(self.b.iter()),
+        }
+    }
+}
+
+impl<'a, A, B> SetOperation<A::Item> for IntersectionCompressed<'a, A, B>
+where A: CompressedSet,
+      B: CompressedSet<Item = A::Item>,
+{
+    fn extend_collection<C>(self, output: &mut C) -> Result<(), C::Error>
+    where C: Collection<A::Item>,
+    {
+        output.extend(self)
+    }
+}
+
+impl<'a, A, B> IntoIterator for IntersectionCompressed<'a, A, B>
+where A: CompressedSet,
+      B: CompressedSet<Item = A::Item>,
+{
+    type Item = A::Item;
+    type IntoIter = IntersectionCompressedIter<'a, A, B>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, A, B> IntoIterator for &'a IntersectionCompressed<'a, A, B>
+where A: CompressedSet,
+      B: CompressedSet<Item = A::Item>,
+{
+    type Item = A::Item;
+    type IntoIter = IntersectionCompressedIter<'a, A, B>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct IntersectionCompressedIter<'a, A: CompressedSet + 'a, B: CompressedSet + 'a> {
+    a: Cursor<A::Iter<'a>>,
+    b: Cursor<B::Iter<'a>>,
+}
+
+impl<'a, A, B> Iterator for IntersectionCompressedIter<'a, A, B>
+where A: CompressedSet,
+      B: CompressedSet<Item = A::Item>,
+{
+    type Item = A::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let a = *self.a.peek()?;
+            let b = *self.b.peek()?;
+
+            match a.cmp(&b) {
+                Ordering::Less => self.a.advance_to(&b),
+                Ordering::Greater => self.b.advance_to(&a),
+                Ordering::Equal => {
+                    self.a.bump();
+                    self.b.bump();
+                    return Some(a);
+                },
+            }
+        }
+    }
+}
+
+impl<'a, A, B> SeekableIterator<A::Item> for IntersectionCompressedIter<'a, A, B>
+where A: CompressedSet,
+      B: CompressedSet<Item = A::Item>,
+{
+    fn advance_to(&mut self, target: &A::Item) {
+        self.a.advance_to(target);
+        self.b.advance_to(target);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec::{BlockSet, EliasFanoSet};
+    use crate::set::{sort_dedup_vec, Set};
+
+    quickcheck! {
+        fn qc_intersection(a: Vec<u32>, b: Vec<u32>) -> bool {
+            use std::collections::BTreeSet;
+            use std::iter::FromIterator;
+
+            let mut a = a;
+            let mut b = b;
+
+            sort_dedup_vec(&mut a);
+            sort_dedup_vec(&mut b);
+
+            let x: Vec<u32> = {
+                let a = BlockSet::new(Set::new_unchecked(&a));
+                let b = BlockSet::new(Set::new_unchecked(&b));
+                IntersectionCompressed::new(&a, &b).into_set_buf().into_vec()
+            };
+
+            let a = BTreeSet::from_iter(a);
+            let b = BTreeSet::from_iter(b);
+            let y: Vec<_> = a.intersection(&b).cloned().collect();
+
+            x.as_slice() == y.as_slice()
+        }
+
+        fn qc_intersection_blocks(a: Vec<u16>, b: Vec<u16>) -> bool {
+            // dense sets spanning multiple blocks
+            let mut a: Vec<u64> = a.into_iter().flat_map(|x| (0..20).map(move |i| u64::from(x) * 20 + i)).collect();
+            let mut b: Vec<u64> = b.into_iter().map(|x| u64::from(x) * 7).collect();
+
+            sort_dedup_vec(&mut a);
+            sort_dedup_vec(&mut b);
+
+            let x: Vec<u64> = {
+                let a = BlockSet::new(Set::new_unchecked(&a));
+                let b = EliasFanoSet::new(Set::new_unchecked(&b));
+                IntersectionCompressed::new(&a, &b).into_iter().collect()
+            };
+
+            let y = crate::duo::Intersection::new(Set::new_unchecked(&a), Set::new_unchecked(&b)).into_set_buf();
+
+            x.as_slice() == y.as_slice()
+        }
+    }
+}
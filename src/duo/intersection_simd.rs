@@ -0,0 +1,513 @@
+use std::cmp;
+use std::convert::Infallible;
+use crate::set::Set;
+use crate::{SetOperation, Collection, Cardinality};
+use super::Intersection;
+use super::intersection::IntersectionIter;
+
+/// When one slice is that many times bigger than the other one, galloping
+/// through the bigger slice is faster than comparing every block of it.
+const SKEW_RATIO: usize = 32;
+
+/// Represent the _intersection_ set operation that will be applied to two slices
+/// of primitive integers, comparing whole blocks of them using SIMD instructions.
+///
+/// The SSE2, SSE4.1 and AVX2 instruction sets are detected at runtime,
+/// the operation falls back to the scalar [`Intersection`] when none of them
+/// is available or when the slices lengths are too different.
+///
+/// Iterating over this operation does not use SIMD instructions.
+///
+/// # Examples
+/// ```
+/// # use sdset::Error;
+/// # fn try_main() -> Result<(), Error> {
+/// use sdset::duo::OpBuilder;
+/// use sdset::{SetOperation, Set, SetBuf};
+///
+/// let a = Set::new(&[1u32, 2, 4, 6, 7, 9, 10, 12, 13])?;
+/// let b = Set::new(&[2u32, 3, 4, 5, 6, 7, 12, 13, 15])?;
+///
+/// let op = OpBuilder::new(a, b).intersection_simd();
+///
+/// let res: SetBuf<u32> = op.into_set_buf();
+/// assert_eq!(&res[..], &[2, 4, 6, 7, 12, 13]);
+/// # Ok(()) }
+/// # try_main().unwrap();
+/// ```
+#[derive(Copy, Clone)]
+pub struct IntersectionSimd<'a, T: 'a> {
+    a: &'a [T],
+    b: &'a [T],
+}
+
+impl<'a, T> IntersectionSimd<'a, T> {
+    /// Construct one with slices checked to be sorted and deduplicated.
+    pub fn new(a: &'a Set<T>, b: &'a Set<T>) -> Self {
+        Self {
+            a: a.as_slice(),
+            b: b.as_slice(),
+        }
+    }
+}
+
+impl<'a, T: SimdElement> IntersectionSimd<'a, T> {
+    fn is_skewed(&self) -> bool {
+        let min = cmp::min(self.a.len(), self.b.len());
+        let max = cmp::max(self.a.len(), self.b.len());
+        min.saturating_mul(SKEW_RATIO) < max
+    }
+
+    #[inline]
+    fn extend_collection<C, U, F>(mut self, output: &mut C, extend: F) -> Result<(), C::Error>
+    where C: Collection<U>,
+          F: Fn(&mut C, &'a [T]) -> Result<(), C::Error>,
+    {
+        if !self.is_skewed() {
+            T::intersect_blocks(&mut self.a, &mut self.b, |s| extend(output, s))?;
+        }
+
+        // the remaining elements are less than a block
+        let (a, b) = (Set::new_unchecked(self.a), Set::new_unchecked(self.b));
+        Intersection::new(a, b).extend_collection(output, extend)
+    }
+}
+
+impl<'a, T: SimdElement> SetOperation<T> for IntersectionSimd<'a, T> {
+    fn extend_collection<C>(self, output: &mut C) -> Result<(), C::Error>
+    where C: Collection<T>,
+    {
+        self.extend_collection(output, Collection::extend_from_slice)
+    }
+}
+
+impl<'a, T: SimdElement> SetOperation<&'a T> for IntersectionSimd<'a, T> {
+    fn extend_collection<C>(self, output: &mut C) -> Result<(), C::Error>
+    where C: Collection<&'a T>,
+    {
+        self.extend_collection(output, Collection::extend)
+    }
+}
+
+impl<'a, T: SimdElement> Cardinality for IntersectionSimd<'a, T> {
+    fn count(mut self) -> usize {
+        let mut count = 0;
+
+        if !self.is_skewed() {
+            let result = T::intersect_blocks(&mut self.a, &mut self.b, |s| {
+                count += s.len();
+                Ok::<_, Infallible>(())
+            });
+            result.unwrap();
+        }
+
+        let (a, b) = (Set::new_unchecked(self.a), Set::new_unchecked(self.b));
+        count + Intersection::new(a, b).count()
+    }
+}
+
+impl<'a, T: Ord> IntoIterator for IntersectionSimd<'a, T> {
+    type Item = &'a T;
+    type IntoIter = IntersectionIter<'a, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        let (a, b) = (Set::new_unchecked(self.a), Set::new_unchecked(self.b));
+        Intersection::new(a, b).iter()
+    }
+}
+
+impl<'a, T: Ord> IntoIterator for &'a IntersectionSimd<'a, T> {
+    type Item = &'a T;
+    type IntoIter = IntersectionIter<'a, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        let (a, b) = (Set::new_unchecked(self.a), Set::new_unchecked(self.b));
+        Intersection::new(a, b).iter()
+    }
+}
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for u32 {}
+    impl Sealed for u64 {}
+}
+
+/// The primitive integers that can be intersected using SIMD instructions.
+///
+/// This trait is sealed and cannot be implemented outside of this crate.
+pub trait SimdElement: Ord + Copy + sealed::Sealed {
+    /// Consume the blocks of both slices, calling `f` with the runs of common elements,
+    /// the elements left in one of the slices are less than a block.
+    #[doc(hidden)]
+    fn intersect_blocks<'a, E, F>(a: &mut &'a [Self], b: &mut &'a [Self], f: F) -> Result<(), E>
+    where F: FnMut(&'a [Self]) -> Result<(), E>;
+}
+
+impl SimdElement for u32 {
+    #[allow(unused_variables)]
+    fn intersect_blocks<'a, E, F>(a: &mut &'a [u32], b: &mut &'a [u32], f: F) -> Result<(), E>
+    where F: FnMut(&'a [u32]) -> Result<(), E>,
+    {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        {
+            if is_x86_feature_detected!("avx2") {
+                return unsafe { x86::intersect_u32_avx2(a, b, f) };
+            }
+            if is_x86_feature_detected!("sse2") {
+                return unsafe { x86::intersect_u32_sse2(a, b, f) };
+            }
+        }
+        Ok(())
+    }
+}
+
+impl SimdElement for u64 {
+    #[allow(unused_variables)]
+    fn intersect_blocks<'a, E, F>(a: &mut &'a [u64], b: &mut &'a [u64], f: F) -> Result<(), E>
+    where F: FnMut(&'a [u64]) -> Result<(), E>,
+    {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        {
+            if is_x86_feature_detected!("avx2") {
+                return unsafe { x86::intersect_u64_avx2(a, b, f) };
+            }
+            if is_x86_feature_detected!("sse4.1") {
+                return unsafe { x86::intersect_u64_sse41(a, b, f) };
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod x86 {
+    #[cfg(target_arch = "x86")]
+    use std::arch::x86::*;
+    #[cfg(target_arch = "x86_64")]
+    use std::arch::x86_64::*;
+
+    // Compares blocks of `$lanes` elements of both slices, `$mask` must return
+    // a bitmask of the elements of the `a` block that are present in the `b` block.
+    // The block with the smallest last element is consumed, both are when equal.
+    macro_rules! intersect_blocks {
+        ($a:ident, $b:ident, $f:ident, $lanes:expr, $mask:ident) => {{
+            let (mut a, mut b) = (*$a, *$b);
+
+            while a.len() >= $lanes && b.len() >= $lanes {
+                let mut mask = $mask(a.as_ptr(), b.as_ptr());
+
+                // emit the runs of consecutive common elements at once
+                while mask != 0 {
+                    let start = mask.trailing_zeros() as usize;
+                    let len = (!(mask >> start)).trailing_zeros() as usize;
+                    $f(&a[start..start + len])?;
+                    mask &= !(((1 << len) - 1) << start);
+                }
+
+                let (last_a, last_b) = (a[$lanes - 1], b[$lanes - 1]);
+                if last_a <= last_b { a = &a[$lanes..] }
+                if last_b <= last_a { b = &b[$lanes..] }
+            }
+
+            *$a = a;
+            *$b = b;
+            Ok(())
+        }};
+    }
+
+    #[inline]
+    #[target_feature(enable = "sse2")]
+    unsafe fn mask_u32_sse2(a: *const u32, b: *const u32) -> u32 {
+        let va = _mm_loadu_si128(a as *const __m128i);
+        let vb = _mm_loadu_si128(b as *const __m128i);
+
+        let r1 = _mm_shuffle_epi32(vb, 0b00_11_10_01);
+        let r2 = _mm_shuffle_epi32(vb, 0b01_00_11_10);
+        let r3 = _mm_shuffle_epi32(vb, 0b10_01_00_11);
+
+        let eq = _mm_or_si128(
+            _mm_or_si128(_mm_cmpeq_epi32(va, vb), _mm_cmpeq_epi32(va, r1)),
+            _mm_or_si128(_mm_cmpeq_epi32(va, r2), _mm_cmpeq_epi32(va, r3)),
+        );
+
+        _mm_movemask_ps(_mm_castsi128_ps(eq)) as u32
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn mask_u32_avx2(a: *const u32, b: *const u32) -> u32 {
+        let va = _mm256_loadu_si256(a as *const __m256i);
+        let vb = _mm256_loadu_si256(b as *const __m256i);
+
+        // rotations are independent to avoid chaining the permutations latencies
+        let mut eq = _mm256_cmpeq_epi32(va, vb);
+        for &r in &[1, 2, 3, 4, 5, 6, 7] {
+            let rotate = _mm256_setr_epi32(r, r + 1, r + 2, r + 3, r + 4, r + 5, r + 6, r + 7);
+            let vr = _mm256_permutevar8x32_epi32(vb, rotate);
+            eq = _mm256_or_si256(eq, _mm256_cmpeq_epi32(va, vr));
+        }
+
+        _mm256_movemask_ps(_mm256_castsi256_ps(eq)) as u32
+    }
+
+    #[inline]
+    #[target_feature(enable = "sse4.1")]
+    unsafe fn mask_u64_sse41(a: *const u64, b: *const u64) -> u32 {
+        let va = _mm_loadu_si128(a as *const __m128i);
+        let vb = _mm_loadu_si128(b as *const __m128i);
+
+        let r1 = _mm_shuffle_epi32(vb, 0b01_00_11_10);
+        let eq = _mm_or_si128(_mm_cmpeq_epi64(va, vb), _mm_cmpeq_epi64(va, r1));
+
+        _mm_movemask_pd(_mm_castsi128_pd(eq)) as u32
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn mask_u64_avx2(a: *const u64, b: *const u64) -> u32 {
+        let va = _mm256_loadu_si256(a as *const __m256i);
+        let vb = _mm256_loadu_si256(b as *const __m256i);
+
+        let r1 = _mm256_permute4x64_epi64(vb, 0b00_11_10_01);
+        let r2 = _mm256_permute4x64_epi64(vb, 0b01_00_11_10);
+        let r3 = _mm256_permute4x64_epi64(vb, 0b10_01_00_11);
+
+        let eq = _mm256_or_si256(
+            _mm256_or_si256(_mm256_cmpeq_epi64(va, vb), _mm256_cmpeq_epi64(va, r1)),
+            _mm256_or_si256(_mm256_cmpeq_epi64(va, r2), _mm256_cmpeq_epi64(va, r3)),
+        );
+
+        _mm256_movemask_pd(_mm256_castsi256_pd(eq)) as u32
+    }
+
+    #[target_feature(enable = "sse2")]
+    pub unsafe fn intersect_u32_sse2<'a, E, F>(a: &mut &'a [u32], b: &mut &'a [u32], mut f: F) -> Result<(), E>
+    where F: FnMut(&'a [u32]) -> Result<(), E>,
+    {
+        intersect_blocks!(a, b, f, 4, mask_u32_sse2)
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn intersect_u32_avx2<'a, E, F>(a: &mut &'a [u32], b: &mut &'a [u32], mut f: F) -> Result<(), E>
+    where F: FnMut(&'a [u32]) -> Result<(), E>,
+    {
+        intersect_blocks!(a, b, f, 8, mask_u32_avx2)
+    }
+
+    #[target_feature(enable = "sse4.1")]
+    pub unsafe fn intersect_u64_sse41<'a, E, F>(a: &mut &'a [u64], b: &mut &'a [u64], mut f: F) -> Result<(), E>
+    where F: FnMut(&'a [u64]) -> Result<(), E>,
+    {
+        intersect_blocks!(a, b, f, 2, mask_u64_sse41)
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn intersect_u64_avx2<'a, E, F>(a: &mut &'a [u64], b: &mut &'a [u64], mut f: F) -> Result<(), E>
+    where F: FnMut(&'a [u64]) -> Result<(), E>,
+    {
+        intersect_blocks!(a, b, f, 4, mask_u64_avx2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod set_to_set {
+        use super::super::*;
+        use crate::set::{sort_dedup_vec, SetBuf};
+
+        #[test]
+        fn two_slices() {
+            let a = &[1, 2, 3];
+            let b = &[2, 3, 4];
+
+            let intersection_: SetBuf<u32> = IntersectionSimd { a, b }.into_set_buf();
+            assert_eq!(&intersection_[..], &[2, 3]);
+        }
+
+        #[test]
+        fn two_slices_big() {
+            let a: Vec<u32> = (0..1000).filter(|x| x % 3 == 0).collect();
+            let b: Vec<u32> = (0..1000).filter(|x| x % 5 == 0).collect();
+
+            let intersection_: SetBuf<u32> = IntersectionSimd { a: &a, b: &b }.into_set_buf();
+            let expected: Vec<u32> = (0..1000).filter(|x| x % 15 == 0).collect();
+            assert_eq!(&intersection_[..], &expected[..]);
+        }
+
+        #[test]
+        fn two_slices_skewed() {
+            let a: Vec<u64> = (0..10_000).collect();
+            let b: Vec<u64> = vec![3, 450, 9_999, 10_000];
+
+            let intersection_: SetBuf<u64> = IntersectionSimd { a: &a, b: &b }.into_set_buf();
+            assert_eq!(&intersection_[..], &[3, 450, 9_999]);
+        }
+
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        #[test]
+        fn every_kernel() {
+            use super::super::x86::*;
+
+            fn check<T, K>(kernel: K)
+            where T: SimdElement + From<u8> + std::ops::Mul<Output=T> + std::fmt::Debug,
+                  K: for<'a> Fn(&mut &'a [T], &mut &'a [T], &mut Vec<T>),
+            {
+                let a: Vec<T> = (0..=255u8).map(T::from).filter(|x| *x != T::from(3)).collect();
+                let b: Vec<T> = (0..=85u8).map(|x| T::from(x) * T::from(3)).collect();
+
+                let (mut ra, mut rb) = (&a[..], &b[..]);
+                let mut result = Vec::new();
+                kernel(&mut ra, &mut rb, &mut result);
+
+                let (ra, rb) = (Set::new_unchecked(ra), Set::new_unchecked(rb));
+                SetOperation::extend_collection(Intersection::new(ra, rb), &mut result).unwrap();
+
+                let mut expected = b.clone();
+                expected.retain(|x| *x != T::from(3));
+                assert_eq!(result, expected);
+            }
+
+            if is_x86_feature_detected!("sse2") {
+                check::<u32, _>(|a, b, out| unsafe {
+                    intersect_u32_sse2(a, b, |s| Collection::extend_from_slice(out, s)).unwrap()
+                });
+            }
+            if is_x86_feature_detected!("avx2") {
+                check::<u32, _>(|a, b, out| unsafe {
+                    intersect_u32_avx2(a, b, |s| Collection::extend_from_slice(out, s)).unwrap()
+                });
+                check::<u64, _>(|a, b, out| unsafe {
+                    intersect_u64_avx2(a, b, |s| Collection::extend_from_slice(out, s)).unwrap()
+                });
+            }
+            if is_x86_feature_detected!("sse4.1") {
+                check::<u64, _>(|a, b, out| unsafe {
+                    intersect_u64_sse41(a, b, |s| Collection::extend_from_slice(out, s)).unwrap()
+                });
+            }
+        }
+
+        quickcheck! {
+            fn qc_count(a: Vec<u32>, b: Vec<u32>) -> bool {
+                use crate::Counter;
+
+                let mut a: Vec<_> = a.into_iter().map(|x| x % 512).collect();
+                let mut b: Vec<_> = b.into_iter().map(|x| x % 512).collect();
+
+                sort_dedup_vec(&mut a);
+                sort_dedup_vec(&mut b);
+
+                let mut counter = Counter::<u32>::new();
+                SetOperation::extend_collection(IntersectionSimd { a: &a, b: &b }, &mut counter).unwrap();
+
+                IntersectionSimd { a: &a, b: &b }.count() == counter.get()
+            }
+
+            fn qc_intersection_u32(a: Vec<u32>, b: Vec<u32>) -> bool {
+                use std::collections::BTreeSet;
+                use std::iter::FromIterator;
+
+                let mut a: Vec<_> = a.into_iter().map(|x| x % 512).collect();
+                let mut b: Vec<_> = b.into_iter().map(|x| x % 512).collect();
+
+                sort_dedup_vec(&mut a);
+                sort_dedup_vec(&mut b);
+
+                let x: SetBuf<u32> = IntersectionSimd { a: &a, b: &b }.into_set_buf();
+
+                let a = BTreeSet::from_iter(a);
+                let b = BTreeSet::from_iter(b);
+                let y = a.intersection(&b);
+                let y: Vec<_> = y.cloned().collect();
+
+                x.as_slice() == y.as_slice()
+            }
+
+            fn qc_intersection_u64(a: Vec<u64>, b: Vec<u64>) -> bool {
+                use std::collections::BTreeSet;
+                use std::iter::FromIterator;
+
+                let mut a: Vec<_> = a.into_iter().map(|x| x % 512).collect();
+                let mut b: Vec<_> = b.into_iter().map(|x| x % 512).collect();
+
+                sort_dedup_vec(&mut a);
+                sort_dedup_vec(&mut b);
+
+                let x: SetBuf<u64> = IntersectionSimd { a: &a, b: &b }.into_set_buf();
+
+                let a = BTreeSet::from_iter(a);
+                let b = BTreeSet::from_iter(b);
+                let y = a.intersection(&b);
+                let y: Vec<_> = y.cloned().collect();
+
+                x.as_slice() == y.as_slice()
+            }
+        }
+    }
+
+    mod set_to_iter {
+        use super::super::*;
+
+        #[test]
+        fn two_slices() {
+            let a = &[1, 2, 3];
+            let b = &[2, 3, 4];
+
+            let intersection_: Vec<u32> = IntersectionSimd { a, b }.into_iter().cloned().collect();
+            assert_eq!(&intersection_[..], &[2, 3]);
+        }
+    }
+}
+
+#[cfg(all(feature = "unstable", test))]
+mod bench {
+    extern crate test;
+    use super::*;
+    use self::test::Bencher;
+    use crate::set::SetBuf;
+
+    #[bench]
+    fn two_slices_big(bench: &mut Bencher) {
+        let a: Vec<u32> = (0..10_000).filter(|x| x % 3 == 0).collect();
+        let b: Vec<u32> = (0..10_000).filter(|x| x % 5 == 0).collect();
+
+        bench.iter(|| {
+            let intersection_: SetBuf<u32> = IntersectionSimd { a: &a, b: &b }.into_set_buf();
+            test::black_box(|| intersection_);
+        });
+    }
+
+    #[bench]
+    fn two_slices_big_scalar(bench: &mut Bencher) {
+        let a: Vec<u32> = (0..10_000).filter(|x| x % 3 == 0).collect();
+        let b: Vec<u32> = (0..10_000).filter(|x| x % 5 == 0).collect();
+
+        bench.iter(|| {
+            let (a, b) = (Set::new_unchecked(&a[..]), Set::new_unchecked(&b[..]));
+            let intersection_: SetBuf<u32> = Intersection::new(a, b).into_set_buf();
+            test::black_box(|| intersection_);
+        });
+    }
+
+    #[bench]
+    fn two_slices_big_u64(bench: &mut Bencher) {
+        let a: Vec<u64> = (0..10_000).filter(|x| x % 3 == 0).collect();
+        let b: Vec<u64> = (0..10_000).filter(|x| x % 5 == 0).collect();
+
+        bench.iter(|| {
+            let intersection_: SetBuf<u64> = IntersectionSimd { a: &a, b: &b }.into_set_buf();
+            test::black_box(|| intersection_);
+        });
+    }
+
+    #[bench]
+    fn two_slices_big_u64_scalar(bench: &mut Bencher) {
+        let a: Vec<u64> = (0..10_000).filter(|x| x % 3 == 0).collect();
+        let b: Vec<u64> = (0..10_000).filter(|x| x % 5 == 0).collect();
+
+        bench.iter(|| {
+            let (a, b) = (Set::new_unchecked(&a[..]), Set::new_unchecked(&b[..]));
+            let intersection_: SetBuf<u64> = Intersection::new(a, b).into_set_buf();
+            test::black_box(|| intersection_);
+        });
+    }
+}
@@ -23,26 +23,50 @@ use crate::set::Set;
 mod union;
 mod difference;
 mod difference_by_key;
+mod difference_compressed;
 mod intersection;
+mod intersection_by_key;
+mod intersection_compressed;
+mod intersection_simd;
+mod strategy;
 mod symmetric_difference;
+mod symmetric_difference_by_key;
+mod union_by_key;
+mod union_compressed;
 
 pub use self::union::Union;
 pub use self::difference::Difference;
 pub use self::difference_by_key::DifferenceByKey;
+pub use self::difference_compressed::DifferenceCompressed;
 pub use self::intersection::Intersection;
+pub use self::intersection_by_key::IntersectionByKey;
+pub use self::intersection_compressed::IntersectionCompressed;
+pub use self::intersection_simd::{IntersectionSimd, SimdElement};
+pub use self::strategy::Strategy;
 pub use self::symmetric_difference::SymmetricDifference;
+pub use self::symmetric_difference_by_key::SymmetricDifferenceByKey;
+pub use self::union_by_key::UnionByKey;
+pub use self::union_compressed::UnionCompressed;
 
 /// Type used to make a set operation on two slices only.
 #[derive(Copy, Clone)]
 pub struct OpBuilder<'a, T: 'a> {
     a: &'a Set<T>,
     b: &'a Set<T>,
+    strategy: Strategy,
 }
 
 impl<'a, T> OpBuilder<'a, T> {
     /// Construct a type with two slices.
     pub fn new(a: &'a Set<T>, b: &'a Set<T>) -> Self {
-        Self { a, b }
+        Self { a, b, strategy: Strategy::Auto }
+    }
+
+    /// Force the algorithm used by the _intersection_ and _difference_ set operations,
+    /// by default it is chosen depending on the slices lengths.
+    pub fn strategy(mut self, strategy: Strategy) -> Self {
+        self.strategy = strategy;
+        self
     }
 
     /// Prepare the two slices for the _union_ set operation.
@@ -52,12 +76,12 @@ impl<'a, T> OpBuilder<'a, T> {
 
     /// Prepare the two slices for the _intersection_ set operation.
     pub fn intersection(self) -> Intersection<'a, T> {
-        Intersection::new(self.a, self.b)
+        Intersection::new(self.a, self.b).strategy(self.strategy)
     }
 
     /// Prepare the two slices for the _difference_ set operation.
     pub fn difference(self) -> Difference<'a, T> {
-        Difference::new(self.a, self.b)
+        Difference::new(self.a, self.b).strategy(self.strategy)
     }
 
     /// Prepare the two slices for the _difference_ set operation.
@@ -66,6 +90,44 @@ impl<'a, T> OpBuilder<'a, T> {
     }
 }
 
+impl<'a, T: SimdElement> OpBuilder<'a, T> {
+    /// Prepare the two slices for the _intersection_ set operation,
+    /// using SIMD instructions when the CPU supports them.
+    pub fn intersection_simd(self) -> IntersectionSimd<'a, T> {
+        IntersectionSimd::new(self.a, self.b)
+    }
+}
+
+/// Type used to make a set operation on two compressed sets,
+/// see [`CompressedSet`](crate::codec::CompressedSet) for the types that can be used.
+#[derive(Copy, Clone)]
+pub struct OpBuilderCompressed<'a, A: 'a, B: 'a> {
+    a: &'a A,
+    b: &'a B,
+}
+
+impl<'a, A, B> OpBuilderCompressed<'a, A, B> {
+    /// Construct a type with two compressed sets.
+    pub fn new(a: &'a A, b: &'a B) -> Self {
+        Self { a, b }
+    }
+
+    /// Prepare the two sets for the _union_ set operation.
+    pub fn union(self) -> UnionCompressed<'a, A, B> {
+        UnionCompressed::new(self.a, self.b)
+    }
+
+    /// Prepare the two sets for the _intersection_ set operation.
+    pub fn intersection(self) -> IntersectionCompressed<'a, A, B> {
+        IntersectionCompressed::new(self.a, self.b)
+    }
+
+    /// Prepare the two sets for the _difference_ set operation.
+    pub fn difference(self) -> DifferenceCompressed<'a, A, B> {
+        DifferenceCompressed::new(self.a, self.b)
+    }
+}
+
 /// Type used to make a set operation on two slices of different types.
 ///
 /// The two functions are used to generate a key that will be used to
@@ -96,4 +158,29 @@ where F: Fn(&T) -> K,
     pub fn difference(self) -> DifferenceByKey<'a, T, U, F, G, K> {
         DifferenceByKey::new(self.a, self.b, self.f, self.g)
     }
+
+    /// Prepare the two slices for the _intersection_ set operation.
+    ///
+    /// Only the elements of the first slice are emitted.
+    pub fn intersection(self) -> IntersectionByKey<'a, T, U, F, G, K> {
+        IntersectionByKey::new(self.a, self.b, self.f, self.g)
+    }
+}
+
+impl<'a, T, F, G, K> OpBuilderByKey<'a, T, T, F, G, K>
+where F: Fn(&T) -> K,
+      G: Fn(&T) -> K,
+      K: Ord,
+{
+    /// Prepare the two slices for the _union_ set operation.
+    ///
+    /// When a key is present in both slices, the elements of the first slice are emitted.
+    pub fn union(self) -> UnionByKey<'a, T, F, G, K> {
+        UnionByKey::new(self.a, self.b, self.f, self.g)
+    }
+
+    /// Prepare the two slices for the _symmetric difference_ set operation.
+    pub fn symmetric_difference(self) -> SymmetricDifferenceByKey<'a, T, F, G, K> {
+        SymmetricDifferenceByKey::new(self.a, self.b, self.f, self.g)
+    }
 }
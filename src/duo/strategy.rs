@@ -0,0 +1,80 @@
+use std::cmp;
+
+/// Under this ratio between the slices lengths, merging them linearly is the fastest.
+const LINEAR_MAX_RATIO: usize = 4;
+
+/// Over this ratio between the slices lengths, binary searching
+/// each element of the smallest slice is the fastest.
+const BINARY_SEARCH_MIN_RATIO: usize = 16_384;
+
+/// The algorithm used to find the common elements of two slices.
+///
+/// The _intersection_ and _difference_ set operations use it when
+/// extending a collection or counting, iterating over them always merges the slices.
+///
+/// # Examples
+/// ```
+/// # use sdset::Error;
+/// # fn try_main() -> Result<(), Error> {
+/// use sdset::duo::{OpBuilder, Strategy};
+/// use sdset::{SetOperation, Set, SetBuf};
+///
+/// let a = Set::new(&[4, 7])?;
+/// let b = Set::new(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12])?;
+///
+/// let op = OpBuilder::new(a, b).strategy(Strategy::BinarySearch).intersection();
+///
+/// let res: SetBuf<i32> = op.into_set_buf();
+/// assert_eq!(&res[..], &[4, 7]);
+/// # Ok(()) }
+/// # try_main().unwrap();
+/// ```
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum Strategy {
+    /// Choose one of the other strategies depending on the slices lengths.
+    #[default]
+    Auto,
+    /// Advance in both slices one element at a time,
+    /// best when the slices have similar lengths.
+    Linear,
+    /// Use exponential searches to skip parts of the slices.
+    Galloping,
+    /// Binary search each element of the smallest slice into the biggest one,
+    /// best when one slice is much smaller than the other.
+    BinarySearch,
+}
+
+impl Strategy {
+    /// Resolve the `Auto` strategy using the lengths of the two slices.
+    pub(super) fn select(self, a: usize, b: usize) -> Strategy {
+        if self != Strategy::Auto { return self }
+
+        let min = cmp::min(a, b);
+        let max = cmp::max(a, b);
+
+        if max <= min.saturating_mul(LINEAR_MAX_RATIO) {
+            Strategy::Linear
+        }
+        else if max >= min.saturating_mul(BINARY_SEARCH_MIN_RATIO) {
+            Strategy::BinarySearch
+        }
+        else {
+            Strategy::Galloping
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn select() {
+        assert_eq!(Strategy::Auto.select(100, 120), Strategy::Linear);
+        assert_eq!(Strategy::Auto.select(100, 0), Strategy::BinarySearch);
+        assert_eq!(Strategy::Auto.select(10, 1000), Strategy::Galloping);
+        assert_eq!(Strategy::Auto.select(1_000_000, 10), Strategy::BinarySearch);
+        assert_eq!(Strategy::Auto.select(1_000_000, 1_000), Strategy::Galloping);
+        assert_eq!(Strategy::Linear.select(1_000_000, 10), Strategy::Linear);
+    }
+}
@@ -1,6 +1,7 @@
 use std::cmp::Ordering;
 use crate::set::Set;
-use crate::{SetOperation, Collection};
+use crate::{SetOperation, SeekableIterator, Collection, Cardinality, exponential_offset_ge};
+use super::Intersection;
 
 /// Represent the _symmetric difference_ set operation that will be applied to two slices.
 ///
@@ -103,6 +104,15 @@ impl<'a, T: Ord> SetOperation<&'a T> for SymmetricDifference<'a, T> {
     }
 }
 
+impl<'a, T: Ord> Cardinality for SymmetricDifference<'a, T> {
+    fn count(self) -> usize {
+        let a = Set::new_unchecked(self.a);
+        let b = Set::new_unchecked(self.b);
+        let intersection = Intersection::new(a, b).count();
+        self.a.len() + self.b.len() - 2 * intersection
+    }
+}
+
 impl<'a, T: Ord> IntoIterator for SymmetricDifference<'a, T> {
     type Item = &'a T;
     type IntoIter = SymmetricDifferenceIter<'a, T>;
@@ -161,6 +171,47 @@ impl<'a, T: Ord> Iterator for SymmetricDifferenceIter<'a, T> {
     }
 }
 
+impl<'a, T: Ord> DoubleEndedIterator for SymmetricDifferenceIter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        loop {
+            match (self.a.split_last(), self.b.split_last()) {
+                (Some((last_a, a)), Some((last_b, b))) => {
+                    match last_a.cmp(last_b) {
+                        Ordering::Less => {
+                            self.b = b;
+                            return Some(last_b);
+                        },
+                        Ordering::Equal => {
+                            self.a = a;
+                            self.b = b;
+                        },
+                        Ordering::Greater => {
+                            self.a = a;
+                            return Some(last_a);
+                        },
+                    }
+                },
+                (Some((last_a, a)), None) => {
+                    self.a = a;
+                    return Some(last_a);
+                },
+                (None, Some((last_b, b))) => {
+                    self.b = b;
+                    return Some(last_b);
+                },
+                (None, None) => return None,
+            }
+        }
+    }
+}
+
+impl<'a, T: Ord> SeekableIterator<T> for SymmetricDifferenceIter<'a, T> {
+    fn advance_to(&mut self, target: &T) {
+        self.a = exponential_offset_ge(self.a, target);
+        self.b = exponential_offset_ge(self.b, target);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     mod set_to_set {
@@ -168,6 +219,21 @@ mod tests {
         use crate::set::{sort_dedup_vec, SetBuf};
 
         quickcheck! {
+            fn qc_count(a: Vec<i32>, b: Vec<i32>) -> bool {
+                use crate::{Cardinality, Counter};
+
+                let mut a = a;
+                let mut b = b;
+
+                sort_dedup_vec(&mut a);
+                sort_dedup_vec(&mut b);
+
+                let mut counter = Counter::<i32>::new();
+                SetOperation::extend_collection(SymmetricDifference { a: &a, b: &b }, &mut counter).unwrap();
+
+                SymmetricDifference { a: &a, b: &b }.count() == counter.get()
+            }
+
             fn qc_symmetric_difference(a: Vec<i32>, b: Vec<i32>) -> bool {
                 use std::collections::BTreeSet;
                 use std::iter::FromIterator;
@@ -213,6 +279,49 @@ mod tests {
 
                 x.as_slice() == y.as_slice()
             }
+
+            fn qc_rev_symmetric_difference(a: Vec<i32>, b: Vec<i32>) -> bool {
+                use std::collections::BTreeSet;
+                use std::iter::FromIterator;
+
+                let mut a = a;
+                let mut b = b;
+
+                sort_dedup_vec(&mut a);
+                sort_dedup_vec(&mut b);
+
+                let x: Vec<i32> = SymmetricDifference { a: &a, b: &b }.into_iter().rev().cloned().collect();
+
+                let a = BTreeSet::from_iter(a);
+                let b = BTreeSet::from_iter(b);
+                let y = a.symmetric_difference(&b);
+                let y: Vec<_> = y.cloned().collect();
+
+                x.iter().eq(y.iter().rev())
+            }
+
+            fn qc_advance_to(a: Vec<i32>, b: Vec<i32>, target: i32) -> bool {
+                use std::collections::BTreeSet;
+                use std::iter::FromIterator;
+                use crate::SeekableIterator;
+
+                let mut a = a;
+                let mut b = b;
+
+                sort_dedup_vec(&mut a);
+                sort_dedup_vec(&mut b);
+
+                let mut iter = SymmetricDifference { a: &a, b: &b }.into_iter();
+                iter.advance_to(&target);
+                let x: Vec<i32> = iter.cloned().collect();
+
+                let a = BTreeSet::from_iter(a);
+                let b = BTreeSet::from_iter(b);
+                let y = a.symmetric_difference(&b);
+                let y: Vec<_> = y.cloned().filter(|&y| y >= target).collect();
+
+                x.as_slice() == y.as_slice()
+            }
         }
     }
 }
@@ -1,6 +1,7 @@
 use std::cmp::{self, Ordering};
 use crate::set::Set;
-use crate::{SetOperation, Collection};
+use crate::{SetOperation, SeekableIterator, Collection, Cardinality, exponential_offset_ge};
+use super::Intersection;
 
 /// Represent the _union_ set operation that will be applied to two slices.
 ///
@@ -50,7 +51,7 @@ impl<'a, T: Ord> Union<'a, T> {
             let first_a = &self.a[0];
             let first_b = &self.b[0];
 
-            match first_a.cmp(&first_b) {
+            match first_a.cmp(first_b) {
                  Ordering::Less => {
                     let off = self.a.iter().take_while(|&x| x < first_b).count();
                     extend(output, &self.a[..off])?;
@@ -103,6 +104,15 @@ impl<'a, T: Ord> SetOperation<&'a T> for Union<'a, T> {
     }
 }
 
+impl<'a, T: Ord> Cardinality for Union<'a, T> {
+    fn count(self) -> usize {
+        let a = Set::new_unchecked(self.a);
+        let b = Set::new_unchecked(self.b);
+        let intersection = Intersection::new(a, b).count();
+        self.a.len() + self.b.len() - intersection
+    }
+}
+
 impl<'a, T: Ord> IntoIterator for Union<'a, T> {
     type Item = &'a T;
     type IntoIter = UnionIter<'a, T>;
@@ -145,25 +155,66 @@ impl<'a, T: Ord> Iterator for UnionIter<'a, T> {
         let first_a = &self.a[0];
         let first_b = &self.b[0];
 
-        match first_a.cmp(&first_b) {
+        match first_a.cmp(first_b) {
             Ordering::Less => {
                 self.a = &self.a[1..];
-                return Some(first_a);
+                Some(first_a)
             },
             Ordering::Equal => {
                 self.a = &self.a[1..];
                 self.b = &self.b[1..];
-                return Some(first_a);
+                Some(first_a)
             },
             Ordering::Greater => {
                 self.b = &self.b[1..];
-                return Some(first_b);
+                Some(first_b)
+            },
+        }
+    }
+}
+
+impl<'a, T: Ord> DoubleEndedIterator for UnionIter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        match (self.a.split_last(), self.b.split_last()) {
+            (Some((last_a, a)), Some((last_b, b))) => {
+                match last_a.cmp(last_b) {
+                    Ordering::Less => {
+                        self.b = b;
+                        Some(last_b)
+                    },
+                    Ordering::Equal => {
+                        self.a = a;
+                        self.b = b;
+                        Some(last_a)
+                    },
+                    Ordering::Greater => {
+                        self.a = a;
+                        Some(last_a)
+                    },
+                }
+            },
+            (Some((last_a, a)), None) => {
+                self.a = a;
+                Some(last_a)
+            },
+            (None, Some((last_b, b))) => {
+                self.b = b;
+                Some(last_b)
             },
+            (None, None) => None,
         }
     }
 }
 
+impl<'a, T: Ord> SeekableIterator<T> for UnionIter<'a, T> {
+    fn advance_to(&mut self, target: &T) {
+        self.a = exponential_offset_ge(self.a, target);
+        self.b = exponential_offset_ge(self.b, target);
+    }
+}
+
 #[cfg(test)]
+#[allow(clippy::redundant_field_names)]
 mod tests {
     mod set_to_set {
         use super::super::*;
@@ -210,6 +261,21 @@ mod tests {
         }
 
         quickcheck! {
+            fn qc_count(a: Vec<i32>, b: Vec<i32>) -> bool {
+                use crate::{Cardinality, Counter};
+
+                let mut a = a;
+                let mut b = b;
+
+                sort_dedup_vec(&mut a);
+                sort_dedup_vec(&mut b);
+
+                let mut counter = Counter::<i32>::new();
+                SetOperation::extend_collection(Union { a: &a, b: &b }, &mut counter).unwrap();
+
+                Union { a: &a, b: &b }.count() == counter.get()
+            }
+
             fn qc_union(a: Vec<i32>, b: Vec<i32>) -> bool {
                 use std::collections::BTreeSet;
                 use std::iter::FromIterator;
@@ -296,6 +362,49 @@ mod tests {
 
                 x.as_slice() == y.as_slice()
             }
+
+            fn qc_rev_union(a: Vec<i32>, b: Vec<i32>) -> bool {
+                use std::collections::BTreeSet;
+                use std::iter::FromIterator;
+
+                let mut a = a;
+                let mut b = b;
+
+                sort_dedup_vec(&mut a);
+                sort_dedup_vec(&mut b);
+
+                let x: Vec<i32> = Union { a: &a, b: &b }.into_iter().rev().cloned().collect();
+
+                let a = BTreeSet::from_iter(a);
+                let b = BTreeSet::from_iter(b);
+                let y = a.union(&b);
+                let y: Vec<_> = y.cloned().collect();
+
+                x.iter().eq(y.iter().rev())
+            }
+
+            fn qc_advance_to(a: Vec<i32>, b: Vec<i32>, target: i32) -> bool {
+                use std::collections::BTreeSet;
+                use std::iter::FromIterator;
+                use crate::SeekableIterator;
+
+                let mut a = a;
+                let mut b = b;
+
+                sort_dedup_vec(&mut a);
+                sort_dedup_vec(&mut b);
+
+                let mut iter = Union { a: &a, b: &b }.into_iter();
+                iter.advance_to(&target);
+                let x: Vec<i32> = iter.cloned().collect();
+
+                let a = BTreeSet::from_iter(a);
+                let b = BTreeSet::from_iter(b);
+                let y = a.union(&b);
+                let y: Vec<_> = y.cloned().filter(|&y| y >= target).collect();
+
+                x.as_slice() == y.as_slice()
+            }
         }
     }
 }
@@ -339,4 +448,31 @@ mod bench {
             test::black_box(|| union_);
         });
     }
+
+    #[bench]
+    fn two_slices_big_counter(bench: &mut Bencher) {
+        use crate::Counter;
+
+        let a: Vec<_> = (0..100).collect();
+        let b: Vec<_> = (51..151).collect();
+
+        bench.iter(|| {
+            let mut counter = Counter::<i32>::new();
+            SetOperation::extend_collection(Union { a: &a, b: &b }, &mut counter).unwrap();
+            test::black_box(|| counter.get());
+        });
+    }
+
+    #[bench]
+    fn two_slices_big_count(bench: &mut Bencher) {
+        use crate::Cardinality;
+
+        let a: Vec<_> = (0..100).collect();
+        let b: Vec<_> = (51..151).collect();
+
+        bench.iter(|| {
+            let union_ = Union { a: &a, b: &b }.count();
+            test::black_box(|| union_);
+        });
+    }
 }
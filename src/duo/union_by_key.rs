@@ -0,0 +1,496 @@
+use std::cmp::Ordering;
+use crate::set::Set;
+use crate::{exponential_offset_ge_by_key, SetOperation, SeekableIterator, Collection};
+
+/// Represent the _union_ set operation that will be applied to two slices
+/// compared using a key extracted from each element.
+///
+/// When a key is present in both slices only the elements of the first slice (`a`)
+/// are emitted, the elements of the second slice (`b`) with the same key are skipped.
+/// That is the reason why both slices must contain elements of the same type.
+///
+/// # Examples
+/// ```
+/// # use sdset::Error;
+/// # fn try_main() -> Result<(), Error> {
+/// use sdset::duo::OpBuilderByKey;
+/// use sdset::{SetOperation, Set, SetBuf};
+///
+/// let a = Set::new(&[(1, 'a'), (1, 'b'), (3, 'a')])?;
+/// let b = Set::new(&[(1, 'z'), (2, 'z'), (4, 'z')])?;
+///
+/// // only use the first field of the tuples for comparison
+/// let f = |x: &(i32, char)| x.0;
+/// let g = |x: &(i32, char)| x.0;
+///
+/// let op = OpBuilderByKey::new(a, b, f, g).union();
+/// let res: SetBuf<(i32, char)> = op.into_set_buf();
+///
+/// assert_eq!(res.as_slice(), &[(1, 'a'), (1, 'b'), (2, 'z'), (3, 'a'), (4, 'z')]);
+/// # Ok(()) }
+/// # try_main().unwrap();
+/// ```
+#[derive(Copy, Clone)]
+pub struct UnionByKey<'a, T, F, G, K>
+where
+    T: 'a,
+    F: Fn(&T) -> K,
+    G: Fn(&T) -> K,
+    K: Ord,
+{
+    a: &'a [T],
+    b: &'a [T],
+    f: F,
+    g: G,
+}
+
+impl<'a, T, F, G, K> UnionByKey<'a, T, F, G, K>
+where
+    T: 'a,
+    F: Fn(&T) -> K,
+    G: Fn(&T) -> K,
+    K: Ord,
+{
+    /// Construct one with slices checked to be sorted and deduplicated.
+    pub fn new(a: &'a Set<T>, b: &'a Set<T>, f: F, g: G) -> Self {
+        Self {
+            a: a.as_slice(),
+            b: b.as_slice(),
+            f,
+            g,
+        }
+    }
+}
+
+impl<'a, T, F, G, K> UnionByKey<'a, T, F, G, K>
+where F: Fn(&T) -> K,
+      G: Fn(&T) -> K,
+      K: Ord,
+{
+    fn extend_collection<C, X, E>(mut self, output: &mut C, extend: E) -> Result<(), C::Error>
+    where C: Collection<X>,
+          E: Fn(&mut C, &'a [T]) -> Result<(), C::Error>,
+    {
+        output.reserve(self.a.len().max(self.b.len()))?;
+
+        while let (Some(first_a), Some(first_b)) = (self.a.first(), self.b.first()) {
+            let key_a = (self.f)(first_a);
+            let key_b = (self.g)(first_b);
+
+            match key_a.cmp(&key_b) {
+                Ordering::Less => {
+                    let off = self.a.iter().take_while(|&x| (self.f)(x) < key_b).count();
+                    extend(output, &self.a[..off])?;
+
+                    self.a = &self.a[off..];
+                },
+                Ordering::Equal => {
+                    let off = self.a.iter().take_while(|&x| (self.f)(x) == key_a).count();
+                    extend(output, &self.a[..off])?;
+
+                    self.a = &self.a[off..];
+
+                    let off = self.b.iter().take_while(|&x| (self.g)(x) == key_b).count();
+                    self.b = &self.b[off..];
+                },
+                Ordering::Greater => {
+                    let off = self.b.iter().take_while(|&x| (self.g)(x) < key_a).count();
+                    extend(output, &self.b[..off])?;
+
+                    self.b = &self.b[off..];
+                },
+            }
+        }
+
+        extend(output, self.a)?;
+        extend(output, self.b)?;
+        Ok(())
+    }
+
+    fn iter(&'a self) -> UnionByKeyIter<'a, T, F, G, K>
+    {
+        UnionByKeyIter {
+            a: self.a,
+            b: self.b,
+            f: &self.f,
+            g: &self.g,
+        }
+    }
+}
+
+impl<'a, T, F, G, K> SetOperation<T> for UnionByKey<'a, T, F, G, K>
+where T: Clone,
+      F: Fn(&T) -> K,
+      G: Fn(&T) -> K,
+      K: Ord,
+{
+    fn extend_collection<C>(self, output: &mut C) -> Result<(), C::Error>
+    where C: Collection<T>,
+    {
+        self.extend_collection(output, Collection::extend_from_slice)
+    }
+}
+
+impl<'a, T, F, G, K> SetOperation<&'a T> for UnionByKey<'a, T, F, G, K>
+where F: Fn(&T) -> K,
+      G: Fn(&T) -> K,
+      K: Ord,
+{
+    fn extend_collection<C>(self, output: &mut C) -> Result<(), C::Error>
+    where C: Collection<&'a T>,
+    {
+        self.extend_collection(output, Collection::extend)
+    }
+}
+
+// This version of IntoIterator takes references to the functions (f/g).
+// See DifferenceByKey for the reason of the separate iterator structs.
+impl<'a, T, F, G, K> IntoIterator for &'a UnionByKey<'a, T, F, G, K>
+where F: Fn(&T) -> K,
+      G: Fn(&T) -> K,
+      K: Ord,
+{
+    type Item = &'a T;
+    type IntoIter = UnionByKeyIter<'a, T, F, G, K>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct UnionByKeyIter<'a, T, F, G, K>
+where
+    T: 'a,
+    F: Fn(&T) -> K,
+    G: Fn(&T) -> K,
+    K: Ord,
+{
+    a: &'a [T],
+    b: &'a [T],
+    f: &'a F,
+    g: &'a G,
+}
+
+impl<'a, T, F, G, K> Iterator for UnionByKeyIter<'a, T, F, G, K>
+where
+    T: 'a,
+    F: Fn(&T) -> K,
+    G: Fn(&T) -> K,
+    K: Ord,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        next_by_key(&mut self.a, &mut self.b, self.f, self.g)
+    }
+}
+
+impl<'a, T, F, G, K> DoubleEndedIterator for UnionByKeyIter<'a, T, F, G, K>
+where
+    T: 'a,
+    F: Fn(&T) -> K,
+    G: Fn(&T) -> K,
+    K: Ord,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        next_back_by_key(&mut self.a, &mut self.b, self.f, self.g)
+    }
+}
+
+impl<'a, T, F, G, K> SeekableIterator<K> for UnionByKeyIter<'a, T, F, G, K>
+where
+    T: 'a,
+    F: Fn(&T) -> K,
+    G: Fn(&T) -> K,
+    K: Ord,
+{
+    fn advance_to(&mut self, target: &K) {
+        self.a = exponential_offset_ge_by_key(self.a, target, self.f);
+        self.b = exponential_offset_ge_by_key(self.b, target, self.g);
+    }
+}
+
+// This version of IntoIterator moves the contents of self into the iterator.
+// Therefore the iterator owns the functions (f/g).
+impl<'a, T, F, G, K> IntoIterator for UnionByKey<'a, T, F, G, K>
+where F: Fn(&T) -> K + 'a,
+      G: Fn(&T) -> K + 'a,
+      K: Ord + 'a,
+{
+    type Item = &'a T;
+    type IntoIter = UnionByKeyIterOwning<'a, T, F, G, K>;
+    fn into_iter(self) -> Self::IntoIter {
+        Self::IntoIter {
+            a: self.a,
+            b: self.b,
+            f: self.f,
+            g: self.g,
+        }
+    }
+}
+
+pub struct UnionByKeyIterOwning<'a, T, F, G, K>
+where
+    T: 'a,
+    F: Fn(&T) -> K,
+    G: Fn(&T) -> K,
+    K: Ord,
+{
+    a: &'a [T],
+    b: &'a [T],
+    f: F,
+    g: G,
+}
+
+impl<'a, T, F, G, K> Iterator for UnionByKeyIterOwning<'a, T, F, G, K>
+where
+    T: 'a,
+    F: Fn(&T) -> K,
+    G: Fn(&T) -> K,
+    K: Ord,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        next_by_key(&mut self.a, &mut self.b, &self.f, &self.g)
+    }
+}
+
+impl<'a, T, F, G, K> DoubleEndedIterator for UnionByKeyIterOwning<'a, T, F, G, K>
+where
+    T: 'a,
+    F: Fn(&T) -> K,
+    G: Fn(&T) -> K,
+    K: Ord,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        next_back_by_key(&mut self.a, &mut self.b, &self.f, &self.g)
+    }
+}
+
+impl<'a, T, F, G, K> SeekableIterator<K> for UnionByKeyIterOwning<'a, T, F, G, K>
+where
+    T: 'a,
+    F: Fn(&T) -> K,
+    G: Fn(&T) -> K,
+    K: Ord,
+{
+    fn advance_to(&mut self, target: &K) {
+        self.a = exponential_offset_ge_by_key(self.a, target, &self.f);
+        self.b = exponential_offset_ge_by_key(self.b, target, &self.g);
+    }
+}
+
+#[inline]
+fn next_by_key<'a, T, F, G, K>(a: &mut &'a [T], b: &mut &'a [T], f: &F, g: &G) -> Option<&'a T>
+where F: Fn(&T) -> K,
+      G: Fn(&T) -> K,
+      K: Ord,
+{
+    match (a.first(), b.first()) {
+        (Some(first_a), Some(first_b)) => {
+            let key_a = f(first_a);
+            match key_a.cmp(&g(first_b)) {
+                Ordering::Less => {
+                    *a = &a[1..];
+                    Some(first_a)
+                },
+                Ordering::Equal => {
+                    // skip the b elements now, the following
+                    // a elements with the same key will be emitted as lesser
+                    let off = b.iter().take_while(|&x| g(x) == key_a).count();
+                    *b = &b[off..];
+                    *a = &a[1..];
+                    Some(first_a)
+                },
+                Ordering::Greater => {
+                    *b = &b[1..];
+                    Some(first_b)
+                },
+            }
+        },
+        (Some(first_a), None) => {
+            *a = &a[1..];
+            Some(first_a)
+        },
+        (None, Some(first_b)) => {
+            *b = &b[1..];
+            Some(first_b)
+        },
+        (None, None) => None,
+    }
+}
+
+#[inline]
+fn next_back_by_key<'a, T, F, G, K>(a: &mut &'a [T], b: &mut &'a [T], f: &F, g: &G) -> Option<&'a T>
+where F: Fn(&T) -> K,
+      G: Fn(&T) -> K,
+      K: Ord,
+{
+    match (a.last(), b.last()) {
+        (Some(last_a), Some(last_b)) => {
+            let key_a = f(last_a);
+            match key_a.cmp(&g(last_b)) {
+                Ordering::Greater => {
+                    *a = &a[..a.len() - 1];
+                    Some(last_a)
+                },
+                Ordering::Equal => {
+                    // skip the b elements now, the previous
+                    // a elements with the same key will be emitted as greater
+                    let off = b.iter().rev().take_while(|&x| g(x) == key_a).count();
+                    *b = &b[..b.len() - off];
+                    *a = &a[..a.len() - 1];
+                    Some(last_a)
+                },
+                Ordering::Less => {
+                    *b = &b[..b.len() - 1];
+                    Some(last_b)
+                },
+            }
+        },
+        (Some(last_a), None) => {
+            *a = &a[..a.len() - 1];
+            Some(last_a)
+        },
+        (None, Some(last_b)) => {
+            *b = &b[..b.len() - 1];
+            Some(last_b)
+        },
+        (None, None) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod set_to_set {
+        use super::super::*;
+        use crate::set::{sort_dedup_vec, SetBuf};
+
+        #[test]
+        fn union_duplicate_relations() {
+            let a = Set::new(&[(1, 'a'), (1, 'b'), (3, 'a'), (3, 'b')]).unwrap();
+            let b = Set::new(&[(0, 'z'), (1, 'y'), (1, 'z'), (2, 'z'), (3, 'z'), (5, 'z')]).unwrap();
+
+            let union: SetBuf<(i32, char)> = UnionByKey::new(a, b, |x| x.0, |x| x.0).into_set_buf();
+
+            assert_eq!(union.as_slice(), &[
+                (0, 'z'),
+                (1, 'a'),
+                (1, 'b'),
+                (2, 'z'),
+                (3, 'a'),
+                (3, 'b'),
+                (5, 'z'),
+            ]);
+        }
+
+        quickcheck! {
+            fn qc_union(a: Vec<i32>, b: Vec<i32>) -> bool {
+                use std::collections::BTreeSet;
+                use std::iter::FromIterator;
+
+                let mut a = a;
+                let mut b = b;
+
+                sort_dedup_vec(&mut a);
+                sort_dedup_vec(&mut b);
+
+                let x: SetBuf<i32> = UnionByKey { a: &a, b: &b, f: |&x| x, g: |&x| x }.into_set_buf();
+
+                let a = BTreeSet::from_iter(a);
+                let b = BTreeSet::from_iter(b);
+                let y = a.union(&b);
+                let y: Vec<_> = y.cloned().collect();
+
+                x.as_slice() == y.as_slice()
+            }
+        }
+    }
+
+    mod set_to_iter {
+        use super::super::*;
+        use crate::set::sort_dedup_vec;
+
+        #[test]
+        fn union_duplicate_relations() {
+            let a = Set::new(&[(1, 'a'), (1, 'b'), (3, 'a'), (3, 'b')]).unwrap();
+            let b = Set::new(&[(0, 'z'), (1, 'y'), (1, 'z'), (2, 'z'), (3, 'z'), (5, 'z')]).unwrap();
+
+            let union = UnionByKey::new(a, b, |x| x.0, |x| x.0);
+            let expected = &[
+                (0, 'z'),
+                (1, 'a'),
+                (1, 'b'),
+                (2, 'z'),
+                (3, 'a'),
+                (3, 'b'),
+                (5, 'z'),
+            ];
+
+            let union_ref: Vec<_> = union.iter().cloned().collect();
+            assert_eq!(union_ref.as_slice(), expected);
+
+            let union_own: Vec<_> = union.into_iter().cloned().collect();
+            assert_eq!(union_own.as_slice(), expected);
+        }
+
+        quickcheck! {
+            fn qc_advance_to(a: Vec<(i8, i8)>, b: Vec<(i8, i8)>, target: i8) -> bool {
+                let mut a = a;
+                let mut b = b;
+
+                sort_dedup_vec(&mut a);
+                sort_dedup_vec(&mut b);
+
+                let op = UnionByKey { a: &a, b: &b, f: |x: &(i8, i8)| x.0, g: |x: &(i8, i8)| x.0 };
+                let expected: Vec<_> = op.iter().filter(|x| x.0 >= target).collect();
+
+                let mut iter = op.iter();
+                iter.advance_to(&target);
+                let x: Vec<_> = iter.collect();
+
+                let mut iter = op.into_iter();
+                iter.advance_to(&target);
+                let y: Vec<_> = iter.collect();
+
+                x == expected && y == expected
+            }
+
+            fn qc_next_and_next_back(a: Vec<(i8, i8)>, b: Vec<(i8, i8)>, sides: Vec<bool>) -> bool {
+                use crate::collect_from_both_ends;
+
+                let mut a = a;
+                let mut b = b;
+
+                sort_dedup_vec(&mut a);
+                sort_dedup_vec(&mut b);
+
+                let op = UnionByKey { a: &a, b: &b, f: |x: &(i8, i8)| x.0, g: |x: &(i8, i8)| x.0 };
+                let expected: Vec<_> = op.iter().collect();
+
+                collect_from_both_ends(op.iter(), &sides) == expected
+                    && collect_from_both_ends(op.into_iter(), &sides) == expected
+            }
+
+            fn qc_union(a: Vec<i32>, b: Vec<i32>) -> bool {
+                use std::collections::BTreeSet;
+                use std::iter::FromIterator;
+
+                let mut a = a;
+                let mut b = b;
+
+                sort_dedup_vec(&mut a);
+                sort_dedup_vec(&mut b);
+
+                let x: Vec<i32> = UnionByKey { a: &a, b: &b, f: |&x| x, g: |&x| x }.into_iter().cloned().collect();
+
+                let a = BTreeSet::from_iter(a);
+                let b = BTreeSet::from_iter(b);
+                let y = a.union(&b);
+                let y: Vec<_> = y.cloned().collect();
+
+                x.as_slice() == y.as_slice()
+            }
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use std::cmp::Ordering;
+use crate::codec::CompressedSet;
+use crate::cursor::Cursor;
+use crate::{SetOperation, SeekableIterator, Collection};
+
+/// Represent the _union_ set operation that will be applied to two compressed sets.
+///
+/// # Examples
+/// ```
+/// # use sdset::Error;
+/// # fn try_main() -> Result<(), Error> {
+/// use sdset::codec::BlockSet;
+/// use sdset::duo::OpBuilderCompressed;
+/// use sdset::{SetOperation, Set, SetBuf};
+///
+/// let a = BlockSet::new(Set::new(&[1u32, 2, 4, 6, 7])?);
+/// let b = BlockSet::new(Set::new(&[2u32, 3, 4, 5, 6, 7])?);
+///
+/// let op = OpBuilderCompressed::new(&a, &b).union();
+///
+/// let res: SetBuf<u32> = op.into_set_buf();
+/// assert_eq!(&res[..], &[1, 2, 3, 4, 5, 6, 7]);
+/// # Ok(()) }
+/// # try_main().unwrap();
+/// ```
+#[derive(Copy, Clone)]
+pub struct UnionCompressed<'a, A: 'a, B: 'a> {
+    a: &'a A,
+    b: &'a B,
+}
+
+impl<'a, A, B> UnionCompressed<'a, A, B> {
+    /// Construct one with two compressed sets.
+    pub fn new(a: &'a A, b: &'a B) -> Self {
+        Self { a, b }
+    }
+}
+
+impl<'a, A, B> UnionCompressed<'a, A, B>
+where A: CompressedSet,
+      B: CompressedSet<Item = A::Item>,
+{
+    fn iter(&self) -> UnionCompressedIter<'a, A, B> {
+        UnionCompressedIter {
+            a: Cursor::new(self.a.iter()),
+            b: Cursor::new(self.b.iter()),
+        }
+    }
+}
+
+impl<'a, A, B> SetOperation<A::Item> for UnionCompressed<'a, A, B>
+where A: CompressedSet,
+      B: CompressedSet<Item = A::Item>,
+{
+    fn extend_collection<C>(self, output: &mut C) -> Result<(), C::Error>
+    where C: Collection<A::Item>,
+    {
+        output.reserve(self.a.len() + self.b.len())?;
+        output.extend(self)
+    }
+}
+
+impl<'a, A, B> IntoIterator for UnionCompressed<'a, A, B>
+where A: CompressedSet,
+      B: CompressedSet<Item = A::Item>,
+{
+    type Item = A::Item;
+    type IntoIter = UnionCompressedIter<'a, A, B>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, A, B> IntoIterator for &'a UnionCompressed<'a, A, B>
+where A: CompressedSet,
+      B: CompressedSet<Item = A::Item>,
+{
+    type Item = A::Item;
+    type IntoIter = UnionCompressedIter<'a, A, B>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct UnionCompressedIter<'a, A: CompressedSet + 'a, B: CompressedSet + 'a> {
+    a: Cursor<A::Iter<'a>>,
+    b: Cursor<B::Iter<'a>>,
+}
+
+impl<'a, A, B> Iterator for UnionCompressedIter<'a, A, B>
+where A: CompressedSet,
+      B: CompressedSet<Item = A::Item>,
+{
+    type Item = A::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match (self.a.peek(), self.b.peek()) {
+            (Some(a), Some(b)) => match a.cmp(b) {
+                Ordering::Less => self.a.bump(),
+                Ordering::Greater => self.b.bump(),
+                Ordering::Equal => {
+                    self.b.bump();
+                    self.a.bump()
+                },
+            },
+            (Some(_), None) => self.a.bump(),
+            (None, Some(_)) => self.b.bump(),
+            (None, None) => None,
+        }
+    }
+}
+
+impl<'a, A, B> SeekableIterator<A::Item> for UnionCompressedIter<'a, A, B>
+where A: CompressedSet,
+      B: CompressedSet<Item = A::Item>,
+{
+    fn advance_to(&mut self, target: &A::Item) {
+        self.a.advance_to(target);
+        self.b.advance_to(target);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec::{BlockSet, EliasFanoSet};
+    use crate::set::{sort_dedup_vec, Set};
+
+    quickcheck! {
+        fn qc_union(a: Vec<u64>, b: Vec<u64>) -> bool {
+            use std::collections::BTreeSet;
+            use std::iter::FromIterator;
+
+            let mut a = a;
+            let mut b = b;
+
+            sort_dedup_vec(&mut a);
+            sort_dedup_vec(&mut b);
+
+            let x: Vec<u64> = {
+                let a = BlockSet::new(Set::new_unchecked(&a));
+                let b = EliasFanoSet::new(Set::new_unchecked(&b));
+                UnionCompressed::new(&a, &b).into_set_buf().into_vec()
+            };
+
+            let a = BTreeSet::from_iter(a);
+            let b = BTreeSet::from_iter(b);
+            let y: Vec<_> = a.union(&b).cloned().collect();
+
+            x.as_slice() == y.as_slice()
+        }
+    }
+}
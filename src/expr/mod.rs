@@ -0,0 +1,139 @@
+//! Contains the types to compose set operations lazily.
+//!
+//! The operations of this module take sorted and deduplicated iterators as inputs,
+//! like the [`Set`](crate::Set) ones, the [`duo`](crate::duo) and [`multi`](crate::multi)
+//! operations or other operations of this module, and are iterators themselves.
+//! A tree of operations is evaluated in one streaming pass without any intermediate allocation,
+//! the _intersection_ and _difference_ use the [`SeekableIterator`](crate::SeekableIterator) of their inputs
+//! to skip the elements that cannot be part of the result.
+//!
+//! # Examples
+//! ```
+//! # use sdset::Error;
+//! # fn try_main() -> Result<(), Error> {
+//! use sdset::expr::OpBuilder;
+//! use sdset::{SetOperation, Set, SetBuf};
+//!
+//! let a = Set::new(&[1, 2, 4, 6, 7])?;
+//! let b = Set::new(&[2, 3, 5, 8])?;
+//! let c = Set::new(&[2, 3, 4, 5, 6, 7])?;
+//! let d = Set::new(&[4, 5])?;
+//!
+//! // (a ∪ b) ∩ (c − d)
+//! let union = OpBuilder::new(a, b).union();
+//! let difference = OpBuilder::new(c, d).difference();
+//! let op = OpBuilder::new(union, difference).intersection();
+//!
+//! let res: SetBuf<i32> = op.into_set_buf();
+//! assert_eq!(&res[..], &[2, 3, 6, 7]);
+//! # Ok(()) }
+//! # try_main().unwrap();
+//! ```
+
+mod union;
+mod difference;
+mod intersection;
+mod parse;
+mod symmetric_difference;
+
+pub use self::union::Union;
+pub use self::difference::Difference;
+pub use self::intersection::Intersection;
+pub use self::parse::{parse, Query, ParseError};
+pub use self::symmetric_difference::SymmetricDifference;
+
+/// Type used to make a lazy set operation on two sorted and deduplicated iterators.
+///
+/// The inputs can be anything that can be turned into such an iterator,
+/// the _intersection_ and _difference_ need their inputs iterators to be seekable.
+#[derive(Copy, Clone)]
+pub struct OpBuilder<A, B> {
+    a: A,
+    b: B,
+}
+
+impl<A: IntoIterator, B: IntoIterator> OpBuilder<A, B> {
+    /// Construct a type with two inputs.
+    pub fn new(a: A, b: B) -> Self {
+        Self { a, b }
+    }
+
+    /// Prepare the two inputs for the _union_ set operation.
+    pub fn union(self) -> Union<A::IntoIter, B::IntoIter> {
+        Union::new(self.a.into_iter(), self.b.into_iter())
+    }
+
+    /// Prepare the two inputs for the _intersection_ set operation.
+    pub fn intersection(self) -> Intersection<A::IntoIter, B::IntoIter> {
+        Intersection::new(self.a.into_iter(), self.b.into_iter())
+    }
+
+    /// Prepare the two inputs for the _difference_ set operation.
+    pub fn difference(self) -> Difference<A::IntoIter, B::IntoIter> {
+        Difference::new(self.a.into_iter(), self.b.into_iter())
+    }
+
+    /// Prepare the two inputs for the _symmetric difference_ set operation.
+    pub fn symmetric_difference(self) -> SymmetricDifference<A::IntoIter, B::IntoIter> {
+        SymmetricDifference::new(self.a.into_iter(), self.b.into_iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::set::{sort_dedup_vec, Set, SetBuf};
+    use crate::{duo, multi, SetOperation};
+
+    #[test]
+    fn nested_operations() {
+        let a = Set::new_unchecked(&[1, 2, 4, 6, 7, 9]);
+        let b = Set::new_unchecked(&[2, 3, 5, 8]);
+        let c = Set::new_unchecked(&[2, 3, 4, 5, 6, 7, 9]);
+        let d = Set::new_unchecked(&[4, 5]);
+        let e = Set::new_unchecked(&[6, 8, 9]);
+
+        // ((a ∪ b) ∩ (c − d)) ∆ (c ∩ e)
+        let union = duo::OpBuilder::new(a, b).union();
+        let difference = OpBuilder::new(c, d).difference();
+        let intersection = OpBuilder::new(union, difference).intersection();
+        let inner = multi::OpBuilder::from_vec(vec![c, e]).intersection();
+        let op = OpBuilder::new(intersection, inner).symmetric_difference();
+
+        let res: SetBuf<i32> = op.into_set_buf();
+        assert_eq!(&res[..], &[2, 3, 7]);
+    }
+
+    quickcheck! {
+        fn qc_nested_operations(a: Vec<i32>, b: Vec<i32>, c: Vec<i32>, d: Vec<i32>) -> bool {
+            use std::collections::BTreeSet;
+            use std::iter::FromIterator;
+
+            let mut a = a;
+            let mut b = b;
+            let mut c = c;
+            let mut d = d;
+
+            sort_dedup_vec(&mut a);
+            sort_dedup_vec(&mut b);
+            sort_dedup_vec(&mut c);
+            sort_dedup_vec(&mut d);
+
+            let x: Vec<i32> = {
+                let union = OpBuilder::new(&a, &b).union();
+                let difference = OpBuilder::new(&c, &d).difference();
+                OpBuilder::new(union, difference).intersection().cloned().collect()
+            };
+
+            let a = BTreeSet::from_iter(a);
+            let b = BTreeSet::from_iter(b);
+            let c = BTreeSet::from_iter(c);
+            let d = BTreeSet::from_iter(d);
+            let union: BTreeSet<_> = a.union(&b).cloned().collect();
+            let difference: BTreeSet<_> = c.difference(&d).cloned().collect();
+            let y: Vec<_> = union.intersection(&difference).cloned().collect();
+
+            x.as_slice() == y.as_slice()
+        }
+    }
+}
@@ -0,0 +1,472 @@
+use std::{error, fmt};
+use crate::set::Set;
+use crate::{SetOperation, SeekableIterator, Collection};
+use super::{Union, Intersection, Difference, SymmetricDifference};
+
+type BoxedIter<'a, T> = Box<dyn SeekableIterator<T, Item = &'a T> + 'a>;
+
+/// Parse a set expression and prepare it to be evaluated lazily,
+/// the names are resolved to [`Set`]s using the `lookup` function.
+///
+/// The operators are, from the loosest to the tightest binding,
+/// the _union_ `|`, the _symmetric difference_ `^`, the _intersection_ `&`
+/// and the _difference_ `-`, parentheses can be used to group operations.
+/// Names are made of alphanumeric characters, `_`, `.` and `:`.
+/// Parentheses can be nested up to 128 levels deep
+/// and an expression can contain up to 65536 names.
+///
+/// The operands of an _intersection_ are reordered to intersect the smallest ones first.
+///
+/// # Examples
+/// ```
+/// # fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+/// use std::collections::HashMap;
+/// use sdset::expr::parse;
+/// use sdset::{SetOperation, Set, SetBuf};
+///
+/// let mut sets = HashMap::new();
+/// sets.insert("a", Set::new(&[1, 2, 3, 4, 5, 6])?);
+/// sets.insert("b", Set::new(&[2, 3, 4])?);
+/// sets.insert("c", Set::new(&[5, 6, 7])?);
+/// sets.insert("d", Set::new(&[3, 6])?);
+///
+/// let query = parse("a & (b | c) - d", |name| sets.get(name).cloned())?;
+///
+/// let res: SetBuf<i32> = query.into_set_buf();
+/// assert_eq!(&res[..], &[2, 4, 5]);
+/// # Ok(()) }
+/// # try_main().unwrap();
+/// ```
+pub fn parse<'a, T, F>(query: &str, lookup: F) -> Result<Query<'a, T>, ParseError>
+where T: Ord + 'a,
+      F: FnMut(&str) -> Option<&'a Set<T>>,
+{
+    let mut parser = Parser { query, position: 0, depth: 0, operands: 0, lookup };
+    let node = parser.union()?;
+
+    match parser.peek() {
+        Some((position, found)) => {
+            let expected = "an operator";
+            Err(ParseError::UnexpectedChar { found, position, expected })
+        },
+        None => Ok(Query { iter: node.into_iter() }),
+    }
+}
+
+/// A parsed set expression, evaluated lazily by iterating over it
+/// or by using the [`SetOperation`] methods.
+pub struct Query<'a, T> {
+    iter: BoxedIter<'a, T>,
+}
+
+impl<'a, T> Iterator for Query<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+}
+
+impl<'a, T> SeekableIterator<T> for Query<'a, T> {
+    fn advance_to(&mut self, target: &T) {
+        self.iter.advance_to(target)
+    }
+}
+
+impl<'a, T: Clone> SetOperation<T> for Query<'a, T> {
+    fn extend_collection<C>(self, output: &mut C) -> Result<(), C::Error>
+    where C: Collection<T>,
+    {
+        for x in self {
+            output.push(x.clone())?;
+        }
+        Ok(())
+    }
+}
+
+/// Represent the possible errors when parsing a set expression.
+///
+/// The positions are byte offsets in the expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// Define that a name is not known by the lookup function.
+    UnknownName {
+        /// The unknown name.
+        name: String,
+        /// The position of the name.
+        position: usize,
+    },
+    /// Define that a character is not valid at this position.
+    UnexpectedChar {
+        /// The unexpected character.
+        found: char,
+        /// The position of the character.
+        position: usize,
+        /// A description of what was expected instead.
+        expected: &'static str,
+    },
+    /// Define that the expression ended too early.
+    UnexpectedEnd {
+        /// A description of what was expected.
+        expected: &'static str,
+    },
+    /// Define that the parentheses are nested too deeply.
+    TooDeep {
+        /// The position of the parenthesis exceeding the limit.
+        position: usize,
+    },
+    /// Define that the expression contains too many names.
+    TooManyOperands {
+        /// The position of the name exceeding the limit.
+        position: usize,
+    },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            ParseError::UnknownName { name, position } => {
+                write!(f, "unknown name `{}` at position {}.", name, position)
+            },
+            ParseError::UnexpectedChar { found, position, expected } => {
+                write!(f, "unexpected `{}` at position {}, expected {}.", found, position, expected)
+            },
+            ParseError::UnexpectedEnd { expected } => {
+                write!(f, "unexpected end of expression, expected {}.", expected)
+            },
+            ParseError::TooDeep { position } => {
+                write!(f, "parentheses nested too deeply at position {}.", position)
+            },
+            ParseError::TooManyOperands { position } => {
+                write!(f, "too many names at position {}.", position)
+            },
+        }
+    }
+}
+
+impl error::Error for ParseError {}
+
+/// A node of the expression tree, the operations are flattened
+/// to make it possible to reorder their operands.
+enum Node<'a, T> {
+    Set(&'a Set<T>),
+    Union(Vec<Node<'a, T>>),
+    Intersection(Vec<Node<'a, T>>),
+    SymmetricDifference(Vec<Node<'a, T>>),
+    Difference(Box<Node<'a, T>>, Vec<Node<'a, T>>),
+}
+
+impl<'a, T: Ord + 'a> Node<'a, T> {
+    /// Returns the maximum number of elements this node can produce.
+    fn estimate(&self) -> usize {
+        match self {
+            Node::Set(set) => set.len(),
+            Node::Union(nodes) | Node::SymmetricDifference(nodes) => {
+                nodes.iter().fold(0, |acc, n| acc.saturating_add(n.estimate()))
+            },
+            Node::Intersection(nodes) => nodes.iter().map(Node::estimate).min().unwrap_or(0),
+            Node::Difference(base, _) => base.estimate(),
+        }
+    }
+
+    fn into_iter(self) -> BoxedIter<'a, T> {
+        match self {
+            Node::Set(set) => Box::new(set.iter()),
+            Node::Union(nodes) => {
+                balanced(nodes, &|a, b| Box::new(Union::new(a, b)))
+            },
+            Node::Intersection(mut nodes) => {
+                // the smallest operands are intersected together and drive the other ones
+                nodes.sort_by_cached_key(Node::estimate);
+                balanced(nodes, &|a, b| Box::new(Intersection::new(a, b)))
+            },
+            Node::SymmetricDifference(nodes) => {
+                balanced(nodes, &|a, b| Box::new(SymmetricDifference::new(a, b)))
+            },
+            Node::Difference(base, others) => {
+                let others = balanced(others, &|a, b| Box::new(Union::new(a, b)));
+                Box::new(Difference::new(base.into_iter(), others))
+            },
+        }
+    }
+}
+
+/// Combine the nodes as a balanced tree, so that each element
+/// goes through a logarithmic number of operations.
+fn balanced<'a, T, F>(mut nodes: Vec<Node<'a, T>>, op: &F) -> BoxedIter<'a, T>
+where T: Ord + 'a,
+      F: Fn(BoxedIter<'a, T>, BoxedIter<'a, T>) -> BoxedIter<'a, T>,
+{
+    if nodes.len() == 1 {
+        return nodes.pop().unwrap().into_iter();
+    }
+
+    let right = nodes.split_off(nodes.len() / 2);
+    op(balanced(nodes, op), balanced(right, op))
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '.' || c == ':'
+}
+
+/// The maximum number of nested parentheses, the parser and
+/// the evaluation recurse on them and must not overflow the stack.
+const MAX_DEPTH: usize = 128;
+
+/// The maximum number of names, each one is an operation to build and evaluate.
+const MAX_OPERANDS: usize = 65_536;
+
+struct Parser<'q, F> {
+    query: &'q str,
+    position: usize,
+    depth: usize,
+    operands: usize,
+    lookup: F,
+}
+
+impl<'a, 'q, T, F> Parser<'q, F>
+where T: Ord + 'a,
+      F: FnMut(&str) -> Option<&'a Set<T>>,
+{
+    /// Returns the next character that is not a whitespace along with its position.
+    fn peek(&mut self) -> Option<(usize, char)> {
+        let rest = &self.query[self.position..];
+        let trimmed = rest.trim_start();
+        self.position += rest.len() - trimmed.len();
+        trimmed.chars().next().map(|c| (self.position, c))
+    }
+
+    /// Consume the next character if it is the given operator.
+    fn eat(&mut self, operator: char) -> bool {
+        match self.peek() {
+            Some((_, c)) if c == operator => {
+                self.position += c.len_utf8();
+                true
+            },
+            _ => false,
+        }
+    }
+
+    fn union(&mut self) -> Result<Node<'a, T>, ParseError> {
+        let mut nodes = vec![self.symmetric_difference()?];
+        while self.eat('|') {
+            nodes.push(self.symmetric_difference()?);
+        }
+        Ok(flatten(nodes, Node::Union, |n| match n { Node::Union(ns) => Ok(ns), n => Err(n) }))
+    }
+
+    fn symmetric_difference(&mut self) -> Result<Node<'a, T>, ParseError> {
+        let mut nodes = vec![self.intersection()?];
+        while self.eat('^') {
+            nodes.push(self.intersection()?);
+        }
+        Ok(flatten(nodes, Node::SymmetricDifference, |n| match n { Node::SymmetricDifference(ns) => Ok(ns), n => Err(n) }))
+    }
+
+    fn intersection(&mut self) -> Result<Node<'a, T>, ParseError> {
+        let mut nodes = vec![self.difference()?];
+        while self.eat('&') {
+            nodes.push(self.difference()?);
+        }
+        Ok(flatten(nodes, Node::Intersection, |n| match n { Node::Intersection(ns) => Ok(ns), n => Err(n) }))
+    }
+
+    fn difference(&mut self) -> Result<Node<'a, T>, ParseError> {
+        let base = self.primary()?;
+        let mut others = Vec::new();
+        while self.eat('-') {
+            others.push(self.primary()?);
+        }
+
+        if others.is_empty() {
+            return Ok(base);
+        }
+
+        match base {
+            Node::Difference(base, mut first_others) => {
+                Extend::extend(&mut first_others, others);
+                Ok(Node::Difference(base, first_others))
+            },
+            base => Ok(Node::Difference(Box::new(base), others)),
+        }
+    }
+
+    fn primary(&mut self) -> Result<Node<'a, T>, ParseError> {
+        let expected = "a name or `(`";
+        let (position, c) = match self.peek() {
+            Some(next) => next,
+            None => return Err(ParseError::UnexpectedEnd { expected }),
+        };
+
+        if c == '(' {
+            if self.depth == MAX_DEPTH {
+                return Err(ParseError::TooDeep { position });
+            }
+            self.position += 1;
+            self.depth += 1;
+            let node = self.union()?;
+            self.depth -= 1;
+            return match self.peek() {
+                Some((_, ')')) => {
+                    self.position += 1;
+                    Ok(node)
+                },
+                Some((position, found)) => {
+                    let expected = "an operator or `)`";
+                    Err(ParseError::UnexpectedChar { found, position, expected })
+                },
+                None => Err(ParseError::UnexpectedEnd { expected: "`)`" }),
+            };
+        }
+
+        let rest = &self.query[position..];
+        let len = rest.find(|c| !is_name_char(c)).unwrap_or(rest.len());
+        if len == 0 {
+            return Err(ParseError::UnexpectedChar { found: c, position, expected });
+        }
+
+        if self.operands == MAX_OPERANDS {
+            return Err(ParseError::TooManyOperands { position });
+        }
+
+        let name = &rest[..len];
+        self.position += len;
+        self.operands += 1;
+        match (self.lookup)(name) {
+            Some(set) => Ok(Node::Set(set)),
+            None => Err(ParseError::UnknownName { name: name.to_string(), position }),
+        }
+    }
+}
+
+/// Merge the operands that are the same operation into the parent one.
+fn flatten<'a, T, F, G>(nodes: Vec<Node<'a, T>>, make: F, split: G) -> Node<'a, T>
+where F: Fn(Vec<Node<'a, T>>) -> Node<'a, T>,
+      G: Fn(Node<'a, T>) -> Result<Vec<Node<'a, T>>, Node<'a, T>>,
+{
+    if nodes.len() == 1 {
+        return nodes.into_iter().next().unwrap();
+    }
+
+    let mut flat = Vec::with_capacity(nodes.len());
+    for node in nodes {
+        match split(node) {
+            Ok(inner) => Extend::extend(&mut flat, inner),
+            Err(node) => flat.push(node),
+        }
+    }
+    make(flat)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::set::{sort_dedup_vec, SetBuf};
+
+    fn lookup(name: &str) -> Option<&'static Set<i32>> {
+        match name {
+            "a" => Some(Set::new_unchecked(&[1, 2, 3, 4, 5, 6])),
+            "b" => Some(Set::new_unchecked(&[2, 3, 4])),
+            "c" => Some(Set::new_unchecked(&[5, 6, 7])),
+            "d" => Some(Set::new_unchecked(&[3, 6])),
+            "tag:empty" => Some(Set::new_unchecked(&[])),
+            _ => None,
+        }
+    }
+
+    fn eval(query: &str) -> Result<Vec<i32>, ParseError> {
+        parse(query, lookup).map(|q| q.cloned().collect())
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(eval("a & (b | c) - d"), Ok(vec![2, 4, 5]));
+        assert_eq!(eval("b | c & d"), Ok(vec![2, 3, 4, 6]));
+        assert_eq!(eval("(b | c) & d"), Ok(vec![3, 6]));
+        assert_eq!(eval("a - b - c"), Ok(vec![1]));
+        assert_eq!(eval("a - (b - d)"), Ok(vec![1, 3, 5, 6]));
+        assert_eq!(eval("(a - b) - c"), Ok(vec![1]));
+        assert_eq!(eval("b ^ c | d"), Ok(vec![2, 3, 4, 5, 6, 7]));
+        assert_eq!(eval("b | c ^ d"), Ok(vec![2, 3, 4, 5, 7]));
+        assert_eq!(eval("a & tag:empty"), Ok(vec![]));
+        assert_eq!(eval("  ((a))  "), Ok(vec![1, 2, 3, 4, 5, 6]));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(eval("a & e"), Err(ParseError::UnknownName { name: "e".to_string(), position: 4 }));
+        assert_eq!(eval(""), Err(ParseError::UnexpectedEnd { expected: "a name or `(`" }));
+        assert_eq!(eval("a &"), Err(ParseError::UnexpectedEnd { expected: "a name or `(`" }));
+        assert_eq!(eval("(a | b"), Err(ParseError::UnexpectedEnd { expected: "`)`" }));
+        assert_eq!(eval("a b"), Err(ParseError::UnexpectedChar { found: 'b', position: 2, expected: "an operator" }));
+        assert_eq!(eval("a)"), Err(ParseError::UnexpectedChar { found: ')', position: 1, expected: "an operator" }));
+        assert_eq!(eval("a & * b"), Err(ParseError::UnexpectedChar { found: '*', position: 4, expected: "a name or `(`" }));
+        assert_eq!(eval("(a b)"), Err(ParseError::UnexpectedChar { found: 'b', position: 3, expected: "an operator or `)`" }));
+    }
+
+    #[test]
+    fn too_deep() {
+        let query = format!("{}a{}", "(".repeat(128), ")".repeat(128));
+        assert_eq!(eval(&query), Ok(vec![1, 2, 3, 4, 5, 6]));
+
+        let query = format!("{}a{}", "(".repeat(129), ")".repeat(129));
+        assert_eq!(eval(&query), Err(ParseError::TooDeep { position: 128 }));
+
+        let query = "(".repeat(200_000);
+        assert_eq!(eval(&query), Err(ParseError::TooDeep { position: 128 }));
+    }
+
+    #[test]
+    fn long_chains() {
+        for operator in &[" & ", " | ", " ^ ", " - "] {
+            let query = vec!["a"; MAX_OPERANDS].join(operator);
+            assert!(eval(&query).is_ok());
+
+            let query = vec!["a"; MAX_OPERANDS + 1].join(operator);
+            let position = MAX_OPERANDS * (1 + operator.len());
+            assert_eq!(eval(&query), Err(ParseError::TooManyOperands { position }));
+        }
+
+        let query = vec!["a"; MAX_OPERANDS].join(" & ");
+        assert_eq!(eval(&query), Ok(vec![1, 2, 3, 4, 5, 6]));
+    }
+
+    #[test]
+    fn display_error() {
+        let error = parse("a | foo", lookup).err().unwrap();
+        assert_eq!(error.to_string(), "unknown name `foo` at position 4.");
+    }
+
+    quickcheck! {
+        fn qc_parse(a: Vec<i32>, b: Vec<i32>, c: Vec<i32>, d: Vec<i32>) -> bool {
+            use std::collections::BTreeSet;
+            use std::iter::FromIterator;
+
+            let mut sets = vec![a, b, c, d];
+            for xs in &mut sets {
+                sort_dedup_vec(xs);
+            }
+
+            let x: SetBuf<i32> = {
+                let lookup = |name: &str| match name {
+                    "a" => Some(Set::new_unchecked(sets[0].as_slice())),
+                    "b" => Some(Set::new_unchecked(sets[1].as_slice())),
+                    "c" => Some(Set::new_unchecked(sets[2].as_slice())),
+                    "d" => Some(Set::new_unchecked(sets[3].as_slice())),
+                    _ => None,
+                };
+                parse("a & (b | c) - d ^ b & c", lookup).unwrap().into_set_buf()
+            };
+
+            let mut sets = sets.into_iter().map(BTreeSet::from_iter);
+            let (a, b, c, d) = (sets.next().unwrap(), sets.next().unwrap(), sets.next().unwrap(), sets.next().unwrap());
+            let union: BTreeSet<i32> = b.union(&c).cloned().collect();
+            let difference: BTreeSet<i32> = union.difference(&d).cloned().collect();
+            let left: BTreeSet<i32> = a.intersection(&difference).cloned().collect();
+            let right: BTreeSet<i32> = b.intersection(&c).cloned().collect();
+            let y: Vec<i32> = left.symmetric_difference(&right).cloned().collect();
+
+            x.as_slice() == y.as_slice()
+        }
+    }
+}
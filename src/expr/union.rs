@@ -0,0 +1,127 @@
+use std::cmp::Ordering;
+use crate::{SetOperation, SeekableIterator, Collection};
+use crate::cursor::Cursor;
+
+/// Represent the lazy _union_ set operation that will be applied to two iterators.
+///
+/// # Examples
+/// ```
+/// # use sdset::Error;
+/// # fn try_main() -> Result<(), Error> {
+/// use sdset::expr::OpBuilder;
+/// use sdset::{SetOperation, Set, SetBuf};
+///
+/// let a = Set::new(&[1, 2, 4, 6, 7])?;
+/// let b = Set::new(&[2, 3, 4, 5, 6, 7])?;
+///
+/// let op = OpBuilder::new(a, b).union();
+///
+/// let res: SetBuf<i32> = op.into_set_buf();
+/// assert_eq!(&res[..], &[1, 2, 3, 4, 5, 6, 7]);
+/// # Ok(()) }
+/// # try_main().unwrap();
+/// ```
+pub struct Union<I: Iterator, J: Iterator> {
+    a: Cursor<I>,
+    b: Cursor<J>,
+}
+
+impl<I: Iterator, J: Iterator> Union<I, J> {
+    /// Construct one with iterators yielding sorted and deduplicated elements.
+    pub fn new(a: I, b: J) -> Self {
+        Self {
+            a: Cursor::new(a),
+            b: Cursor::new(b),
+        }
+    }
+}
+
+impl<'a, T, I, J> Iterator for Union<I, J>
+where T: 'a + Ord,
+      I: Iterator<Item = &'a T>,
+      J: Iterator<Item = &'a T>,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match (self.a.peek().copied(), self.b.peek().copied()) {
+            (Some(a), Some(b)) => {
+                match a.cmp(b) {
+                    Ordering::Less => self.a.bump(),
+                    Ordering::Equal => {
+                        self.b.bump();
+                        self.a.bump()
+                    },
+                    Ordering::Greater => self.b.bump(),
+                }
+            },
+            (Some(_), None) => self.a.bump(),
+            (None, _) => self.b.bump(),
+        }
+    }
+}
+
+impl<'a, T, I, J> SeekableIterator<T> for Union<I, J>
+where T: 'a + Ord,
+      I: SeekableIterator<T, Item = &'a T>,
+      J: SeekableIterator<T, Item = &'a T>,
+{
+    fn advance_to(&mut self, target: &T) {
+        self.a.advance_to(target);
+        self.b.advance_to(target);
+    }
+}
+
+impl<'a, T, I, J> SetOperation<T> for Union<I, J>
+where T: 'a + Ord + Clone,
+      I: Iterator<Item = &'a T>,
+      J: Iterator<Item = &'a T>,
+{
+    fn extend_collection<C>(self, output: &mut C) -> Result<(), C::Error>
+    where C: Collection<T>,
+    {
+        for x in self {
+            output.push(x.clone())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::set::{sort_dedup_vec, SetBuf};
+
+    #[test]
+    fn two_slices() {
+        let a = &[1, 2, 3];
+        let b = &[2, 4];
+
+        let union_: SetBuf<i32> = Union::new(a.iter(), b.iter()).into_set_buf();
+        assert_eq!(&union_[..], &[1, 2, 3, 4]);
+    }
+
+    quickcheck! {
+        fn qc_union(a: Vec<i32>, b: Vec<i32>, target: i32) -> bool {
+            use std::collections::BTreeSet;
+            use std::iter::FromIterator;
+
+            let mut a = a;
+            let mut b = b;
+
+            sort_dedup_vec(&mut a);
+            sort_dedup_vec(&mut b);
+
+            let mut iter = Union::new(a.iter(), b.iter());
+            iter.advance_to(&target);
+            let x: Vec<i32> = iter.cloned().collect();
+
+            let a = BTreeSet::from_iter(a);
+            let b = BTreeSet::from_iter(b);
+            let y = a.union(&b);
+            let y: Vec<_> = y.cloned().filter(|&y| y >= target).collect();
+
+            x.as_slice() == y.as_slice()
+        }
+    }
+}
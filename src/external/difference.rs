@@ -0,0 +1,62 @@
+use std::io::Read;
+use std::marker::PhantomData;
+use crate::codec::Integer;
+use crate::Collection;
+use super::Error;
+use super::merge::Merge;
+
+/// Represent the _difference_ set operation that will be applied to the runs,
+/// the elements of the first run that are not in the other ones are kept.
+///
+/// The runs are no more read once the first one has been entirely read.
+///
+/// # Examples
+/// ```
+/// # fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+/// use sdset::external::OpBuilder;
+/// use sdset::Set;
+///
+/// let a = Set::new(&[1u32, 2, 4, 6, 7])?;
+/// let b = Set::new(&[2u32, 3, 5, 7])?;
+/// let c = Set::new(&[4u32, 9])?;
+///
+/// let op = OpBuilder::<_, u32>::from_vec(vec![a.as_bytes(), b.as_bytes(), c.as_bytes()]).difference();
+///
+/// let mut res = Vec::new();
+/// op.extend_collection(&mut res)?;
+/// assert_eq!(&res[..], &[1, 6]);
+/// # Ok(()) }
+/// # try_main().unwrap();
+/// ```
+pub struct Difference<R, T> {
+    readers: Vec<R>,
+    _phantom: PhantomData<T>,
+}
+
+impl<R, T> Difference<R, T> {
+    /// Construct one with readers of sorted and deduplicated runs.
+    pub fn new(readers: Vec<R>) -> Self {
+        Self { readers, _phantom: PhantomData }
+    }
+}
+
+impl<R: Read, T: Integer> Difference<R, T> {
+    /// Extend a [`Collection`] with the values of the runs using this set operation,
+    /// the runs are checked to be sorted and deduplicated while they are read.
+    pub fn extend_collection<C>(self, output: &mut C) -> Result<(), Error<C::Error>>
+    where C: Collection<T>,
+    {
+        let mut merge = Merge::new(self.readers)?;
+
+        while !merge.is_exhausted(0) {
+            match merge.pop()? {
+                Some((x, sources)) => if sources == [0] {
+                    output.push(x).map_err(Error::Collection)?;
+                },
+                None => break,
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,84 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::io::Read;
+use crate::codec::Integer;
+use crate::set::Error as SetError;
+use super::{Error, RunReader};
+
+/// A k-way merge of sorted runs read from readers, the next element
+/// of every run is kept in a binary heap.
+pub(super) struct Merge<R, T> {
+    readers: Vec<RunReader<R, T>>,
+    exhausted: Vec<bool>,
+    heap: BinaryHeap<Reverse<(T, usize)>>,
+    sources: Vec<usize>,
+}
+
+impl<R: Read, T: Integer> Merge<R, T> {
+    pub fn new<E>(readers: Vec<R>) -> Result<Self, Error<E>> {
+        let mut readers: Vec<_> = readers.into_iter().map(RunReader::new).collect();
+        let mut exhausted = vec![true; readers.len()];
+        let mut heap = BinaryHeap::with_capacity(readers.len());
+
+        for (i, reader) in readers.iter_mut().enumerate() {
+            if let Some(x) = reader.next().transpose()? {
+                exhausted[i] = false;
+                heap.push(Reverse((x, i)));
+            }
+        }
+
+        Ok(Merge { readers, exhausted, heap, sources: Vec::new() })
+    }
+
+    /// Returns the number of runs.
+    pub fn len(&self) -> usize {
+        self.readers.len()
+    }
+
+    /// Returns the number of runs that still have elements.
+    pub fn remaining(&self) -> usize {
+        self.heap.len()
+    }
+
+    /// Returns `true` if the run at `index` has no more elements.
+    pub fn is_exhausted(&self, index: usize) -> bool {
+        self.exhausted.get(index).cloned().unwrap_or(true)
+    }
+
+    /// Pop the minimum of all the runs and returns it
+    /// along with the indexes of the runs it comes from.
+    pub fn pop<E>(&mut self) -> Result<Option<(T, &[usize])>, Error<E>> {
+        let (min, index) = match self.heap.pop() {
+            Some(Reverse(entry)) => entry,
+            None => return Ok(None),
+        };
+
+        self.sources.clear();
+        self.sources.push(index);
+        while let Some(&Reverse((x, i))) = self.heap.peek() {
+            if x != min { break }
+            self.heap.pop();
+            self.sources.push(i);
+        }
+
+        for k in 0..self.sources.len() {
+            let index = self.sources[k];
+            self.advance(index, min)?;
+        }
+
+        Ok(Some((min, &self.sources)))
+    }
+
+    /// Read the next element of the run at `index`, checking that it follows the previous one.
+    fn advance<E>(&mut self, index: usize, previous: T) -> Result<(), Error<E>> {
+        match self.readers[index].next().transpose()? {
+            Some(x) => match x.cmp(&previous) {
+                Ordering::Greater => self.heap.push(Reverse((x, index))),
+                Ordering::Equal => return Err(Error::Set(SetError::NotDedup)),
+                Ordering::Less => return Err(Error::Set(SetError::NotSort)),
+            },
+            None => self.exhausted[index] = true,
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,232 @@
+//! Contains the types to make set operations on sorted runs that do not fit in memory.
+//!
+//! The runs are read from [`io::Read`] sources, e.g. files, as little-endian fixed-width
+//! integers, the format read by [`Set::from_bytes`](crate::Set::from_bytes). Only the next
+//! element of every run is kept in memory and the results are given to a
+//! [`Collection`](crate::Collection), which can write them anywhere.
+//!
+//! The [`Sorter`] sorts and deduplicates elements given in any order using a bounded
+//! amount of memory, by spilling sorted runs into temporary files that are merged at the end.
+//!
+//! # Examples
+//! ```
+//! # fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+//! use sdset::external::OpBuilder;
+//! use sdset::Set;
+//!
+//! let a = Set::new(&[1u64, 2, 4])?;
+//! let b = Set::new(&[2u64, 3, 5, 7])?;
+//! let c = Set::new(&[4u64, 6, 7])?;
+//!
+//! let runs = vec![a.as_bytes(), b.as_bytes(), c.as_bytes()];
+//! let op = OpBuilder::<_, u64>::from_vec(runs).union();
+//!
+//! let mut res = Vec::new();
+//! op.extend_collection(&mut res)?;
+//! assert_eq!(&res[..], &[1, 2, 3, 4, 5, 6, 7]);
+//! # Ok(()) }
+//! # try_main().unwrap();
+//! ```
+
+use std::{error, fmt, io};
+use std::marker::PhantomData;
+use crate::set::Error as SetError;
+
+mod difference;
+mod intersection;
+mod merge;
+mod reader;
+mod sorter;
+mod union;
+
+pub use self::difference::Difference;
+pub use self::intersection::Intersection;
+pub use self::reader::RunReader;
+pub use self::sorter::Sorter;
+pub use self::union::Union;
+
+/// Type used to acquire any number of readers of sorted runs
+/// and make a set operation on these runs.
+pub struct OpBuilder<R, T> {
+    readers: Vec<R>,
+    _phantom: PhantomData<T>,
+}
+
+impl<R, T> Default for OpBuilder<R, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<R, T> OpBuilder<R, T> {
+    /// Construct an empty one.
+    pub fn new() -> Self {
+        Self::from_vec(Vec::new())
+    }
+
+    /// Construct an empty one with enough space for `capacity` readers or more.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::from_vec(Vec::with_capacity(capacity))
+    }
+
+    /// Construct it with the given readers.
+    pub fn from_vec(readers: Vec<R>) -> Self {
+        Self { readers, _phantom: PhantomData }
+    }
+
+    /// Reserve additional space for the underlying vec.
+    pub fn reserve(&mut self, additional: usize) {
+        self.readers.reserve(additional);
+    }
+
+    /// Add a new reader that will be used for the future set operation
+    /// and consume and return the type.
+    #[allow(clippy::should_implement_trait)]
+    pub fn add(mut self, reader: R) -> Self {
+        self.push(reader);
+        self
+    }
+
+    /// Push a new reader that will be used for the future set operation.
+    pub fn push(&mut self, reader: R) {
+        self.readers.push(reader);
+    }
+
+    /// Prepare the runs for the _union_ set operation.
+    pub fn union(self) -> Union<R, T> {
+        Union::new(self.readers)
+    }
+
+    /// Prepare the runs for the _intersection_ set operation.
+    pub fn intersection(self) -> Intersection<R, T> {
+        Intersection::new(self.readers)
+    }
+
+    /// Prepare the runs for the _difference_ set operation.
+    pub fn difference(self) -> Difference<R, T> {
+        Difference::new(self.readers)
+    }
+}
+
+/// Represent the possible errors of the external set operations.
+#[derive(Debug)]
+pub enum Error<E> {
+    /// Define that reading a run or writing a temporary file failed.
+    Io(io::Error),
+    /// Define that a run is not sorted or not deduplicated.
+    Set(SetError),
+    /// Define that the output [`Collection`](crate::Collection) returned an error.
+    Collection(E),
+}
+
+impl<E> From<io::Error> for Error<E> {
+    fn from(error: io::Error) -> Error<E> {
+        Error::Io(error)
+    }
+}
+
+impl<E> From<SetError> for Error<E> {
+    fn from(error: SetError) -> Error<E> {
+        Error::Set(error)
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for Error<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            Error::Io(e) => fmt::Display::fmt(e, f),
+            Error::Set(e) => fmt::Display::fmt(e, f),
+            Error::Collection(e) => fmt::Display::fmt(e, f),
+        }
+    }
+}
+
+impl<E: error::Error + 'static> error::Error for Error<E> {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            Error::Set(e) => Some(e),
+            Error::Collection(e) => Some(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::multi;
+    use crate::set::{Set, SetBuf, sort_dedup_vec};
+    use crate::write::{WriteCollection, LittleEndian};
+    use crate::{Collection, SetOperation};
+
+    fn encode(xs: &[u32]) -> Vec<u8> {
+        let mut output = WriteCollection::new(Vec::new(), LittleEndian);
+        output.extend_from_slice(xs).unwrap();
+        output.into_inner()
+    }
+
+    #[test]
+    fn unsorted_run() {
+        let a = encode(&[1, 2, 3]);
+        let b = encode(&[2, 5, 4]);
+
+        let mut res = Vec::new();
+        let op = OpBuilder::<_, u32>::from_vec(vec![&a[..], &b[..]]).union();
+        match op.extend_collection(&mut res) {
+            Err(Error::Set(SetError::NotSort)) => (),
+            other => panic!("unexpected result {:?}", other),
+        }
+
+        let b = encode(&[2, 2]);
+        let op = OpBuilder::<_, u32>::from_vec(vec![&a[..], &b[..]]).intersection();
+        match op.extend_collection(&mut res) {
+            Err(Error::Set(SetError::NotDedup)) => (),
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+
+    #[test]
+    fn truncated_run() {
+        let mut a = encode(&[1, 2, 3]);
+        a.pop();
+
+        let mut res = Vec::new();
+        let op = OpBuilder::<_, u32>::from_vec(vec![&a[..]]).union();
+        match op.extend_collection(&mut res) {
+            Err(Error::Io(e)) => assert_eq!(e.kind(), io::ErrorKind::UnexpectedEof),
+            other => panic!("unexpected result {:?}", other),
+        }
+        assert_eq!(&res[..], &[1]);
+    }
+
+    quickcheck! {
+        fn qc_operations(xss: Vec<Vec<u32>>) -> bool {
+            let mut xss = xss;
+            for xs in &mut xss {
+                // reduce the range to have common elements
+                xs.iter_mut().for_each(|x| *x %= 100);
+                sort_dedup_vec(xs);
+            }
+
+            let sets: Vec<_> = xss.iter().map(|xs| Set::new_unchecked(&xs[..])).collect();
+            let runs: Vec<_> = xss.iter().map(|xs| encode(xs)).collect();
+            let readers = || runs.iter().map(|r| &r[..]).collect::<Vec<_>>();
+
+            let mut union: Vec<u32> = Vec::new();
+            OpBuilder::from_vec(readers()).union().extend_collection(&mut union).unwrap();
+            let expected: SetBuf<u32> = multi::OpBuilder::from_vec(sets.clone()).union().into_set_buf();
+
+            let mut intersection: Vec<u32> = Vec::new();
+            OpBuilder::from_vec(readers()).intersection().extend_collection(&mut intersection).unwrap();
+            let expected_intersection: SetBuf<u32> = multi::OpBuilder::from_vec(sets.clone()).intersection().into_set_buf();
+
+            let mut difference: Vec<u32> = Vec::new();
+            OpBuilder::from_vec(readers()).difference().extend_collection(&mut difference).unwrap();
+            let expected_difference: SetBuf<u32> = multi::OpBuilder::from_vec(sets).difference().into_set_buf();
+
+            union == expected.into_vec()
+                && intersection == expected_intersection.into_vec()
+                && difference == expected_difference.into_vec()
+        }
+    }
+}
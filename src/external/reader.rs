@@ -0,0 +1,44 @@
+use std::io::{self, BufReader, Read};
+use std::marker::PhantomData;
+use std::mem;
+use crate::codec::Integer;
+
+/// An iterator reading little-endian fixed-width integers from a reader.
+///
+/// The reader is buffered, a file can be given directly.
+pub struct RunReader<R, T> {
+    reader: BufReader<R>,
+    _phantom: PhantomData<T>,
+}
+
+impl<R: Read, T: Integer> RunReader<R, T> {
+    /// Construct one reading from the given reader.
+    pub fn new(reader: R) -> Self {
+        RunReader { reader: BufReader::new(reader), _phantom: PhantomData }
+    }
+}
+
+impl<R: Read, T: Integer> Iterator for RunReader<R, T> {
+    type Item = io::Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let size = mem::size_of::<T>();
+        let mut bytes = [0; 8];
+        let mut read = 0;
+
+        // a run can only end between two integers
+        while read < size {
+            match self.reader.read(&mut bytes[read..size]) {
+                Ok(0) if read == 0 => return None,
+                Ok(0) => return Some(Err(io::ErrorKind::UnexpectedEof.into())),
+                Ok(n) => read += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => (),
+                Err(e) => return Some(Err(e)),
+            }
+        }
+
+        // the integer has been read from as many bytes as its size
+        Some(Ok(T::from_u64(u64::from_le_bytes(bytes)).unwrap()))
+    }
+}
+
@@ -0,0 +1,210 @@
+use std::collections::hash_map::RandomState;
+use std::fs::{self, File, OpenOptions};
+use std::hash::{BuildHasher, Hasher};
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::{cmp, env, process};
+use crate::codec::Integer;
+use crate::set::sort_dedup_vec;
+use crate::write::{WriteCollection, LittleEndian};
+use crate::Collection;
+use super::{Error, Union};
+
+/// Used to give a unique name to the temporary files of the process.
+static SPILL_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// The maximum number of temporary files opened at once to merge them.
+const FAN_IN: usize = 64;
+
+/// Sort and deduplicate elements given in any order using a bounded amount of memory,
+/// like [`SetBuf::from_dirty`](crate::SetBuf::from_dirty) does in memory.
+///
+/// The elements are kept in a buffer, once it is full it is sorted, deduplicated and written
+/// into a temporary file. The files are merged with a [`Union`] at the end
+/// and removed when the sorter is dropped.
+///
+/// At most 64 files are opened at once, when there are more of them
+/// they are first merged by groups into new files until there are few enough.
+///
+/// # Examples
+/// ```
+/// # fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+/// use sdset::external::Sorter;
+///
+/// // keep at most 100 elements in memory
+/// let mut sorter = Sorter::new(100);
+/// sorter.extend((0..1000u32).rev().map(|x| x / 2))?;
+/// assert_eq!(sorter.spills(), 10);
+///
+/// let mut res = Vec::new();
+/// sorter.extend_collection(&mut res)?;
+/// assert_eq!(res, (0..500).collect::<Vec<_>>());
+/// # Ok(()) }
+/// # try_main().unwrap();
+/// ```
+pub struct Sorter<T> {
+    buffer: Vec<T>,
+    capacity: usize,
+    fan_in: usize,
+    dir: PathBuf,
+    spills: Vec<PathBuf>,
+}
+
+impl<T: Integer> Sorter<T> {
+    /// Construct one keeping at most `capacity` elements in memory,
+    /// the temporary files are written in the [`env::temp_dir`] directory.
+    pub fn new(capacity: usize) -> Self {
+        Self::with_dir(env::temp_dir(), capacity)
+    }
+
+    /// Construct one keeping at most `capacity` elements in memory,
+    /// the temporary files are written in the given directory.
+    pub fn with_dir<P: Into<PathBuf>>(dir: P, capacity: usize) -> Self {
+        let capacity = cmp::max(capacity, 1);
+        Sorter {
+            buffer: Vec::with_capacity(capacity),
+            capacity,
+            fan_in: FAN_IN,
+            dir: dir.into(),
+            spills: Vec::new(),
+        }
+    }
+
+    /// Returns the number of temporary files written.
+    pub fn spills(&self) -> usize {
+        self.spills.len()
+    }
+
+    /// Insert one element, the buffer is written into a temporary file if it is full.
+    pub fn push(&mut self, elem: T) -> io::Result<()> {
+        self.buffer.push(elem);
+        if self.buffer.len() >= self.capacity {
+            self.spill()?;
+        }
+        Ok(())
+    }
+
+    /// Insert the elements from the given [`Iterator`].
+    pub fn extend<I>(&mut self, elems: I) -> io::Result<()>
+    where I: IntoIterator<Item=T>
+    {
+        elems.into_iter().try_for_each(|elem| self.push(elem))
+    }
+
+    /// Extend a [`Collection`] with the sorted and deduplicated elements.
+    pub fn extend_collection<C>(mut self, output: &mut C) -> Result<(), Error<C::Error>>
+    where C: Collection<T>,
+    {
+        if self.spills.is_empty() {
+            sort_dedup_vec(&mut self.buffer);
+            return output.extend(self.buffer.drain(..)).map_err(Error::Collection);
+        }
+
+        if !self.buffer.is_empty() {
+            self.spill()?;
+        }
+
+        // merge the oldest files into new ones until they can all be opened at once
+        while self.spills.len() > self.fan_in {
+            let files = self.spills[..self.fan_in].iter().map(File::open).collect::<io::Result<Vec<_>>>()?;
+            let mut output = self.create_spill()?;
+            Union::<_, T>::new(files).extend_collection(&mut output).map_err(|e| match e {
+                Error::Io(e) | Error::Collection(e) => Error::Io(e),
+                Error::Set(e) => Error::Set(e),
+            })?;
+            output.into_inner().flush()?;
+
+            for path in &self.spills[..self.fan_in] {
+                fs::remove_file(path)?;
+            }
+            self.spills.drain(..self.fan_in);
+        }
+
+        let files = self.spills.iter().map(File::open).collect::<io::Result<Vec<_>>>()?;
+        Union::new(files).extend_collection(output)
+    }
+
+    fn spill(&mut self) -> io::Result<()> {
+        sort_dedup_vec(&mut self.buffer);
+
+        let mut output = self.create_spill()?;
+        Collection::<T>::extend_from_slice(&mut output, &self.buffer)?;
+        output.into_inner().flush()?;
+
+        self.buffer.clear();
+        Ok(())
+    }
+
+    /// Create a new temporary file, it is removed when the sorter is dropped.
+    ///
+    /// The names end with a random number to not be predictable and
+    /// another name is tried if the file already exists.
+    fn create_spill(&mut self) -> io::Result<WriteCollection<BufWriter<File>, LittleEndian>> {
+        loop {
+            let id = SPILL_ID.fetch_add(1, Ordering::Relaxed);
+            let random = RandomState::new().build_hasher().finish();
+            let name = format!("sdset-{}-{}-{:016x}.run", process::id(), id, random);
+            let path = self.dir.join(name);
+
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(file) => {
+                    // registered before being written to be removed even if writing fails
+                    self.spills.push(path);
+                    return Ok(WriteCollection::new(BufWriter::new(file), LittleEndian));
+                },
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+impl<T> Drop for Sorter<T> {
+    fn drop(&mut self) {
+        for path in &self.spills {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge_passes() {
+        let dir = env::temp_dir().join(format!("sdset-merge-passes-{}", process::id()));
+        fs::create_dir(&dir).unwrap();
+
+        // 37 files with the last buffer, merged 4 by 4
+        let mut sorter = Sorter::with_dir(&dir, 3);
+        sorter.fan_in = 4;
+        sorter.extend((0..100u32).rev().chain(0..10)).unwrap();
+        assert_eq!(sorter.spills(), 36);
+
+        let mut res = Vec::new();
+        sorter.extend_collection(&mut res).unwrap();
+        assert_eq!(res, (0..100).collect::<Vec<_>>());
+
+        let remaining = fs::read_dir(&dir).unwrap().count();
+        fs::remove_dir(&dir).unwrap();
+        assert_eq!(remaining, 0);
+    }
+
+    quickcheck! {
+        fn qc_sort_dedup(xs: Vec<u16>, capacity: usize) -> bool {
+            let mut sorter = Sorter::new(capacity % 20);
+            sorter.extend(xs.iter().cloned()).unwrap();
+            let paths = sorter.spills.clone();
+
+            let mut res = Vec::new();
+            sorter.extend_collection(&mut res).unwrap();
+
+            let mut expected = xs;
+            sort_dedup_vec(&mut expected);
+
+            res == expected && paths.iter().all(|p| !p.exists())
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use std::io::Read;
+use std::marker::PhantomData;
+use crate::codec::Integer;
+use crate::Collection;
+use super::Error;
+use super::merge::Merge;
+
+/// Represent the _union_ set operation that will be applied to the runs.
+///
+/// # Examples
+/// ```
+/// # fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+/// use sdset::external::OpBuilder;
+/// use sdset::Set;
+///
+/// let a = Set::new(&[1u32, 2, 4])?;
+/// let b = Set::new(&[2u32, 3, 5, 7])?;
+/// let c = Set::new(&[4u32, 6, 7])?;
+///
+/// let op = OpBuilder::<_, u32>::from_vec(vec![a.as_bytes(), b.as_bytes(), c.as_bytes()]).union();
+///
+/// let mut res = Vec::new();
+/// op.extend_collection(&mut res)?;
+/// assert_eq!(&res[..], &[1, 2, 3, 4, 5, 6, 7]);
+/// # Ok(()) }
+/// # try_main().unwrap();
+/// ```
+pub struct Union<R, T> {
+    readers: Vec<R>,
+    _phantom: PhantomData<T>,
+}
+
+impl<R, T> Union<R, T> {
+    /// Construct one with readers of sorted and deduplicated runs.
+    pub fn new(readers: Vec<R>) -> Self {
+        Self { readers, _phantom: PhantomData }
+    }
+}
+
+impl<R: Read, T: Integer> Union<R, T> {
+    /// Extend a [`Collection`] with the values of the runs using this set operation,
+    /// the runs are checked to be sorted and deduplicated while they are read.
+    pub fn extend_collection<C>(self, output: &mut C) -> Result<(), Error<C::Error>>
+    where C: Collection<T>,
+    {
+        let mut merge = Merge::new(self.readers)?;
+        while let Some((x, _)) = merge.pop()? {
+            output.push(x).map_err(Error::Collection)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,224 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use crate::exponential_offset_ge;
+
+/// A k-way merge of sorted and deduplicated slices, the next minimum
+/// of all the slices is found in `O(log k)` using a binary heap.
+///
+/// The maximums are kept in another heap to merge the slices from the back,
+/// an entry of a heap is outdated once its slice has been emptied from the other end.
+#[derive(Clone)]
+pub struct HeapMerge<'a, T: 'a> {
+    slices: Vec<&'a [T]>,
+    heap: BinaryHeap<Reverse<(&'a T, usize)>>,
+    back: BinaryHeap<(&'a T, usize)>,
+}
+
+impl<'a, T: Ord> HeapMerge<'a, T> {
+    pub fn new(slices: Vec<&'a [T]>) -> Self {
+        let heap = slices.iter()
+            .enumerate()
+            .filter_map(|(i, s)| s.first().map(|x| Reverse((x, i))))
+            .collect();
+
+        let back = slices.iter()
+            .enumerate()
+            .filter_map(|(i, s)| s.last().map(|x| (x, i)))
+            .collect();
+
+        HeapMerge { slices, heap, back }
+    }
+
+    /// Returns the minimum of the heap, removing the outdated entries.
+    fn peek_minimum(&mut self) -> Option<(&'a T, usize)> {
+        while let Some(&Reverse((x, i))) = self.heap.peek() {
+            if !self.slices[i].is_empty() { return Some((x, i)) }
+            self.heap.pop();
+        }
+        None
+    }
+
+    /// Returns the maximum of the back heap, removing the outdated entries.
+    fn peek_maximum(&mut self) -> Option<(&'a T, usize)> {
+        while let Some(&(x, i)) = self.back.peek() {
+            if !self.slices[i].is_empty() { return Some((x, i)) }
+            self.back.pop();
+        }
+        None
+    }
+
+    /// Consume the first `off` elements of the slice at `index`
+    /// and push its new first element in the heap.
+    fn advance(&mut self, index: usize, off: usize) {
+        let slice = &self.slices[index][off..];
+        self.slices[index] = slice;
+        if let Some(first) = slice.first() {
+            self.heap.push(Reverse((first, index)));
+        }
+    }
+
+    /// Consume the last element of the slice at `index`
+    /// and push its new last element in the back heap.
+    fn retreat(&mut self, index: usize) {
+        let slice = self.slices[index];
+        let slice = &slice[..slice.len() - 1];
+        self.slices[index] = slice;
+        if let Some(last) = slice.last() {
+            self.back.push((last, index));
+        }
+    }
+
+    /// Pop the minimum of the heap and remove it from every slice,
+    /// returns the minimum along with the index of one of the slices it comes from.
+    fn pop_minimum(&mut self) -> Option<(&'a T, usize)> {
+        let (min, index) = self.peek_minimum()?;
+        self.heap.pop();
+
+        while let Some((x, i)) = self.peek_minimum() {
+            if x != min { break }
+            self.heap.pop();
+            self.advance(i, 1);
+        }
+
+        Some((min, index))
+    }
+
+    /// Skip the elements less than `target` in every slice.
+    pub fn advance_to(&mut self, target: &T) {
+        while let Some((x, i)) = self.peek_minimum() {
+            if x >= target { break }
+            self.heap.pop();
+            let slice = self.slices[i];
+            let off = slice.len() - exponential_offset_ge(slice, target).len();
+            self.advance(i, off);
+        }
+    }
+
+    /// Returns the next run of elements that are less than all the other slices ones,
+    /// they all come from the same slice and are already removed from the other slices.
+    pub fn next_run(&mut self) -> Option<&'a [T]> {
+        let (_, index) = self.pop_minimum()?;
+        let slice = self.slices[index];
+
+        let off = match self.peek_minimum() {
+            Some((next, _)) => slice.iter().take_while(|&x| x < next).count(),
+            None => slice.len(),
+        };
+
+        self.advance(index, off);
+        Some(&slice[..off])
+    }
+}
+
+impl<'a, T: Ord> Iterator for HeapMerge<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (min, index) = self.pop_minimum()?;
+        self.advance(index, 1);
+        Some(min)
+    }
+}
+
+impl<'a, T: Ord> DoubleEndedIterator for HeapMerge<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (max, _) = self.peek_maximum()?;
+
+        while let Some((x, i)) = self.peek_maximum() {
+            if x != max { break }
+            self.back.pop();
+            self.retreat(i);
+        }
+
+        Some(max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::set::sort_dedup_vec;
+
+    #[test]
+    fn runs() {
+        let a = &[1, 2, 3, 7, 8];
+        let b = &[3, 4, 9];
+        let c = &[];
+
+        let mut merge = HeapMerge::new(vec![&a[..], &b[..], &c[..]]);
+        assert_eq!(merge.next_run(), Some(&[1, 2][..]));
+        assert_eq!(merge.next_run(), Some(&[3][..]));
+        assert_eq!(merge.next_run(), Some(&[4][..]));
+        assert_eq!(merge.next_run(), Some(&[7, 8][..]));
+        assert_eq!(merge.next_run(), Some(&[9][..]));
+        assert_eq!(merge.next_run(), None);
+    }
+
+    quickcheck! {
+        fn qc_heap_merge(xss: Vec<Vec<i32>>) -> bool {
+            use std::collections::BTreeSet;
+
+            let mut xss = xss;
+
+            for xs in &mut xss {
+                sort_dedup_vec(xs);
+            }
+
+            let slices: Vec<_> = xss.iter().map(|xs| xs.as_slice()).collect();
+            let x: Vec<i32> = HeapMerge::new(slices.clone()).cloned().collect();
+
+            let mut merge = HeapMerge::new(slices);
+            let mut z = Vec::new();
+            while let Some(run) = merge.next_run() {
+                z.extend_from_slice(run);
+            }
+
+            let y: BTreeSet<_> = xss.into_iter().flatten().collect();
+            let y: Vec<_> = y.into_iter().collect();
+
+            x == y && z == y
+        }
+
+        fn qc_heap_merge_both_ends(xss: Vec<Vec<i32>>, fronts: Vec<bool>) -> bool {
+            use std::collections::BTreeSet;
+
+            let mut xss = xss;
+
+            for xs in &mut xss {
+                sort_dedup_vec(xs);
+            }
+
+            let slices: Vec<_> = xss.iter().map(|xs| xs.as_slice()).collect();
+            let mut merge = HeapMerge::new(slices);
+
+            let y: BTreeSet<_> = xss.iter().flatten().collect();
+            let mut y = y.into_iter();
+
+            // take from both ends then collect the middle forward
+            for front in fronts {
+                let (x, y) = if front { (merge.next(), y.next()) } else { (merge.next_back(), y.next_back()) };
+                if x != y { return false }
+            }
+
+            merge.eq(y)
+        }
+
+        fn qc_heap_merge_advance_to(xss: Vec<Vec<i32>>, target: i32) -> bool {
+            use std::collections::BTreeSet;
+
+            let mut xss = xss;
+
+            for xs in &mut xss {
+                sort_dedup_vec(xs);
+            }
+
+            let slices: Vec<_> = xss.iter().map(|xs| xs.as_slice()).collect();
+            let mut merge = HeapMerge::new(slices);
+            merge.advance_to(&target);
+
+            let y: BTreeSet<_> = xss.iter().flatten().filter(|&&x| x >= target).collect();
+
+            merge.eq(y)
+        }
+    }
+}
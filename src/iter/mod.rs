@@ -0,0 +1,137 @@
+//! Contains the types to make set operations on two sorted and deduplicated iterators.
+//!
+//! The inputs do not need to be [`Set`](crate::Set)s, they can be any iterator
+//! yielding owned elements, e.g. coming from a decoder, a file reader or a channel.
+//! The operations are iterators themselves and can be nested.
+//!
+//! It is the caller responsibility to give iterators that are sorted and deduplicated,
+//! this is checked when the debug assertions are enabled and a panic is raised otherwise.
+//!
+//! # Examples
+//! ```
+//! use sdset::iter;
+//!
+//! let a = vec![1, 2, 4, 6, 7];
+//! let b = vec![2, 3, 4, 5, 6, 7];
+//! let c = vec![4, 5, 8];
+//!
+//! let union = iter::union(a, b);
+//! let res: Vec<i32> = iter::difference(union, c).collect();
+//!
+//! assert_eq!(&res[..], &[1, 2, 3, 6, 7]);
+//! ```
+
+mod union;
+mod difference;
+mod intersection;
+mod symmetric_difference;
+
+pub use self::union::Union;
+pub use self::difference::Difference;
+pub use self::intersection::Intersection;
+pub use self::symmetric_difference::SymmetricDifference;
+
+/// Prepare the two iterators for the _union_ set operation.
+pub fn union<A, B>(a: A, b: B) -> Union<A::IntoIter, B::IntoIter>
+where A: IntoIterator,
+      B: IntoIterator<Item = A::Item>,
+      A::Item: Ord,
+{
+    Union::new(a.into_iter(), b.into_iter())
+}
+
+/// Prepare the two iterators for the _intersection_ set operation.
+pub fn intersection<A, B>(a: A, b: B) -> Intersection<A::IntoIter, B::IntoIter>
+where A: IntoIterator,
+      B: IntoIterator<Item = A::Item>,
+      A::Item: Ord,
+{
+    Intersection::new(a.into_iter(), b.into_iter())
+}
+
+/// Prepare the two iterators for the _difference_ set operation.
+pub fn difference<A, B>(a: A, b: B) -> Difference<A::IntoIter, B::IntoIter>
+where A: IntoIterator,
+      B: IntoIterator<Item = A::Item>,
+      A::Item: Ord,
+{
+    Difference::new(a.into_iter(), b.into_iter())
+}
+
+/// Prepare the two iterators for the _symmetric difference_ set operation.
+pub fn symmetric_difference<A, B>(a: A, b: B) -> SymmetricDifference<A::IntoIter, B::IntoIter>
+where A: IntoIterator,
+      B: IntoIterator<Item = A::Item>,
+      A::Item: Ord,
+{
+    SymmetricDifference::new(a.into_iter(), b.into_iter())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::set::sort_dedup_vec;
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "not sorted and deduplicated")]
+    fn unsorted_input() {
+        let a = vec![1, 3, 2];
+        let b = vec![2, 4];
+        let _: Vec<i32> = union(a, b).collect();
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "not sorted and deduplicated")]
+    fn duplicated_input() {
+        let a = vec![1, 2, 3];
+        let b = vec![2, 2, 4];
+        let _: Vec<i32> = intersection(a, b).collect();
+    }
+
+    #[test]
+    fn lazy_first_element() {
+        use std::sync::mpsc::channel;
+
+        let (sender, receiver) = channel();
+
+        // must not block waiting for the first element
+        let mut op = union(receiver, vec![2, 5]);
+
+        sender.send(1).unwrap();
+        sender.send(5).unwrap();
+        drop(sender);
+
+        assert_eq!(op.next(), Some(1));
+        assert_eq!(op.collect::<Vec<_>>(), vec![2, 5]);
+    }
+
+    quickcheck! {
+        fn qc_nested_operations(a: Vec<i32>, b: Vec<i32>, c: Vec<i32>) -> bool {
+            use std::collections::BTreeSet;
+            use std::iter::FromIterator;
+
+            let mut a = a;
+            let mut b = b;
+            let mut c = c;
+
+            sort_dedup_vec(&mut a);
+            sort_dedup_vec(&mut b);
+            sort_dedup_vec(&mut c);
+
+            let x: Vec<i32> = {
+                let union = union(a.clone(), b.clone());
+                intersection(union, c.clone()).collect()
+            };
+
+            let a = BTreeSet::from_iter(a);
+            let b = BTreeSet::from_iter(b);
+            let c = BTreeSet::from_iter(c);
+            let union: BTreeSet<_> = a.union(&b).cloned().collect();
+            let y: Vec<_> = union.intersection(&c).cloned().collect();
+
+            x.as_slice() == y.as_slice()
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use std::cmp::Ordering;
+use crate::{SetOperation, Collection};
+use crate::cursor::Cursor;
+
+/// Represent the _union_ set operation that will be applied to two iterators.
+///
+/// # Examples
+/// ```
+/// use sdset::iter;
+///
+/// let a = vec![1, 2, 4, 6, 7];
+/// let b = vec![2, 3, 4, 5, 6, 7];
+///
+/// let res: Vec<i32> = iter::union(a, b).collect();
+/// assert_eq!(&res[..], &[1, 2, 3, 4, 5, 6, 7]);
+/// ```
+pub struct Union<I: Iterator, J: Iterator> {
+    a: Cursor<I>,
+    b: Cursor<J>,
+}
+
+impl<I, J> Union<I, J>
+where I: Iterator,
+      J: Iterator<Item = I::Item>,
+      I::Item: Ord,
+{
+    /// Construct one with iterators promised to be sorted and deduplicated.
+    pub fn new(a: I, b: J) -> Self {
+        Self {
+            a: Cursor::new(a),
+            b: Cursor::new(b),
+        }
+    }
+}
+
+impl<I, J> Iterator for Union<I, J>
+where I: Iterator,
+      J: Iterator<Item = I::Item>,
+      I::Item: Ord,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let ordering = match (self.a.peek(), self.b.peek()) {
+            (Some(a), Some(b)) => a.cmp(b),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => return None,
+        };
+
+        match ordering {
+            Ordering::Less => self.a.bump(),
+            Ordering::Equal => {
+                self.b.bump();
+                self.a.bump()
+            },
+            Ordering::Greater => self.b.bump(),
+        }
+    }
+}
+
+impl<T, I, J> SetOperation<T> for Union<I, J>
+where T: Ord,
+      I: Iterator<Item = T>,
+      J: Iterator<Item = T>,
+{
+    fn extend_collection<C>(self, output: &mut C) -> Result<(), C::Error>
+    where C: Collection<T>,
+    {
+        for x in self {
+            output.push(x)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::set::{sort_dedup_vec, SetBuf};
+
+    #[test]
+    fn two_iters() {
+        let a = vec![1, 2, 3];
+        let b = vec![2, 4];
+
+        let union_: SetBuf<i32> = Union::new(a.into_iter(), b.into_iter()).into_set_buf();
+        assert_eq!(&union_[..], &[1, 2, 3, 4]);
+    }
+
+    quickcheck! {
+        fn qc_union(a: Vec<i32>, b: Vec<i32>) -> bool {
+            use std::collections::BTreeSet;
+            use std::iter::FromIterator;
+
+            let mut a = a;
+            let mut b = b;
+
+            sort_dedup_vec(&mut a);
+            sort_dedup_vec(&mut b);
+
+            let x: Vec<i32> = Union::new(a.clone().into_iter(), b.clone().into_iter()).collect();
+
+            let a = BTreeSet::from_iter(a);
+            let b = BTreeSet::from_iter(b);
+            let y = a.union(&b);
+            let y: Vec<_> = y.cloned().collect();
+
+            x.as_slice() == y.as_slice()
+        }
+    }
+}
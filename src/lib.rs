@@ -1,12 +1,16 @@
 //! Operations for already sorted and deduplicated slices.
 //!
-//! This library contains two modules containing types to produce set operations:
+//! This library contains four modules containing types to produce set operations:
 //!   - The [`duo`] module is for types limited to be used with two slices not more not less.
 //!   - The [`multi`] module types can be used to do set operations on multiple slices from zero up to an infinite number.
+//!   - The [`expr`] module types compose set operations lazily, their inputs can be iterators or other operations.
+//!   - The [`iter`] module types make set operations on iterators yielding owned elements, not slices.
 //!
 //! The [`duo`] operations are much more performant than [`multi`]
 //! so prefer using [`duo`] when you know that you will need set operations for two slices.
 //!
+//! Sets of integers can be stored in a compact binary form using the [`codec`] module.
+//!
 //! # Examples
 //!
 //! Using a [`duo`] _union_ set operation on two slices.
@@ -47,6 +51,26 @@
 //! # Ok(()) }
 //! # try_main().unwrap();
 //! ```
+//!
+//! Using the operators on two [`Set`]s, like the [`BTreeSet`](std::collections::BTreeSet) ones.
+//!
+//! ```
+//! # use sdset::Error;
+//! # fn try_main() -> Result<(), Error> {
+//! use sdset::{Set, SetBuf};
+//!
+//! let a = Set::new(&[1, 2, 4, 6, 7])?;
+//! let b = Set::new(&[2, 3, 4, 5, 6, 7])?;
+//!
+//! let res: SetBuf<i32> = a | b;
+//! assert_eq!(&res[..], &[1, 2, 3, 4, 5, 6, 7]);
+//!
+//! let mut res: SetBuf<i32> = a - b;
+//! res |= Set::new(&[3, 5])?;
+//! assert_eq!(&res[..], &[1, 3, 5]);
+//! # Ok(()) }
+//! # try_main().unwrap();
+//! ```
 
 #![warn(missing_docs)]
 
@@ -58,15 +82,22 @@ extern crate serde;
 #[cfg(test)]
 #[macro_use] extern crate quickcheck;
 
+pub mod codec;
 pub mod duo;
+pub mod expr;
+pub mod external;
+pub mod iter;
 pub mod multi;
 pub mod set;
+pub mod write;
 mod collection;
+mod cursor;
+mod heap_merge;
 mod two_minimums;
 
 use std::cmp::{self, Ordering};
-pub use crate::set::{Set, SetBuf, Error};
-pub use crate::collection::{Collection, Counter};
+pub use crate::set::{Set, SetBuf, SetRelation, Error};
+pub use crate::collection::{Collection, Counter, Limit, LimitError, Offset};
 
 /// Exponential searches this sorted slice for a given element.
 ///
@@ -191,8 +222,8 @@ fn exponential_offset_ge<'a, T>(slice: &'a [T], elem: &T) -> &'a [T]
 where T: Ord,
 {
     // linear search the first 3 elements
-    if slice.len() == 0 || slice[0].cmp(elem) != Ordering::Less {
-        return &slice;
+    if slice.is_empty() || slice[0].cmp(elem) != Ordering::Less {
+        return slice;
     }
     if slice.len() == 1 || slice[1].cmp(elem) != Ordering::Less {
         return &slice[1..];
@@ -218,6 +249,44 @@ where T: Ord,
     }
 }
 
+/// Given `slice` with elements sorted in increasing order,
+/// returns a subslice of `slice` ending at the last element that is
+/// less than or equal to the passed `elem`, searching from the end.
+/// If all elements are greater than `elem`, an empty slice will be returned.
+#[inline(always)]
+fn exponential_offset_le<'a, T>(slice: &'a [T], elem: &T) -> &'a [T]
+where T: Ord,
+{
+    let len = slice.len();
+
+    // linear search the last 3 elements
+    if len == 0 || slice[len - 1].cmp(elem) != Ordering::Greater {
+        return slice;
+    }
+    if len == 1 || slice[len - 2].cmp(elem) != Ordering::Greater {
+        return &slice[..len - 1];
+    }
+    if len == 2 || slice[len - 3].cmp(elem) != Ordering::Greater {
+        return &slice[..len - 2];
+    }
+
+    // exponential search backward until the threshold has been passed
+    let mut index = 4;
+    while index < len && slice[len - 1 - index].cmp(elem) == Ordering::Greater {
+        index *= 2;
+    }
+
+    // binary search between the current index (less than the threshold)
+    // and the previous index (greater than the threshold) to find the exact index
+    let start = len - 1 - cmp::min(index, len - 1);
+    let end = len - index / 2;
+
+    match slice[start..end].binary_search_by(|x| x.cmp(elem)) {
+        Ok(pos) => &slice[..start + pos + 1],
+        Err(pos) => &slice[..start + pos],
+    }
+}
+
 /// Given `slice` with elements sorted in increasing order,
 /// and f() which transforms elements of `slice` to the same type as `b`,
 /// returns a subslice of `slice` starting at the first element that f(element) is
@@ -229,8 +298,8 @@ where F: FnMut(&T) -> B,
       B: Ord,
 {
     // linear search the first 3 elements
-    if slice.len() == 0 || f(&slice[0]).cmp(b) != Ordering::Less {
-        return &slice;
+    if slice.is_empty() || f(&slice[0]).cmp(b) != Ordering::Less {
+        return slice;
     }
     if slice.len() == 1 || f(&slice[1]).cmp(b) != Ordering::Less {
         return &slice[1..];
@@ -250,10 +319,48 @@ where F: FnMut(&T) -> B,
     let half_bound = index / 2;
     let bound = cmp::min(index + 1, slice.len());
 
-    match slice[half_bound..bound].binary_search_by(|x| f(&x).cmp(b)) {
-        Ok(pos) => &slice[half_bound + pos..],
-        Err(pos) => &slice[half_bound + pos..],
+    // keys can be duplicated, the search must stop at the first equal one
+    let pos = slice[half_bound..bound].partition_point(|x| f(x).cmp(b) == Ordering::Less);
+    &slice[half_bound + pos..]
+}
+
+/// Given `slice` with elements sorted in increasing order,
+/// and f() which transforms elements of `slice` to the same type as `b`,
+/// returns a subslice of `slice` ending at the last element that f(element) is
+/// less than or equal to the passed `b`, searching from the end.
+/// If all elements are greater than `b`, an empty slice will be returned.
+#[inline(always)]
+fn exponential_offset_le_by_key<'a, T, B, F>(slice: &'a [T], b: &B, mut f: F) -> &'a [T]
+where F: FnMut(&T) -> B,
+      B: Ord,
+{
+    let len = slice.len();
+
+    // linear search the last 3 elements
+    if len == 0 || f(&slice[len - 1]).cmp(b) != Ordering::Greater {
+        return slice;
+    }
+    if len == 1 || f(&slice[len - 2]).cmp(b) != Ordering::Greater {
+        return &slice[..len - 1];
+    }
+    if len == 2 || f(&slice[len - 3]).cmp(b) != Ordering::Greater {
+        return &slice[..len - 2];
     }
+
+    // exponential search backward until the threshold has been passed
+    let mut index = 4;
+    while index < len && f(&slice[len - 1 - index]).cmp(b) == Ordering::Greater {
+        index *= 2;
+    }
+
+    // binary search between the current index (less than the threshold)
+    // and the previous index (greater than the threshold) to find the exact index
+    let start = len - 1 - cmp::min(index, len - 1);
+    let end = len - index / 2;
+
+    // keys can be duplicated, the search must stop after the last equal one
+    let pos = slice[start..end].partition_point(|x| f(x).cmp(b) != Ordering::Greater);
+    &slice[..start + pos]
 }
 
 /// Represent a type that can produce a set operation on multiple [`Set`]s.
@@ -270,6 +377,104 @@ pub trait SetOperation<T>: Sized {
     }
 }
 
+/// Represent a set operation that can count the number of elements
+/// it would produce without producing them.
+///
+/// The counts are the same as the ones given by a [`Counter`] but
+/// the operations take shortcuts where possible, e.g. the _union_ of two slices
+/// is counted with `|a| + |b| - |a ∩ b|` and the _intersection_ gallops over the slices.
+///
+/// The `multi` _intersection_ gallops over any number of slices, starting from the smallest one.
+/// The other `multi` operations only take shortcuts for zero, one or two slices
+/// and feed a [`Counter`] otherwise.
+///
+/// ```
+/// # use sdset::Error;
+/// # fn try_main() -> Result<(), Error> {
+/// use sdset::duo::OpBuilder;
+/// use sdset::{Cardinality, Set};
+///
+/// let a = Set::new(&[1, 2, 4, 6, 7])?;
+/// let b = Set::new(&[2, 3, 4, 5, 6, 7])?;
+///
+/// assert_eq!(OpBuilder::new(a, b).union().count(), 7);
+/// assert_eq!(OpBuilder::new(a, b).intersection().count(), 4);
+/// # Ok(()) }
+/// # try_main().unwrap();
+/// ```
+pub trait Cardinality {
+    /// Returns the number of elements that this set operation would produce.
+    fn count(self) -> usize;
+}
+
+/// Represent an iterator over sorted elements that can skip forward
+/// to an element without yielding the ones before it.
+///
+/// The iterators of the set operations and the [`Set`] one are seekable,
+/// they gallop over their slices to reach the target.
+/// The iterators of the by-key operations are seekable to a key.
+///
+/// ```
+/// # use sdset::Error;
+/// # fn try_main() -> Result<(), Error> {
+/// use sdset::duo::OpBuilder;
+/// use sdset::{SeekableIterator, Set};
+///
+/// let a = Set::new(&[1, 2, 4, 6, 7, 9])?;
+/// let b = Set::new(&[2, 3, 4, 5, 6, 7])?;
+///
+/// let mut iter = a.iter();
+/// iter.advance_to(&5);
+/// assert_eq!(iter.next(), Some(&6));
+///
+/// let mut iter = OpBuilder::new(a, b).intersection().into_iter();
+/// iter.advance_to(&5);
+/// assert_eq!(iter.next(), Some(&6));
+/// assert_eq!(iter.next(), Some(&7));
+/// assert_eq!(iter.next(), None);
+/// # Ok(()) }
+/// # try_main().unwrap();
+/// ```
+pub trait SeekableIterator<T>: Iterator {
+    /// Advance the iterator so that the next element it yields
+    /// is the first one greater than or equal to `target`.
+    ///
+    /// Nothing is done if the next element is already greater than or equal to `target`.
+    fn advance_to(&mut self, target: &T);
+}
+
+impl<'a, T: Ord> SeekableIterator<T> for std::slice::Iter<'a, T> {
+    fn advance_to(&mut self, target: &T) {
+        *self = exponential_offset_ge(self.as_slice(), target).iter();
+    }
+}
+
+impl<T, I: SeekableIterator<T> + ?Sized> SeekableIterator<T> for Box<I> {
+    fn advance_to(&mut self, target: &T) {
+        (**self).advance_to(target)
+    }
+}
+
+/// Collects the elements of `iter` calling `next` or `next_back`
+/// according to `sides`, cycling over it, in the order of the iterator.
+#[cfg(test)]
+fn collect_from_both_ends<I: DoubleEndedIterator>(mut iter: I, sides: &[bool]) -> Vec<I::Item> {
+    let mut front = Vec::new();
+    let mut back = Vec::new();
+    let mut sides = sides.iter().cycle();
+    loop {
+        let from_front = sides.next().cloned().unwrap_or(true);
+        match if from_front { iter.next() } else { iter.next_back() } {
+            Some(x) if from_front => front.push(x),
+            Some(x) => back.push(x),
+            None => break,
+        }
+    }
+    back.reverse();
+    front.append(&mut back);
+    front
+}
+
 #[cfg(all(feature = "unstable", test))]
 mod bench {
     mod _btree {
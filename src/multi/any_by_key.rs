@@ -0,0 +1,556 @@
+use std::cmp;
+use crate::set::{Set, vec_sets_into_slices};
+use crate::{SetOperation, SeekableIterator, Collection, exponential_offset_ge_by_key, exponential_offset_le_by_key};
+
+/// Represent the _any_ set operation that will be applied to multiple slices
+/// of two different types.
+///
+/// Note that the result is all the elements of the base slice
+/// which have a key that can be found in at least one of the other slices,
+/// elements of the other slices are never emitted. This is a filter of the base slice,
+/// not a union like the one of [`duo::OpBuilderByKey`](crate::duo::OpBuilderByKey).
+///
+/// # Examples
+/// ```
+/// # use sdset::Error;
+/// # fn try_main() -> Result<(), Error> {
+/// use sdset::multi::OpBuilderByKey;
+/// use sdset::{SetOperation, Set, SetBuf};
+///
+/// #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+/// struct Foo { a: i32, b: u8 }
+///
+/// let a = Set::new(&[
+///     Foo{ a: 1, b: 6 },
+///     Foo{ a: 1, b: 7 },
+///     Foo{ a: 2, b: 9 },
+///     Foo{ a: 4, b: 10 },
+///     Foo{ a: 8, b: 10 },
+/// ])?;
+/// let b = Set::new(&[2, 3, 5, 7])?;
+/// let c = Set::new(&[4, 6, 7])?;
+///
+/// // Return the field of Foo that will be used for comparison
+/// let f = |x: &Foo| x.a;
+///
+/// // directly use the i32 for comparison
+/// let g = |x: &i32| *x;
+///
+/// let op = OpBuilderByKey::from_vec(a, vec![b, c], f, g).any();
+/// let res: SetBuf<Foo> = op.into_set_buf();
+///
+/// assert_eq!(&res[..], &[Foo{ a: 2, b: 9 }, Foo{ a: 4, b: 10 }]);
+/// # Ok(()) }
+/// # try_main().unwrap();
+/// ```
+#[derive(Clone)]
+pub struct AnyByKey<'a, T: 'a, U: 'a, F, G, K>
+where F: Fn(&T) -> K,
+      G: Fn(&U) -> K,
+      K: Ord,
+{
+    base: &'a [T],
+    others: Vec<&'a [U]>,
+    f: F,
+    g: G,
+}
+
+impl<'a, T, U, F, G, K> AnyByKey<'a, T, U, F, G, K>
+where F: Fn(&T) -> K,
+      G: Fn(&U) -> K,
+      K: Ord,
+{
+    /// Construct one with slices checked to be sorted and deduplicated.
+    pub fn new(base: &'a Set<T>, others: Vec<&'a Set<U>>, f: F, g: G) -> Self {
+        Self {
+            base: base.as_slice(),
+            others: vec_sets_into_slices(others),
+            f,
+            g,
+        }
+    }
+}
+
+impl<'a, T, U, F, G, K> AnyByKey<'a, T, U, F, G, K>
+where F: Fn(&T) -> K,
+      G: Fn(&U) -> K,
+      K: Ord,
+{
+    fn extend_collection<C, X, E>(mut self, output: &mut C, extend: E) -> Result<(), C::Error>
+    where C: Collection<X>,
+          E: Fn(&mut C, &'a [T]) -> Result<(), C::Error>,
+    {
+        while let Some(first) = self.base.first().map(|x| (self.f)(x)) {
+            let mut minimum = None;
+            for slice in self.others.iter_mut() {
+                *slice = exponential_offset_ge_by_key(slice, &first, &self.g);
+
+                let first = match slice.first() {
+                    Some(first) => Some((self.g)(first)),
+                    None => None,
+                };
+
+                minimum = match (minimum, first) {
+                    (Some(min), Some(first)) => Some(cmp::min(min, first)),
+                    (None, Some(first)) => Some(first),
+                    (min, _) => min,
+                };
+            }
+
+            match minimum {
+                Some(min) if min == first => {
+                    let off = self.base.iter().take_while(|&x| (self.f)(x) == min).count();
+                    extend(output, &self.base[..off])?;
+
+                    self.base = &self.base[off..];
+                },
+                Some(min) => {
+                    self.base = exponential_offset_ge_by_key(self.base, &min, &self.f);
+                },
+                None => break,
+            }
+        }
+        Ok(())
+    }
+
+    fn iter(&'a self) -> AnyByKeyIter<'a, T, U, F, G, K>
+    {
+        AnyByKeyIter {
+            base: self.base,
+            others: self.others.clone(),
+            f: &self.f,
+            g: &self.g,
+        }
+    }
+}
+
+impl<'a, T, U, F, G, K> SetOperation<T> for AnyByKey<'a, T, U, F, G, K>
+where T: Clone,
+      F: Fn(&T) -> K,
+      G: Fn(&U) -> K,
+      K: Ord,
+{
+    fn extend_collection<C>(self, output: &mut C) -> Result<(), C::Error>
+    where C: Collection<T>,
+    {
+        self.extend_collection(output, Collection::extend_from_slice)
+    }
+}
+
+impl<'a, T, U, F, G, K> SetOperation<&'a T> for AnyByKey<'a, T, U, F, G, K>
+where F: Fn(&T) -> K,
+      G: Fn(&U) -> K,
+      K: Ord,
+{
+    fn extend_collection<C>(self, output: &mut C) -> Result<(), C::Error>
+    where C: Collection<&'a T>,
+    {
+        self.extend_collection(output, Collection::extend)
+    }
+}
+
+// This version of IntoIterator takes references to the functions (f/g).
+// See DifferenceByKey for the reason of the separate iterator structs.
+impl<'a, T, U, F, G, K> IntoIterator for &'a AnyByKey<'a, T, U, F, G, K>
+where F: Fn(&T) -> K,
+      G: Fn(&U) -> K,
+      K: Ord,
+{
+    type Item = &'a T;
+    type IntoIter = AnyByKeyIter<'a, T, U, F, G, K>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct AnyByKeyIter<'a, T, U, F, G, K>
+where
+    T: 'a,
+    U: 'a,
+    F: Fn(&T) -> K,
+    G: Fn(&U) -> K,
+    K: Ord,
+{
+    base: &'a [T],
+    others: Vec<&'a [U]>,
+    f: &'a F,
+    g: &'a G,
+}
+
+impl<'a, T, U, F, G, K> Iterator for AnyByKeyIter<'a, T, U, F, G, K>
+where
+    T: 'a,
+    U: 'a,
+    F: Fn(&T) -> K,
+    G: Fn(&U) -> K,
+    K: Ord,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        next_by_key(&mut self.base, &mut self.others, self.f, self.g)
+    }
+}
+
+impl<'a, T, U, F, G, K> DoubleEndedIterator for AnyByKeyIter<'a, T, U, F, G, K>
+where
+    T: 'a,
+    U: 'a,
+    F: Fn(&T) -> K,
+    G: Fn(&U) -> K,
+    K: Ord,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        next_back_by_key(&mut self.base, &mut self.others, self.f, self.g)
+    }
+}
+
+impl<'a, T, U, F, G, K> SeekableIterator<K> for AnyByKeyIter<'a, T, U, F, G, K>
+where
+    T: 'a,
+    U: 'a,
+    F: Fn(&T) -> K,
+    G: Fn(&U) -> K,
+    K: Ord,
+{
+    fn advance_to(&mut self, target: &K) {
+        self.base = exponential_offset_ge_by_key(self.base, target, self.f);
+        for slice in &mut self.others {
+            *slice = exponential_offset_ge_by_key(slice, target, self.g);
+        }
+    }
+}
+
+// This version of IntoIterator moves the contents of self into the iterator.
+// Therefore the iterator owns the functions (f/g).
+impl<'a, T, U, F, G, K> IntoIterator for AnyByKey<'a, T, U, F, G, K>
+where F: Fn(&T) -> K,
+      G: Fn(&U) -> K,
+      K: Ord,
+{
+    type Item = &'a T;
+    type IntoIter = AnyByKeyIterOwning<'a, T, U, F, G, K>;
+    fn into_iter(self) -> Self::IntoIter {
+        Self::IntoIter {
+            base: self.base,
+            others: self.others,
+            f: self.f,
+            g: self.g,
+        }
+    }
+}
+
+pub struct AnyByKeyIterOwning<'a, T, U, F, G, K>
+where
+    T: 'a,
+    U: 'a,
+    F: Fn(&T) -> K,
+    G: Fn(&U) -> K,
+    K: Ord,
+{
+    base: &'a [T],
+    others: Vec<&'a [U]>,
+    f: F,
+    g: G,
+}
+
+impl<'a, T, U, F, G, K> Iterator for AnyByKeyIterOwning<'a, T, U, F, G, K>
+where
+    T: 'a,
+    U: 'a,
+    F: Fn(&T) -> K,
+    G: Fn(&U) -> K,
+    K: Ord,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        next_by_key(&mut self.base, &mut self.others, &self.f, &self.g)
+    }
+}
+
+impl<'a, T, U, F, G, K> DoubleEndedIterator for AnyByKeyIterOwning<'a, T, U, F, G, K>
+where
+    T: 'a,
+    U: 'a,
+    F: Fn(&T) -> K,
+    G: Fn(&U) -> K,
+    K: Ord,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        next_back_by_key(&mut self.base, &mut self.others, &self.f, &self.g)
+    }
+}
+
+impl<'a, T, U, F, G, K> SeekableIterator<K> for AnyByKeyIterOwning<'a, T, U, F, G, K>
+where
+    T: 'a,
+    U: 'a,
+    F: Fn(&T) -> K,
+    G: Fn(&U) -> K,
+    K: Ord,
+{
+    fn advance_to(&mut self, target: &K) {
+        self.base = exponential_offset_ge_by_key(self.base, target, &self.f);
+        for slice in &mut self.others {
+            *slice = exponential_offset_ge_by_key(slice, target, &self.g);
+        }
+    }
+}
+
+#[inline]
+fn next_by_key<'a, T, U, F, G, K>(base: &mut &'a [T], others: &mut [&'a [U]], f: &F, g: &G) -> Option<&'a T>
+where F: Fn(&T) -> K,
+      G: Fn(&U) -> K,
+      K: Ord,
+{
+    loop {
+        let first_base = f(base.first()?);
+        let mut minimum = None;
+        for slice in others.iter_mut() {
+            *slice = exponential_offset_ge_by_key(slice, &first_base, g);
+            if let Some(first) = slice.first() {
+                let first_other = g(first);
+                minimum = match minimum {
+                    Some(min) => Some(cmp::min(min, first_other)),
+                    None => Some(first_other),
+                };
+            }
+        }
+
+        match minimum {
+            Some(min) if min == first_base => {
+                let result = &base[0];
+                *base = &base[1..];
+                return Some(result);
+            },
+            Some(min) => {
+                *base = exponential_offset_ge_by_key(base, &min, f);
+            },
+            None => {
+                *base = &[];
+                return None;
+            },
+        }
+    }
+}
+
+#[inline]
+fn next_back_by_key<'a, T, U, F, G, K>(base: &mut &'a [T], others: &mut [&'a [U]], f: &F, g: &G) -> Option<&'a T>
+where F: Fn(&T) -> K,
+      G: Fn(&U) -> K,
+      K: Ord,
+{
+    loop {
+        let last_base = f(base.last()?);
+        let mut maximum = None;
+        for slice in others.iter_mut() {
+            *slice = exponential_offset_le_by_key(slice, &last_base, g);
+            if let Some(last) = slice.last() {
+                let last_other = g(last);
+                maximum = match maximum {
+                    Some(max) => Some(cmp::max(max, last_other)),
+                    None => Some(last_other),
+                };
+            }
+        }
+
+        match maximum {
+            Some(max) if max == last_base => {
+                let result = &base[base.len() - 1];
+                *base = &base[..base.len() - 1];
+                return Some(result);
+            },
+            Some(max) => {
+                *base = exponential_offset_le_by_key(base, &max, f);
+            },
+            None => {
+                *base = &[];
+                return None;
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod set_to_set {
+        use super::super::*;
+        use crate::set::{sort_dedup_vec, SetBuf};
+
+        #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+        struct Foo {
+            a: i32,
+            b: i8,
+        }
+
+        impl Foo {
+            fn new(a: i32) -> Foo {
+                Foo { a, b: 0 }
+            }
+        }
+
+        #[test]
+        fn one_slice() {
+            let a = &[Foo::new(1), Foo::new(2), Foo::new(3)];
+
+            let op = AnyByKey { base: a, others: vec![], f: |x| x.a, g: |&x| x };
+            let res: SetBuf<Foo> = op.into_set_buf();
+            assert_eq!(&res[..], &[]);
+        }
+
+        #[test]
+        fn two_slices() {
+            let a = &[Foo::new(1), Foo::new(2), Foo::new(3)];
+            let b = &[2, 4];
+
+            let op = AnyByKey { base: a, others: vec![b], f: |x| x.a, g: |&x| x };
+            let res: SetBuf<Foo> = op.into_set_buf();
+            assert_eq!(&res[..], &[Foo::new(2)]);
+        }
+
+        #[test]
+        fn three_slices_duplicate_relations() {
+            let a = &[Foo::new(1), Foo::new(3), Foo { a: 3, b: 1 }, Foo::new(5), Foo::new(6)];
+            let b = &[2, 3, 4];
+            let c = &[4, 5, 7];
+
+            let op = AnyByKey { base: a, others: vec![b, c], f: |x| x.a, g: |&x| x };
+            let res: SetBuf<Foo> = op.into_set_buf();
+            assert_eq!(&res[..], &[Foo::new(3), Foo { a: 3, b: 1 }, Foo::new(5)]);
+        }
+
+        quickcheck! {
+            fn qc_any(base: Vec<i32>, xss: Vec<Vec<i64>>) -> bool {
+                use std::collections::BTreeSet;
+                use std::iter::FromIterator;
+
+                let mut base = base;
+                let mut xss = xss;
+
+                sort_dedup_vec(&mut base);
+
+                for xs in &mut xss {
+                    sort_dedup_vec(xs);
+                }
+
+                let x: SetBuf<i32> = {
+                    let xss = xss.iter().map(|xs| xs.as_slice()).collect();
+                    AnyByKey { base: &base, others: xss, f: |&x| x, g: |&x| x as i32 }.into_set_buf()
+                };
+
+                let mut union = BTreeSet::new();
+                for v in xss {
+                    union = &union | &BTreeSet::from_iter(v.into_iter().map(|x| x as i32));
+                }
+                let y = BTreeSet::from_iter(base);
+                let y: Vec<_> = y.intersection(&union).cloned().collect();
+
+                x.as_slice() == y.as_slice()
+            }
+        }
+    }
+
+    mod set_to_iter {
+        use super::super::*;
+        use crate::set::sort_dedup_vec;
+
+        #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+        struct Foo {
+            a: i32,
+            b: i8,
+        }
+
+        impl Foo {
+            fn new(a: i32) -> Foo {
+                Foo { a, b: 0 }
+            }
+        }
+
+        #[test]
+        fn three_slices_duplicate_relations() {
+            let a = &[Foo::new(1), Foo::new(3), Foo { a: 3, b: 1 }, Foo::new(5), Foo::new(6)];
+            let b = &[2, 3, 4];
+            let c = &[4, 5, 7];
+
+            let any = AnyByKey { base: a, others: vec![b, c], f: |x| x.a, g: |&x| x };
+            let any_ref: Vec<Foo> = any.iter().cloned().collect();
+            assert_eq!(&any_ref[..], &[Foo::new(3), Foo { a: 3, b: 1 }, Foo::new(5)]);
+            let any_own: Vec<Foo> = any.into_iter().cloned().collect();
+            assert_eq!(&any_own[..], &[Foo::new(3), Foo { a: 3, b: 1 }, Foo::new(5)]);
+        }
+
+        quickcheck! {
+            fn qc_advance_to(base: Vec<(i8, i8)>, xss: Vec<Vec<i8>>, target: i8) -> bool {
+                let mut base = base;
+                let mut xss = xss;
+
+                sort_dedup_vec(&mut base);
+                for xs in &mut xss {
+                    sort_dedup_vec(xs);
+                }
+
+                let others = xss.iter().map(|xs| xs.as_slice()).collect();
+                let op = AnyByKey { base: &base, others, f: |x: &(i8, i8)| x.0, g: |&x: &i8| x };
+                let expected: Vec<_> = op.iter().filter(|x| x.0 >= target).collect();
+
+                let mut iter = op.iter();
+                iter.advance_to(&target);
+                let x: Vec<_> = iter.collect();
+
+                let mut iter = op.clone().into_iter();
+                iter.advance_to(&target);
+                let y: Vec<_> = iter.collect();
+
+                x == expected && y == expected
+            }
+
+            fn qc_next_and_next_back(base: Vec<(i8, i8)>, xss: Vec<Vec<i8>>, sides: Vec<bool>) -> bool {
+                use crate::collect_from_both_ends;
+
+                let mut base = base;
+                let mut xss = xss;
+
+                sort_dedup_vec(&mut base);
+                for xs in &mut xss {
+                    sort_dedup_vec(xs);
+                }
+
+                let others = xss.iter().map(|xs| xs.as_slice()).collect();
+                let op = AnyByKey { base: &base, others, f: |x: &(i8, i8)| x.0, g: |&x: &i8| x };
+                let expected: Vec<_> = op.iter().collect();
+
+                collect_from_both_ends(op.iter(), &sides) == expected
+                    && collect_from_both_ends(op.clone().into_iter(), &sides) == expected
+            }
+
+            fn qc_any(base: Vec<i32>, xss: Vec<Vec<i64>>) -> bool {
+                use std::collections::BTreeSet;
+                use std::iter::FromIterator;
+
+                let mut base = base;
+                let mut xss = xss;
+
+                sort_dedup_vec(&mut base);
+
+                for xs in &mut xss {
+                    sort_dedup_vec(xs);
+                }
+
+                let x: Vec<i32> = {
+                    let xss = xss.iter().map(|xs| xs.as_slice()).collect();
+                    AnyByKey { base: &base, others: xss, f: |&x| x, g: |&x| x as i32 }.into_iter().cloned().collect()
+                };
+
+                let mut union = BTreeSet::new();
+                for v in xss {
+                    union = &union | &BTreeSet::from_iter(v.into_iter().map(|x| x as i32));
+                }
+                let y = BTreeSet::from_iter(base);
+                let y: Vec<_> = y.intersection(&union).cloned().collect();
+
+                x.as_slice() == y.as_slice()
+            }
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use std::cmp;
 use crate::set::{Set, vec_sets_into_slices};
-use crate::{SetOperation, Collection, exponential_offset_ge};
+use crate::{SetOperation, SeekableIterator, Collection, Cardinality, Counter, exponential_offset_ge, exponential_offset_le};
+use crate::duo;
 
 /// Represent the _difference_ set operation that will be applied to the slices.
 ///
@@ -104,6 +105,21 @@ impl<'a, T: Ord> SetOperation<&'a T> for Difference<'a, T> {
     }
 }
 
+impl<'a, T: Ord> Cardinality for Difference<'a, T> {
+    fn count(self) -> usize {
+        match self.slices.as_slice() {
+            [] => 0,
+            [a] => a.len(),
+            [a, b] => duo::Difference::new(Set::new_unchecked(a), Set::new_unchecked(b)).count(),
+            _ => {
+                let mut counter = Counter::<&'a T>::new();
+                self.extend_collection(&mut counter, Collection::extend).unwrap();
+                counter.get()
+            },
+        }
+    }
+}
+
 impl<'a, T: Ord> IntoIterator for Difference<'a, T> {
     type Item = &'a T;
     type IntoIter = DifferenceIter<'a, T>;
@@ -130,10 +146,7 @@ impl<'a, T: Ord> Iterator for DifferenceIter<'a, T> {
     type Item = &'a T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let (base, others) = match self.slices.split_first_mut() {
-            Some(split) => split,
-            None => return None,
-        };
+        let (base, others) = self.slices.split_first_mut()?;
 
         loop {
             if base.is_empty() {
@@ -164,6 +177,34 @@ impl<'a, T: Ord> Iterator for DifferenceIter<'a, T> {
     }
 }
 
+impl<'a, T: Ord> DoubleEndedIterator for DifferenceIter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (base, others) = self.slices.split_first_mut()?;
+
+        while let Some((last, rest)) = base.split_last() {
+            *base = rest;
+            let found = others.iter_mut().any(|slice| {
+                *slice = exponential_offset_le(slice, last);
+                slice.last() == Some(last)
+            });
+            if !found {
+                return Some(last);
+            }
+        }
+
+        None
+    }
+}
+
+impl<'a, T: Ord> SeekableIterator<T> for DifferenceIter<'a, T> {
+    fn advance_to(&mut self, target: &T) {
+        // the other slices are skipped when searching for the next element
+        if let Some(base) = self.slices.first_mut() {
+            *base = exponential_offset_ge(base, target);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     mod set_to_set {
@@ -221,6 +262,23 @@ mod tests {
         }
 
         quickcheck! {
+            fn qc_count(xss: Vec<Vec<i32>>) -> bool {
+                use crate::{Cardinality, Counter};
+
+                let mut xss = xss;
+
+                for xs in &mut xss {
+                    sort_dedup_vec(xs);
+                }
+
+                let slices: Vec<_> = xss.iter().map(|xs| xs.as_slice()).collect();
+
+                let mut counter = Counter::<i32>::new();
+                SetOperation::extend_collection(Difference { slices: slices.clone() }, &mut counter).unwrap();
+
+                Difference { slices }.count() == counter.get()
+            }
+
             fn qc_difference(xss: Vec<Vec<i32>>) -> bool {
                 use std::collections::BTreeSet;
                 use std::iter::FromIterator;
@@ -357,6 +415,76 @@ mod tests {
 
                 x.as_slice() == y.as_slice()
             }
+
+            fn qc_rev_difference(xss: Vec<Vec<i32>>) -> bool {
+                use std::collections::BTreeSet;
+                use std::iter::FromIterator;
+
+                // FIXME temporary hack (can have mutable parameters!)
+                let mut xss = xss;
+
+                // small values so that the slices share elements
+                for xs in &mut xss {
+                    xs.iter_mut().for_each(|x| *x %= 16);
+                    sort_dedup_vec(xs);
+                }
+
+                let x: Vec<i32> = {
+                    let xss = xss.iter().map(|xs| xs.as_slice()).collect();
+                    Difference { slices: xss }.into_iter().rev().cloned().collect()
+                };
+
+                let mut xss = xss.into_iter();
+                let mut y = match xss.next() {
+                    Some(xs) => BTreeSet::from_iter(xs),
+                    None => BTreeSet::new(),
+                };
+
+                for v in xss {
+                    let x = BTreeSet::from_iter(v.iter().cloned());
+                    y = y.difference(&x).cloned().collect();
+                }
+                let y: Vec<_> = y.into_iter().collect();
+
+                x.iter().eq(y.iter().rev())
+            }
+
+            fn qc_advance_to(xss: Vec<Vec<i32>>, target: i32) -> bool {
+                use std::collections::BTreeSet;
+                use std::iter::FromIterator;
+                use crate::SeekableIterator;
+
+                // FIXME temporary hack (can have mutable parameters!)
+                let mut xss = xss;
+
+                // small values so that the slices share elements
+                for xs in &mut xss {
+                    xs.iter_mut().for_each(|x| *x %= 16);
+                    sort_dedup_vec(xs);
+                }
+
+                let target = target % 16;
+                let x: Vec<i32> = {
+                    let xss = xss.iter().map(|xs| xs.as_slice()).collect();
+                    let mut iter = Difference { slices: xss }.into_iter();
+                    iter.advance_to(&target);
+                    iter.cloned().collect()
+                };
+
+                let mut xss = xss.into_iter();
+                let mut y = match xss.next() {
+                    Some(xs) => BTreeSet::from_iter(xs),
+                    None => BTreeSet::new(),
+                };
+
+                for v in xss {
+                    let x = BTreeSet::from_iter(v.iter().cloned());
+                    y = y.difference(&x).cloned().collect();
+                }
+                let y: Vec<_> = y.into_iter().filter(|&y| y >= target).collect();
+
+                x.as_slice() == y.as_slice()
+            }
         }
     }
 }
@@ -1,6 +1,6 @@
 use std::cmp;
 use crate::set::{Set, vec_sets_into_slices};
-use crate::{SetOperation, Collection, exponential_offset_ge_by_key};
+use crate::{SetOperation, SeekableIterator, Collection, exponential_offset_ge_by_key, exponential_offset_le_by_key};
 
 /// Represent the _difference_ set operation that will be applied to multiple slices
 /// of two different types.
@@ -61,8 +61,8 @@ where F: Fn(&T) -> K,
         Self {
             base: base.as_slice(),
             others: vec_sets_into_slices(others),
-            f: f,
-            g: g,
+            f,
+            g,
         }
     }
 }
@@ -225,6 +225,35 @@ where
     }
 }
 
+impl<'a, T, U, F, G, K> DoubleEndedIterator for DifferenceByKeyIter<'a, T, U, F, G, K>
+where
+    T: 'a,
+    U: 'a,
+    F: Fn(&T) -> K,
+    G: Fn(&U) -> K,
+    K: Ord,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        next_back_by_key(&mut self.base, &mut self.others, self.f, self.g)
+    }
+}
+
+impl<'a, T, U, F, G, K> SeekableIterator<K> for DifferenceByKeyIter<'a, T, U, F, G, K>
+where
+    T: 'a,
+    U: 'a,
+    F: Fn(&T) -> K,
+    G: Fn(&U) -> K,
+    K: Ord,
+{
+    fn advance_to(&mut self, target: &K) {
+        self.base = exponential_offset_ge_by_key(self.base, target, self.f);
+        for slice in &mut self.others {
+            *slice = exponential_offset_ge_by_key(slice, target, self.g);
+        }
+    }
+}
+
 // This version of IntoIterator moves the contents of self into the iterator.
 // Therefore the iterator owns the functions (f/g).
 // The separate structs are required to not break the public API which takes the functions
@@ -306,6 +335,64 @@ where
     }
 }
 
+impl<'a, T, U, F, G, K> DoubleEndedIterator for DifferenceByKeyIterOwning<'a, T, U, F, G, K>
+where
+    T: 'a,
+    U: 'a,
+    F: Fn(&T) -> K,
+    G: Fn(&U) -> K,
+    K: Ord,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        next_back_by_key(&mut self.base, &mut self.others, &self.f, &self.g)
+    }
+}
+
+impl<'a, T, U, F, G, K> SeekableIterator<K> for DifferenceByKeyIterOwning<'a, T, U, F, G, K>
+where
+    T: 'a,
+    U: 'a,
+    F: Fn(&T) -> K,
+    G: Fn(&U) -> K,
+    K: Ord,
+{
+    fn advance_to(&mut self, target: &K) {
+        self.base = exponential_offset_ge_by_key(self.base, target, &self.f);
+        for slice in &mut self.others {
+            *slice = exponential_offset_ge_by_key(slice, target, &self.g);
+        }
+    }
+}
+
+#[inline]
+fn next_back_by_key<'a, T, U, F, G, K>(base: &mut &'a [T], others: &mut [&'a [U]], f: &F, g: &G) -> Option<&'a T>
+where F: Fn(&T) -> K,
+      G: Fn(&U) -> K,
+      K: Ord,
+{
+    loop {
+        let last_base = base.last()?;
+        let key = f(last_base);
+        *base = &base[..base.len() - 1];
+
+        let mut maximum = None;
+        for slice in others.iter_mut() {
+            *slice = exponential_offset_le_by_key(slice, &key, g);
+            if let Some(last) = slice.last() {
+                let last_other = g(last);
+                maximum = match maximum {
+                    Some(max) => Some(cmp::max(max, last_other)),
+                    None => Some(last_other),
+                };
+            }
+        }
+
+        if maximum != Some(key) {
+            return Some(last_base);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     mod set_to_set {
@@ -481,6 +568,49 @@ mod tests {
         }
 
         quickcheck! {
+            fn qc_advance_to(base: Vec<(i8, i8)>, xss: Vec<Vec<i8>>, target: i8) -> bool {
+                let mut base = base;
+                let mut xss = xss;
+
+                sort_dedup_vec(&mut base);
+                for xs in &mut xss {
+                    sort_dedup_vec(xs);
+                }
+
+                let others = xss.iter().map(|xs| xs.as_slice()).collect();
+                let op = DifferenceByKey { base: &base, others, f: |x: &(i8, i8)| x.0, g: |&x: &i8| x };
+                let expected: Vec<_> = op.iter().filter(|x| x.0 >= target).collect();
+
+                let mut iter = op.iter();
+                iter.advance_to(&target);
+                let x: Vec<_> = iter.collect();
+
+                let mut iter = op.clone().into_iter();
+                iter.advance_to(&target);
+                let y: Vec<_> = iter.collect();
+
+                x == expected && y == expected
+            }
+
+            fn qc_next_and_next_back(base: Vec<(i8, i8)>, xss: Vec<Vec<i8>>, sides: Vec<bool>) -> bool {
+                use crate::collect_from_both_ends;
+
+                let mut base = base;
+                let mut xss = xss;
+
+                sort_dedup_vec(&mut base);
+                for xs in &mut xss {
+                    sort_dedup_vec(xs);
+                }
+
+                let others = xss.iter().map(|xs| xs.as_slice()).collect();
+                let op = DifferenceByKey { base: &base, others, f: |x: &(i8, i8)| x.0, g: |&x: &i8| x };
+                let expected: Vec<_> = op.iter().collect();
+
+                collect_from_both_ends(op.iter(), &sides) == expected
+                    && collect_from_both_ends(op.clone().into_iter(), &sides) == expected
+            }
+
             fn qc_difference(base: Vec<i32>, xss: Vec<Vec<i64>>) -> bool {
                 use std::collections::BTreeSet;
                 use std::iter::FromIterator;
@@ -0,0 +1,145 @@
+use crate::codec::CompressedSet;
+use crate::cursor::Cursor;
+use crate::{SetOperation, SeekableIterator, Collection};
+
+/// Represent the _difference_ set operation that will be applied to compressed sets.
+///
+/// The elements of the first set are searched in the other ones by galloping
+/// through them, only the parts that may contain those elements are decoded.
+///
+/// # Examples
+/// ```
+/// # use sdset::Error;
+/// # fn try_main() -> Result<(), Error> {
+/// use sdset::codec::BlockSet;
+/// use sdset::multi::OpBuilderCompressed;
+/// use sdset::{SetOperation, Set, SetBuf};
+///
+/// let a = BlockSet::new(Set::new(&[1u32, 2, 4, 6, 7])?);
+/// let b = BlockSet::new(Set::new(&[2u32, 3, 5])?);
+/// let c = BlockSet::new(Set::new(&[4u32, 8])?);
+///
+/// let op = OpBuilderCompressed::from_vec(vec![&a, &b, &c]).difference();
+///
+/// let res: SetBuf<u32> = op.into_set_buf();
+/// assert_eq!(&res[..], &[1, 6, 7]);
+/// # Ok(()) }
+/// # try_main().unwrap();
+/// ```
+#[derive(Clone)]
+pub struct DifferenceCompressed<'a, S: 'a> {
+    sets: Vec<&'a S>,
+}
+
+impl<'a, S> DifferenceCompressed<'a, S> {
+    /// Construct one with compressed sets, the first one is the base set.
+    pub fn new(sets: Vec<&'a S>) -> Self {
+        Self { sets }
+    }
+}
+
+impl<'a, S: CompressedSet> DifferenceCompressed<'a, S> {
+    fn iter(&self) -> DifferenceCompressedIter<'a, S> {
+        let mut iters = self.sets.iter().map(|set| Cursor::new(set.iter()));
+        DifferenceCompressedIter {
+            base: iters.next(),
+            others: iters.collect(),
+        }
+    }
+}
+
+impl<'a, S: CompressedSet> SetOperation<S::Item> for DifferenceCompressed<'a, S> {
+    fn extend_collection<C>(self, output: &mut C) -> Result<(), C::Error>
+    where C: Collection<S::Item>,
+    {
+        output.extend(self)
+    }
+}
+
+impl<'a, S: CompressedSet> IntoIterator for DifferenceCompressed<'a, S> {
+    type Item = S::Item;
+    type IntoIter = DifferenceCompressedIter<'a, S>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, S: CompressedSet> IntoIterator for &'a DifferenceCompressed<'a, S> {
+    type Item = S::Item;
+    type IntoIter = DifferenceCompressedIter<'a, S>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct DifferenceCompressedIter<'a, S: CompressedSet + 'a> {
+    base: Option<Cursor<S::Iter<'a>>>,
+    others: Vec<Cursor<S::Iter<'a>>>,
+}
+
+impl<'a, S: CompressedSet> Iterator for DifferenceCompressedIter<'a, S> {
+    type Item = S::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let base = self.base.as_mut()?;
+        loop {
+            let x = base.bump()?;
+            let found = self.others.iter_mut().any(|iter| {
+                iter.advance_to(&x);
+                iter.peek() == Some(&x)
+            });
+            if !found {
+                return Some(x);
+            }
+        }
+    }
+}
+
+impl<'a, S: CompressedSet> SeekableIterator<S::Item> for DifferenceCompressedIter<'a, S> {
+    fn advance_to(&mut self, target: &S::Item) {
+        // the other sets are skipped when searching for the next element
+        if let Some(base) = &mut self.base {
+            base.advance_to(target);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec::BlockSet;
+    use crate::set::{sort_dedup_vec, Set};
+
+    quickcheck! {
+        fn qc_difference(xss: Vec<Vec<u32>>) -> bool {
+            use std::collections::BTreeSet;
+            use std::iter::FromIterator;
+
+            // overlap the sets and make some of them span multiple blocks
+            let mut xss: Vec<Vec<u32>> = xss.into_iter().enumerate().map(|(i, xs)| {
+                xs.into_iter().flat_map(|x| (0..(i as u32 % 3) * 50 + 1).map(move |j| x % 16 * 100 + j)).collect()
+            }).collect();
+            for xs in &mut xss {
+                sort_dedup_vec(xs);
+            }
+
+            let x: Vec<u32> = {
+                let sets: Vec<_> = xss.iter().map(|xs| BlockSet::new(Set::new_unchecked(xs))).collect();
+                DifferenceCompressed::new(sets.iter().collect()).into_set_buf().into_vec()
+            };
+
+            let mut xss = xss.into_iter();
+            let mut y = match xss.next() {
+                Some(xs) => BTreeSet::from_iter(xs),
+                None => BTreeSet::new(),
+            };
+            for xs in xss {
+                let xs = BTreeSet::from_iter(xs);
+                y = BTreeSet::from_iter(y.difference(&xs).cloned());
+            }
+            let y: Vec<_> = y.into_iter().collect();
+
+            x.as_slice() == y.as_slice()
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use crate::set::{Set, vec_sets_into_slices};
-use crate::{SetOperation, Collection, exponential_offset_ge};
+use crate::{SetOperation, SeekableIterator, Collection, Cardinality, exponential_offset_ge, exponential_offset_le};
+use crate::duo;
 
 use self::Equality::*;
 
@@ -63,7 +64,7 @@ fn test_equality<'a, T: Ord>(slices: &[&'a [T]]) -> Equality<'a, T> {
 
 impl<'a, T: Ord> Intersection<'a, T> {
     #[inline]
-    fn extend_collection<C, U, F>(mut self, output: &mut C, push: F) -> Result<(), C::Error>
+    pub(super) fn extend_collection<C, U, F>(mut self, output: &mut C, push: F) -> Result<(), C::Error>
     where C: Collection<U>,
           F: Fn(&mut C, &'a T) -> Result<(), C::Error>,
     {
@@ -113,6 +114,36 @@ impl<'a, T: Ord> SetOperation<&'a T> for Intersection<'a, T> {
     }
 }
 
+impl<'a, T: Ord> Cardinality for Intersection<'a, T> {
+    fn count(self) -> usize {
+        match self.slices.as_slice() {
+            [] => 0,
+            [a] => a.len(),
+            [a, b] => duo::Intersection::new(Set::new_unchecked(a), Set::new_unchecked(b)).count(),
+            _ => {
+                // walk the smallest slice and gallop the others to each of its elements
+                let mut slices = self.slices;
+                slices.sort_unstable_by_key(|s| s.len());
+                let (first, others) = slices.split_first_mut().unwrap();
+
+                let mut count = 0;
+                'elems: for x in first.iter() {
+                    for slice in others.iter_mut() {
+                        *slice = exponential_offset_ge(slice, x);
+                        match slice.first() {
+                            Some(y) if y == x => (),
+                            Some(_) => continue 'elems,
+                            None => break 'elems,
+                        }
+                    }
+                    count += 1;
+                }
+                count
+            },
+        }
+    }
+}
+
 impl<'a, T: Ord> IntoIterator for Intersection<'a, T> {
     type Item = &'a T;
     type IntoIter = IntersectionIter<'a, T>;
@@ -160,6 +191,39 @@ impl<'a, T: Ord> Iterator for IntersectionIter<'a, T> {
     }
 }
 
+impl<'a, T: Ord> DoubleEndedIterator for IntersectionIter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        loop {
+            // an empty slice is the minimum and ends the intersection
+            let min = match self.slices.iter().map(|s| s.last()).min() {
+                Some(Some(min)) => min,
+                _ => return None,
+            };
+
+            let mut equal = true;
+            for slice in &mut self.slices {
+                *slice = exponential_offset_le(slice, min);
+                equal &= slice.last() == Some(min);
+            }
+
+            if equal {
+                for slice in &mut self.slices {
+                    *slice = &slice[..slice.len() - 1];
+                }
+                return Some(min);
+            }
+        }
+    }
+}
+
+impl<'a, T: Ord> SeekableIterator<T> for IntersectionIter<'a, T> {
+    fn advance_to(&mut self, target: &T) {
+        for slice in &mut self.slices {
+            *slice = exponential_offset_ge(slice, target);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     mod set_to_set {
@@ -207,7 +271,36 @@ mod tests {
             assert_eq!(&intersection_[..], &[3]);
         }
     
+        #[test]
+        fn count_three_slices() {
+            use crate::Cardinality;
+
+            let a = &[1, 2, 3, 5, 8, 13, 21];
+            let b = &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13];
+            let c = &[2, 5, 13, 34];
+
+            assert_eq!(Intersection { slices: vec![a, b, c] }.count(), 3);
+            assert_eq!(Intersection { slices: vec![c, b, &[]] }.count(), 0);
+        }
+
         quickcheck! {
+            fn qc_count(xss: Vec<Vec<i32>>) -> bool {
+                use crate::{Cardinality, Counter};
+
+                let mut xss = xss;
+
+                for xs in &mut xss {
+                    sort_dedup_vec(xs);
+                }
+
+                let slices: Vec<_> = xss.iter().map(|xs| xs.as_slice()).collect();
+
+                let mut counter = Counter::<i32>::new();
+                SetOperation::extend_collection(Intersection { slices: slices.clone() }, &mut counter).unwrap();
+
+                Intersection { slices }.count() == counter.get()
+            }
+
             fn qc_intersection(xss: Vec<Vec<i32>>) -> bool {
                 use std::collections::BTreeSet;
                 use std::iter::FromIterator;
@@ -332,6 +425,76 @@ mod tests {
     
                 x.as_slice() == y.as_slice()
             }
+
+            fn qc_rev_intersection(xss: Vec<Vec<i32>>) -> bool {
+                use std::collections::BTreeSet;
+                use std::iter::FromIterator;
+    
+                // FIXME temporary hack (can have mutable parameters!)
+                let mut xss = xss;
+    
+                // small values so that the slices share elements
+                for xs in &mut xss {
+                    xs.iter_mut().for_each(|x| *x %= 16);
+                    sort_dedup_vec(xs);
+                }
+    
+                let x: Vec<i32> = {
+                    let xss = xss.iter().map(|xs| xs.as_slice()).collect();
+                    Intersection { slices: xss }.into_iter().rev().cloned().collect()
+                };
+    
+                let mut xss = xss.into_iter();
+                let mut y = match xss.next() {
+                    Some(xs) => BTreeSet::from_iter(xs),
+                    None => BTreeSet::new(),
+                };
+    
+                for v in xss {
+                    let x = BTreeSet::from_iter(v.iter().cloned());
+                    y = y.intersection(&x).cloned().collect();
+                }
+                let y: Vec<_> = y.into_iter().collect();
+    
+                x.iter().eq(y.iter().rev())
+            }
+
+            fn qc_advance_to(xss: Vec<Vec<i32>>, target: i32) -> bool {
+                use std::collections::BTreeSet;
+                use std::iter::FromIterator;
+                use crate::SeekableIterator;
+    
+                // FIXME temporary hack (can have mutable parameters!)
+                let mut xss = xss;
+    
+                // small values so that the slices share elements
+                for xs in &mut xss {
+                    xs.iter_mut().for_each(|x| *x %= 16);
+                    sort_dedup_vec(xs);
+                }
+    
+                let target = target % 16;
+                let x: Vec<i32> = {
+                    let xss = xss.iter().map(|xs| xs.as_slice()).collect();
+                    let mut iter = Intersection { slices: xss }.into_iter();
+                    iter.advance_to(&target);
+                    iter.cloned().collect()
+                };
+    
+                let mut xss = xss.into_iter();
+                let mut y = match xss.next() {
+                    Some(xs) => BTreeSet::from_iter(xs),
+                    None => BTreeSet::new(),
+                };
+    
+                for v in xss {
+                    let x = BTreeSet::from_iter(v.iter().cloned());
+                    y = y.intersection(&x).cloned().collect();
+                }
+                let y: Vec<_> = y.into_iter().filter(|&y| y >= target).collect();
+    
+                x.as_slice() == y.as_slice()
+            }
         }
     }
 }
@@ -0,0 +1,154 @@
+use crate::codec::CompressedSet;
+use crate::cursor::Cursor;
+use crate::{SetOperation, SeekableIterator, Collection};
+
+/// Represent the _intersection_ set operation that will be applied to compressed sets.
+///
+/// The sets are galloped through starting with the smallest one,
+/// only the parts that may contain common elements are decoded.
+///
+/// # Examples
+/// ```
+/// # use sdset::Error;
+/// # fn try_main() -> Result<(), Error> {
+/// use sdset::codec::BlockSet;
+/// use sdset::multi::OpBuilderCompressed;
+/// use sdset::{SetOperation, Set, SetBuf};
+///
+/// let a = BlockSet::new(Set::new(&[1u32, 2, 4])?);
+/// let b = BlockSet::new(Set::new(&[2u32, 3, 4, 5, 7])?);
+/// let c = BlockSet::new(Set::new(&[2u32, 4, 6, 7])?);
+///
+/// let op = OpBuilderCompressed::from_vec(vec![&a, &b, &c]).intersection();
+///
+/// let res: SetBuf<u32> = op.into_set_buf();
+/// assert_eq!(&res[..], &[2, 4]);
+/// # Ok(()) }
+/// # try_main().unwrap();
+/// ```
+#[derive(Clone)]
+pub struct IntersectionCompressed<'a, S: 'a> {
+    sets: Vec<&'a S>,
+}
+
+impl<'a, S> IntersectionCompressed<'a, S> {
+    /// Construct one with compressed sets.
+    pub fn new(sets: Vec<&'a S>) -> Self {
+        Self { sets }
+    }
+}
+
+impl<'a, S: CompressedSet> IntersectionCompressed<'a, S> {
+    fn iter(&self) -> IntersectionCompressedIter<'a, S> {
+        let mut sets = self.sets.clone();
+        sets.sort_unstable_by_key(|set| set.len());
+        IntersectionCompressedIter {
+            iters: sets.into_iter().map(|set| Cursor::new(set.iter())).collect(),
+        }
+    }
+}
+
+impl<'a, S: CompressedSet> SetOperation<S::Item> for IntersectionCompressed<'a, S> {
+    fn extend_collection<C>(self, output: &mut C) -> Result<(), C::Error>
+    where C: Collection<S::Item>,
+    {
+        output.extend(self)
+    }
+}
+
+impl<'a, S: CompressedSet> IntoIterator for IntersectionCompressed<'a, S> {
+    type Item = S::Item;
+    type IntoIter = IntersectionCompressedIter<'a, S>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, S: CompressedSet> IntoIterator for &'a IntersectionCompressed<'a, S> {
+    type Item = S::Item;
+    type IntoIter = IntersectionCompressedIter<'a, S>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct IntersectionCompressedIter<'a, S: CompressedSet + 'a> {
+    iters: Vec<Cursor<S::Iter<'a>>>,
+}
+
+impl<'a, S: CompressedSet> Iterator for IntersectionCompressedIter<'a, S> {
+    type Item = S::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let count = self.iters.len();
+        let mut candidate = *self.iters.first_mut()?.peek()?;
+
+        // cycle through the sets, restarting the count each time
+        // one of them skips the candidate, until all of them agree
+        let mut agreeing = 0;
+        for index in (0..count).cycle() {
+            let iter = &mut self.iters[index];
+            iter.advance_to(&candidate);
+            let x = *iter.peek()?;
+
+            if x == candidate {
+                agreeing += 1;
+                if agreeing == count {
+                    break;
+                }
+            } else {
+                candidate = x;
+                agreeing = 1;
+            }
+        }
+
+        self.iters.iter_mut().for_each(|iter| { iter.bump(); });
+        Some(candidate)
+    }
+}
+
+impl<'a, S: CompressedSet> SeekableIterator<S::Item> for IntersectionCompressedIter<'a, S> {
+    fn advance_to(&mut self, target: &S::Item) {
+        self.iters.iter_mut().for_each(|iter| iter.advance_to(target));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec::BlockSet;
+    use crate::set::{sort_dedup_vec, Set};
+
+    quickcheck! {
+        fn qc_intersection(xss: Vec<Vec<u32>>) -> bool {
+            use std::collections::BTreeSet;
+            use std::iter::FromIterator;
+
+            // overlap the sets and make some of them span multiple blocks
+            let mut xss: Vec<Vec<u32>> = xss.into_iter().enumerate().map(|(i, xs)| {
+                xs.into_iter().flat_map(|x| (0..(i as u32 % 3) * 50 + 1).map(move |j| x % 16 * 100 + j)).collect()
+            }).collect();
+            for xs in &mut xss {
+                sort_dedup_vec(xs);
+            }
+
+            let x: Vec<u32> = {
+                let sets: Vec<_> = xss.iter().map(|xs| BlockSet::new(Set::new_unchecked(xs))).collect();
+                IntersectionCompressed::new(sets.iter().collect()).into_set_buf().into_vec()
+            };
+
+            let mut xss = xss.into_iter();
+            let mut y = match xss.next() {
+                Some(xs) => BTreeSet::from_iter(xs),
+                None => BTreeSet::new(),
+            };
+            for xs in xss {
+                let xs = BTreeSet::from_iter(xs);
+                y = BTreeSet::from_iter(y.intersection(&xs).cloned());
+            }
+            let y: Vec<_> = y.into_iter().collect();
+
+            x.as_slice() == y.as_slice()
+        }
+    }
+}
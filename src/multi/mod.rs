@@ -23,15 +23,33 @@ use crate::set::Set;
 
 mod union;
 mod intersection;
+mod intersection_by_key;
+mod intersection_compressed;
 mod difference;
 mod difference_by_key;
+mod difference_compressed;
 mod symmetric_difference;
+mod threshold;
+mod union_compressed;
+mod union_with_counts;
+mod union_with_mask;
+mod union_with_sources;
+mod any_by_key;
 
 pub use self::union::Union;
 pub use self::intersection::Intersection;
+pub use self::intersection_by_key::IntersectionByKey;
+pub use self::intersection_compressed::IntersectionCompressed;
 pub use self::difference::Difference;
 pub use self::difference_by_key::DifferenceByKey;
+pub use self::difference_compressed::DifferenceCompressed;
 pub use self::symmetric_difference::SymmetricDifference;
+pub use self::threshold::Threshold;
+pub use self::union_compressed::UnionCompressed;
+pub use self::union_with_counts::UnionWithCounts;
+pub use self::union_with_mask::UnionWithMask;
+pub use self::union_with_sources::UnionWithSources;
+pub use self::any_by_key::AnyByKey;
 
 /// Type used to acquire any number of slices
 /// and make a set operation on these slices.
@@ -40,6 +58,12 @@ pub struct OpBuilder<'a, T: 'a> {
     slices: Vec<&'a Set<T>>,
 }
 
+impl<'a, T> Default for OpBuilder<'a, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<'a, T> OpBuilder<'a, T> {
     /// Construct an empty one.
     pub fn new() -> Self {
@@ -66,6 +90,7 @@ impl<'a, T> OpBuilder<'a, T> {
 
     /// Add a new set that will be used for the future set operation
     /// and consume and return the type.
+    #[allow(clippy::should_implement_trait)]
     pub fn add(mut self, set: &'a Set<T>) -> Self {
         self.push(set);
         self
@@ -81,6 +106,29 @@ impl<'a, T> OpBuilder<'a, T> {
         Union::new(self.slices)
     }
 
+    /// Prepare the slices for the _union_ set operation,
+    /// associating each element with the number of slices it is part of.
+    pub fn union_with_counts(self) -> UnionWithCounts<'a, T> {
+        UnionWithCounts::new(self.slices)
+    }
+
+    /// Prepare the slices for the _union_ set operation,
+    /// associating each element with a bitmask of the slices it is part of.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is more than 64 slices,
+    /// use [`union_with_sources`](OpBuilder::union_with_sources) for more.
+    pub fn union_with_mask(self) -> UnionWithMask<'a, T> {
+        UnionWithMask::new(self.slices)
+    }
+
+    /// Prepare the slices for the _union_ set operation,
+    /// associating each element with the indexes of the slices it is part of.
+    pub fn union_with_sources(self) -> UnionWithSources<'a, T> {
+        UnionWithSources::new(self.slices)
+    }
+
     /// Prepare the slices for the _intersection_ set operation.
     pub fn intersection(self) -> Intersection<'a, T> {
         Intersection::new(self.slices)
@@ -95,6 +143,66 @@ impl<'a, T> OpBuilder<'a, T> {
     pub fn symmetric_difference(self) -> SymmetricDifference<'a, T> {
         SymmetricDifference::new(self.slices)
     }
+
+    /// Prepare the slices for the _threshold_ set operation,
+    /// keeping the elements that are in at least `n` slices.
+    pub fn at_least(self, n: usize) -> Threshold<'a, T> {
+        Threshold::new(self.slices, n)
+    }
+}
+
+/// Type used to acquire any number of compressed sets
+/// and make a set operation on these sets,
+/// see [`CompressedSet`](crate::codec::CompressedSet) for the types that can be used.
+#[derive(Clone)]
+pub struct OpBuilderCompressed<'a, S: 'a> {
+    sets: Vec<&'a S>,
+}
+
+impl<'a, S> Default for OpBuilderCompressed<'a, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, S> OpBuilderCompressed<'a, S> {
+    /// Construct an empty one.
+    pub fn new() -> Self {
+        Self { sets: Vec::new() }
+    }
+
+    /// Construct it with the content of the given vec.
+    pub fn from_vec(sets: Vec<&'a S>) -> Self {
+        Self { sets }
+    }
+
+    /// Add a new set that will be used for the future set operation
+    /// and consume and return the type.
+    #[allow(clippy::should_implement_trait)]
+    pub fn add(mut self, set: &'a S) -> Self {
+        self.push(set);
+        self
+    }
+
+    /// Push a new set that will be used for the future set operation.
+    pub fn push(&mut self, set: &'a S) {
+        self.sets.push(set);
+    }
+
+    /// Prepare the sets for the _union_ set operation.
+    pub fn union(self) -> UnionCompressed<'a, S> {
+        UnionCompressed::new(self.sets)
+    }
+
+    /// Prepare the sets for the _intersection_ set operation.
+    pub fn intersection(self) -> IntersectionCompressed<'a, S> {
+        IntersectionCompressed::new(self.sets)
+    }
+
+    /// Prepare the sets for the _difference_ set operation.
+    pub fn difference(self) -> DifferenceCompressed<'a, S> {
+        DifferenceCompressed::new(self.sets)
+    }
 }
 
 /// Type used to make a set operation on two slices of different types.
@@ -138,13 +246,34 @@ where F: Fn(&T) -> K,
 
     /// Add a new set that will be used for the future set operation
     /// and consume and return the type.
+    #[allow(clippy::should_implement_trait)]
     pub fn add(mut self, set: &'a Set<U>) -> Self {
         self.push(set);
         self
     }
 
-    /// Prepare the two slices for the _difference_ set operation.
+    /// Prepare the slices for the _difference_ set operation.
+    ///
+    /// Only the elements of the base slice are emitted,
+    /// the ones with a key that is not in any of the other slices.
     pub fn difference(self) -> DifferenceByKey<'a, T, U, F, G, K> {
         DifferenceByKey::new(self.base, self.others, self.f, self.g)
     }
+
+    /// Prepare the slices for the _intersection_ set operation.
+    ///
+    /// Only the elements of the base slice are emitted,
+    /// the ones with a key that is in all the other slices.
+    pub fn intersection(self) -> IntersectionByKey<'a, T, U, F, G, K> {
+        IntersectionByKey::new(self.base, self.others, self.f, self.g)
+    }
+
+    /// Prepare the slices for the _any_ set operation.
+    ///
+    /// Only the elements of the base slice are emitted,
+    /// the ones with a key that is in at least one of the other slices.
+    /// This is not a union, the elements of the other slices are never emitted.
+    pub fn any(self) -> AnyByKey<'a, T, U, F, G, K> {
+        AnyByKey::new(self.base, self.others, self.f, self.g)
+    }
 }
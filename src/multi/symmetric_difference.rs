@@ -1,6 +1,8 @@
 use crate::set::{Set, vec_sets_into_slices};
 use crate::two_minimums::{two_minimums, Minimums::*};
-use crate::{SetOperation, Collection};
+use crate::{SetOperation, SeekableIterator, Collection, Cardinality, Counter, exponential_offset_ge};
+use crate::duo;
+use super::union_with_counts::next_back_with_sources;
 
 /// Represent the _symmetric difference_ set operation that will be applied to the slices.
 ///
@@ -99,6 +101,21 @@ impl<'a, T: Ord> SetOperation<&'a T> for SymmetricDifference<'a, T> {
     }
 }
 
+impl<'a, T: Ord> Cardinality for SymmetricDifference<'a, T> {
+    fn count(self) -> usize {
+        match self.slices.as_slice() {
+            [] => 0,
+            [a] => a.len(),
+            [a, b] => duo::SymmetricDifference::new(Set::new_unchecked(a), Set::new_unchecked(b)).count(),
+            _ => {
+                let mut counter = Counter::<&'a T>::new();
+                self.extend_collection(&mut counter, Collection::extend, Collection::push).unwrap();
+                counter.get()
+            },
+        }
+    }
+}
+
 impl<'a, T: Ord> IntoIterator for SymmetricDifference<'a, T> {
     type Item = &'a T;
     type IntoIter = SymmetricDifferenceIter<'a, T>;
@@ -157,6 +174,27 @@ impl<'a, T: Ord> Iterator for SymmetricDifferenceIter<'a, T> {
     }
 }
 
+impl<'a, T: Ord> DoubleEndedIterator for SymmetricDifferenceIter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        loop {
+            let mut count = 0;
+            let max = next_back_with_sources(&mut self.slices, |_| count += 1)?;
+            // if count is odd
+            if count % 2 != 0 {
+                return Some(max);
+            }
+        }
+    }
+}
+
+impl<'a, T: Ord> SeekableIterator<T> for SymmetricDifferenceIter<'a, T> {
+    fn advance_to(&mut self, target: &T) {
+        for slice in &mut self.slices {
+            *slice = exponential_offset_ge(slice, target);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     mod set_to_set {
@@ -164,6 +202,23 @@ mod tests {
         use crate::set::{sort_dedup_vec, SetBuf};
     
         quickcheck! {
+            fn qc_count(xss: Vec<Vec<i32>>) -> bool {
+                use crate::{Cardinality, Counter};
+
+                let mut xss = xss;
+
+                for xs in &mut xss {
+                    sort_dedup_vec(xs);
+                }
+
+                let slices: Vec<_> = xss.iter().map(|xs| xs.as_slice()).collect();
+
+                let mut counter = Counter::<i32>::new();
+                SetOperation::extend_collection(SymmetricDifference { slices: slices.clone() }, &mut counter).unwrap();
+
+                SymmetricDifference { slices }.count() == counter.get()
+            }
+
             fn qc_symmetric_difference(xss: Vec<Vec<i32>>) -> bool {
                 use std::collections::BTreeSet;
                 use std::iter::FromIterator;
@@ -222,6 +277,66 @@ mod tests {
     
                 x.as_slice() == y.as_slice()
             }
+
+            fn qc_rev_symmetric_difference(xss: Vec<Vec<i32>>) -> bool {
+                use std::collections::BTreeSet;
+                use std::iter::FromIterator;
+    
+                // FIXME temporary hack (can have mutable parameters!)
+                let mut xss = xss;
+    
+                // small values so that the slices share elements
+                for xs in &mut xss {
+                    xs.iter_mut().for_each(|x| *x %= 16);
+                    sort_dedup_vec(xs);
+                }
+    
+                let x: Vec<i32> = {
+                    let xss = xss.iter().map(|xs| xs.as_slice()).collect();
+                    SymmetricDifference { slices: xss }.into_iter().rev().cloned().collect()
+                };
+    
+                let mut y = BTreeSet::new();
+                for v in xss {
+                    let x = BTreeSet::from_iter(v.iter().cloned());
+                    y = y.symmetric_difference(&x).cloned().collect();
+                }
+                let y: Vec<_> = y.into_iter().collect();
+    
+                x.iter().eq(y.iter().rev())
+            }
+
+            fn qc_advance_to(xss: Vec<Vec<i32>>, target: i32) -> bool {
+                use std::collections::BTreeSet;
+                use std::iter::FromIterator;
+                use crate::SeekableIterator;
+    
+                // FIXME temporary hack (can have mutable parameters!)
+                let mut xss = xss;
+    
+                // small values so that the slices share elements
+                for xs in &mut xss {
+                    xs.iter_mut().for_each(|x| *x %= 16);
+                    sort_dedup_vec(xs);
+                }
+    
+                let target = target % 16;
+                let x: Vec<i32> = {
+                    let xss = xss.iter().map(|xs| xs.as_slice()).collect();
+                    let mut iter = SymmetricDifference { slices: xss }.into_iter();
+                    iter.advance_to(&target);
+                    iter.cloned().collect()
+                };
+    
+                let mut y = BTreeSet::new();
+                for v in xss {
+                    let x = BTreeSet::from_iter(v.iter().cloned());
+                    y = y.symmetric_difference(&x).cloned().collect();
+                }
+                let y: Vec<_> = y.into_iter().filter(|&y| y >= target).collect();
+    
+                x.as_slice() == y.as_slice()
+            }
         }
     }
 }
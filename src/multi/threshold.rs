@@ -0,0 +1,391 @@
+use crate::set::{Set, vec_sets_into_slices, vec_slices_into_sets_unchecked};
+use crate::two_minimums::{two_minimums, Minimums::*};
+use crate::{SetOperation, SeekableIterator, Collection, Cardinality, Counter, exponential_offset_ge, exponential_offset_le};
+use super::{Union, Intersection};
+
+/// Represent the _threshold_ set operation that will be applied to the slices.
+///
+/// Note that the threshold is all the elements that are in at least `n` of the slices,
+/// a threshold of one is the union and a threshold of the number of slices is the intersection.
+///
+/// # Examples
+/// ```
+/// # use sdset::Error;
+/// # fn try_main() -> Result<(), Error> {
+/// use sdset::multi::OpBuilder;
+/// use sdset::{SetOperation, Set, SetBuf};
+///
+/// let a = Set::new(&[1, 2, 4])?;
+/// let b = Set::new(&[2, 3, 4, 5, 7])?;
+/// let c = Set::new(&[2, 4, 6, 7])?;
+///
+/// let op = OpBuilder::from_vec(vec![a, b, c]).at_least(2);
+///
+/// let res: SetBuf<i32> = op.into_set_buf();
+/// assert_eq!(&res[..], &[2, 4, 7]);
+/// # Ok(()) }
+/// # try_main().unwrap();
+/// ```
+#[derive(Clone)]
+pub struct Threshold<'a, T: 'a> {
+    slices: Vec<&'a [T]>,
+    n: usize,
+}
+
+impl<'a, T> Threshold<'a, T> {
+    /// Construct one with slices checked to be sorted and deduplicated.
+    ///
+    /// A threshold of zero is considered to be one.
+    pub fn new(slices: Vec<&'a Set<T>>, n: usize) -> Self {
+        Self {
+            slices: vec_sets_into_slices(slices),
+            n: n.max(1),
+        }
+    }
+}
+
+/// Returns the smallest element that is in at least `n` slices and remove it from them.
+///
+/// An element less than the `n`th smallest first element of the slices cannot be
+/// in `n` slices, so the other slices skip the elements less than it.
+#[inline]
+fn next_threshold<'a, T: Ord>(slices: &mut [&'a [T]], n: usize, firsts: &mut Vec<&'a T>) -> Option<&'a T> {
+    loop {
+        let pivot = if n <= 2 {
+            match two_minimums(slices) {
+                Two((_, f), _) if n == 1 => f,
+                Two(_, (_, s)) => s,
+                One((_, f)) if n == 1 => f,
+                _ => return None,
+            }
+        } else {
+            firsts.clear();
+            Extend::extend(firsts, slices.iter().filter_map(|s| s.first()));
+            if firsts.len() < n { return None }
+            *firsts.select_nth_unstable(n - 1).1
+        };
+
+        let mut count = 0;
+        for slice in slices.iter_mut() {
+            *slice = exponential_offset_ge(slice, pivot);
+            if slice.first() == Some(pivot) {
+                count += 1;
+            }
+        }
+
+        if count >= n {
+            for slice in slices.iter_mut() {
+                if slice.first() == Some(pivot) {
+                    *slice = &slice[1..];
+                }
+            }
+            return Some(pivot);
+        }
+    }
+}
+
+/// Returns the greatest element that is in at least `n` slices and remove it from them.
+///
+/// An element greater than the `n`th greatest last element of the slices cannot be
+/// in `n` slices, so the other slices skip the elements greater than it.
+#[inline]
+fn next_back_threshold<'a, T: Ord>(slices: &mut [&'a [T]], n: usize, lasts: &mut Vec<&'a T>) -> Option<&'a T> {
+    loop {
+        lasts.clear();
+        Extend::extend(lasts, slices.iter().filter_map(|s| s.last()));
+        if lasts.len() < n { return None }
+        let pivot = *lasts.select_nth_unstable_by(n - 1, |a, b| b.cmp(a)).1;
+
+        let mut count = 0;
+        for slice in slices.iter_mut() {
+            *slice = exponential_offset_le(slice, pivot);
+            if slice.last() == Some(pivot) {
+                count += 1;
+            }
+        }
+
+        if count >= n {
+            for slice in slices.iter_mut() {
+                if slice.last() == Some(pivot) {
+                    *slice = &slice[..slice.len() - 1];
+                }
+            }
+            return Some(pivot);
+        }
+    }
+}
+
+impl<'a, T: Ord> Threshold<'a, T> {
+    #[inline]
+    fn extend_collection<C, U, F, G>(mut self, output: &mut C, extend: F, push: G) -> Result<(), C::Error>
+    where C: Collection<U>,
+          F: Fn(&mut C, &'a [T]) -> Result<(), C::Error>,
+          G: Fn(&mut C, &'a T) -> Result<(), C::Error>,
+    {
+        if self.n == 1 {
+            let union = Union::new(vec_slices_into_sets_unchecked(self.slices));
+            return union.extend_collection(output, extend, push);
+        }
+
+        if self.n == self.slices.len() {
+            let intersection = Intersection::new(vec_slices_into_sets_unchecked(self.slices));
+            return intersection.extend_collection(output, push);
+        }
+
+        let mut firsts = Vec::with_capacity(self.slices.len());
+        while let Some(x) = next_threshold(&mut self.slices, self.n, &mut firsts) {
+            push(output, x)?;
+        }
+        Ok(())
+    }
+
+    fn iter(&self) -> ThresholdIter<'a, T>
+    {
+        ThresholdIter {
+            slices: self.slices.clone(),
+            n: self.n,
+            firsts: Vec::new(),
+        }
+    }
+}
+
+impl<'a, T: Ord + Clone> SetOperation<T> for Threshold<'a, T> {
+    fn extend_collection<C>(self, output: &mut C) -> Result<(), C::Error>
+    where C: Collection<T>,
+    {
+        self.extend_collection(output, Collection::extend_from_slice, |v, x| v.push(x.clone()))
+    }
+}
+
+impl<'a, T: Ord> SetOperation<&'a T> for Threshold<'a, T> {
+    fn extend_collection<C>(self, output: &mut C) -> Result<(), C::Error>
+    where C: Collection<&'a T>,
+    {
+        self.extend_collection(output, Collection::extend, Collection::push)
+    }
+}
+
+impl<'a, T: Ord> Cardinality for Threshold<'a, T> {
+    fn count(self) -> usize {
+        let mut counter = Counter::<&'a T>::new();
+        self.extend_collection(&mut counter, Collection::extend, Collection::push).unwrap();
+        counter.get()
+    }
+}
+
+impl<'a, T: Ord> IntoIterator for Threshold<'a, T> {
+    type Item = &'a T;
+    type IntoIter = ThresholdIter<'a, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        ThresholdIter {
+            slices: self.slices,
+            n: self.n,
+            firsts: Vec::new(),
+        }
+    }
+}
+
+impl<'a, T: Ord> IntoIterator for &'a Threshold<'a, T> {
+    type Item = &'a T;
+    type IntoIter = ThresholdIter<'a, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct ThresholdIter<'a, T> {
+    slices: Vec<&'a [T]>,
+    n: usize,
+    // the first or last elements of the slices, depending on the iteration side
+    firsts: Vec<&'a T>,
+}
+
+impl<'a, T: Ord> Iterator for ThresholdIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        next_threshold(&mut self.slices, self.n, &mut self.firsts)
+    }
+}
+
+impl<'a, T: Ord> DoubleEndedIterator for ThresholdIter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        next_back_threshold(&mut self.slices, self.n, &mut self.firsts)
+    }
+}
+
+impl<'a, T: Ord> SeekableIterator<T> for ThresholdIter<'a, T> {
+    fn advance_to(&mut self, target: &T) {
+        for slice in &mut self.slices {
+            *slice = exponential_offset_ge(slice, target);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    fn threshold_btree(xss: &[Vec<i32>], n: usize) -> Vec<i32> {
+        use std::collections::BTreeMap;
+
+        let mut counts = BTreeMap::new();
+        for x in xss.iter().flatten() {
+            *counts.entry(*x).or_insert(0) += 1;
+        }
+        counts.into_iter().filter(|&(_, c)| c >= n.max(1)).map(|(x, _)| x).collect()
+    }
+
+    mod set_to_set {
+        use super::super::*;
+        use super::threshold_btree;
+        use crate::set::{sort_dedup_vec, SetBuf};
+
+        #[test]
+        fn no_slice() {
+            let threshold: SetBuf<i32> = Threshold { slices: vec![], n: 2 }.into_set_buf();
+            assert_eq!(&threshold[..], &[]);
+        }
+
+        #[test]
+        fn more_than_slices() {
+            let a = &[1, 2, 3];
+            let b = &[2, 3, 4];
+
+            let threshold: SetBuf<i32> = Threshold { slices: vec![a, b], n: 3 }.into_set_buf();
+            assert_eq!(&threshold[..], &[]);
+        }
+
+        #[test]
+        fn four_slices() {
+            let a = &[1, 2, 3, 6];
+            let b = &[2, 3, 4, 6, 7];
+            let c = &[3, 4, 5, 6, 7];
+            let d = &[0, 1, 3, 7];
+
+            let threshold: SetBuf<i32> = Threshold { slices: vec![a, b, c, d], n: 3 }.into_set_buf();
+            assert_eq!(&threshold[..], &[3, 6, 7]);
+        }
+
+        quickcheck! {
+            fn qc_count(xss: Vec<Vec<i32>>, n: usize) -> bool {
+                use crate::{Cardinality, Counter};
+
+                let mut xss = xss;
+
+                // small values so that the slices share elements
+                for xs in &mut xss {
+                    xs.iter_mut().for_each(|x| *x %= 16);
+                    sort_dedup_vec(xs);
+                }
+
+                let n = n % (xss.len() + 2);
+                let slices: Vec<_> = xss.iter().map(|xs| xs.as_slice()).collect();
+
+                let mut counter = Counter::<i32>::new();
+                SetOperation::extend_collection(Threshold { slices: slices.clone(), n: n.max(1) }, &mut counter).unwrap();
+
+                Threshold { slices, n: n.max(1) }.count() == counter.get()
+            }
+
+            fn qc_threshold(xss: Vec<Vec<i32>>, n: usize) -> bool {
+                let mut xss = xss;
+
+                // small values so that the slices share elements
+                for xs in &mut xss {
+                    xs.iter_mut().for_each(|x| *x %= 16);
+                    sort_dedup_vec(xs);
+                }
+
+                let n = n % (xss.len() + 2);
+                let x: SetBuf<i32> = {
+                    let sets = xss.iter().map(|xs| Set::new_unchecked(xs.as_slice())).collect();
+                    Threshold::new(sets, n).into_set_buf()
+                };
+
+                x.as_slice() == threshold_btree(&xss, n).as_slice()
+            }
+        }
+    }
+
+    mod set_to_iter {
+        use super::super::*;
+        use super::threshold_btree;
+        use crate::set::sort_dedup_vec;
+
+        #[test]
+        fn four_slices() {
+            let a = &[1, 2, 3, 6];
+            let b = &[2, 3, 4, 6, 7];
+            let c = &[3, 4, 5, 6, 7];
+            let d = &[0, 1, 3, 7];
+
+            let threshold = Threshold { slices: vec![a, b, c, d], n: 3 };
+            let threshold_ref: Vec<i32> = threshold.iter().cloned().collect();
+            assert_eq!(&threshold_ref[..], &[3, 6, 7]);
+            let threshold_rev: Vec<i32> = threshold.iter().rev().cloned().collect();
+            assert_eq!(&threshold_rev[..], &[7, 6, 3]);
+            let threshold_own: Vec<i32> = threshold.into_iter().cloned().collect();
+            assert_eq!(&threshold_own[..], &[3, 6, 7]);
+        }
+
+        quickcheck! {
+            fn qc_threshold(xss: Vec<Vec<i32>>, n: usize) -> bool {
+                let mut xss = xss;
+
+                // small values so that the slices share elements
+                for xs in &mut xss {
+                    xs.iter_mut().for_each(|x| *x %= 16);
+                    sort_dedup_vec(xs);
+                }
+
+                let n = n % (xss.len() + 2);
+                let x: Vec<i32> = {
+                    let sets = xss.iter().map(|xs| Set::new_unchecked(xs.as_slice())).collect();
+                    Threshold::new(sets, n).into_iter().cloned().collect()
+                };
+
+                x.as_slice() == threshold_btree(&xss, n).as_slice()
+            }
+
+            fn qc_rev_threshold(xss: Vec<Vec<i32>>, n: usize) -> bool {
+                let mut xss = xss;
+
+                // small values so that the slices share elements
+                for xs in &mut xss {
+                    xs.iter_mut().for_each(|x| *x %= 16);
+                    sort_dedup_vec(xs);
+                }
+
+                let n = n % (xss.len() + 2);
+                let x: Vec<i32> = {
+                    let sets = xss.iter().map(|xs| Set::new_unchecked(xs.as_slice())).collect();
+                    Threshold::new(sets, n).into_iter().rev().cloned().collect()
+                };
+
+                x.iter().eq(threshold_btree(&xss, n).iter().rev())
+            }
+
+            fn qc_advance_to(xss: Vec<Vec<i32>>, n: usize, target: i32) -> bool {
+                use crate::SeekableIterator;
+
+                let mut xss = xss;
+
+                // small values so that the slices share elements
+                for xs in &mut xss {
+                    xs.iter_mut().for_each(|x| *x %= 16);
+                    sort_dedup_vec(xs);
+                }
+
+                let n = n % (xss.len() + 2);
+                let target = target % 16;
+                let x: Vec<i32> = {
+                    let sets = xss.iter().map(|xs| Set::new_unchecked(xs.as_slice())).collect();
+                    let mut iter = Threshold::new(sets, n).into_iter();
+                    iter.advance_to(&target);
+                    iter.cloned().collect()
+                };
+
+                x.iter().eq(threshold_btree(&xss, n).iter().filter(|&&x| x >= target))
+            }
+        }
+    }
+}
@@ -1,6 +1,13 @@
 use crate::set::{Set, vec_sets_into_slices};
 use crate::two_minimums::{two_minimums, Minimums::*};
-use crate::{SetOperation, Collection};
+use crate::heap_merge::HeapMerge;
+use super::union_with_counts::next_back_with_sources;
+use crate::{SetOperation, SeekableIterator, Collection, Cardinality, Counter, exponential_offset_ge};
+use crate::duo;
+
+/// From this number of slices, the minimums are found using a binary heap
+/// instead of looking at the first element of every slice.
+const HEAP_MERGE_THRESHOLD: usize = 24;
 
 /// Represent the _union_ set operation that will be applied to the slices.
 ///
@@ -38,7 +45,7 @@ impl<'a, T> Union<'a, T> {
 
 impl<'a, T: Ord> Union<'a, T> {
     #[inline]
-    fn extend_collection<C, U, F, G>(mut self, output: &mut C, extend: F, push: G) -> Result<(), C::Error>
+    pub(super) fn extend_collection<C, U, F, G>(self, output: &mut C, extend: F, push: G) -> Result<(), C::Error>
     where C: Collection<U>,
           F: Fn(&mut C, &'a [T]) -> Result<(), C::Error>,
           G: Fn(&mut C, &'a T) -> Result<(), C::Error>,
@@ -47,6 +54,18 @@ impl<'a, T: Ord> Union<'a, T> {
             output.reserve(slice.len())?;
         }
 
+        if self.slices.len() >= HEAP_MERGE_THRESHOLD {
+            self.extend_collection_heap(output, extend)
+        } else {
+            self.extend_collection_minimums(output, extend, push)
+        }
+    }
+
+    fn extend_collection_minimums<C, U, F, G>(mut self, output: &mut C, extend: F, push: G) -> Result<(), C::Error>
+    where C: Collection<U>,
+          F: Fn(&mut C, &'a [T]) -> Result<(), C::Error>,
+          G: Fn(&mut C, &'a T) -> Result<(), C::Error>,
+    {
         loop {
             match two_minimums(&self.slices) {
                 Two((i, f), (_, s)) => {
@@ -72,11 +91,20 @@ impl<'a, T: Ord> Union<'a, T> {
         Ok(())
     }
 
-    fn iter(&self) -> UnionIter<'a, T>
+    fn extend_collection_heap<C, U, F>(self, output: &mut C, extend: F) -> Result<(), C::Error>
+    where C: Collection<U>,
+          F: Fn(&mut C, &'a [T]) -> Result<(), C::Error>,
     {
-        UnionIter {
-            slices: self.slices.clone(),
+        let mut merge = HeapMerge::new(self.slices);
+        while let Some(run) = merge.next_run() {
+            extend(output, run)?;
         }
+        Ok(())
+    }
+
+    fn iter(&self) -> UnionIter<'a, T>
+    {
+        UnionIter::new(self.slices.clone())
     }
 }
 
@@ -96,13 +124,26 @@ impl<'a, T: Ord> SetOperation<&'a T> for Union<'a, T> {
     }
 }
 
+impl<'a, T: Ord> Cardinality for Union<'a, T> {
+    fn count(self) -> usize {
+        match self.slices.as_slice() {
+            [] => 0,
+            [a] => a.len(),
+            [a, b] => duo::Union::new(Set::new_unchecked(a), Set::new_unchecked(b)).count(),
+            _ => {
+                let mut counter = Counter::<&'a T>::new();
+                self.extend_collection(&mut counter, Collection::extend, Collection::push).unwrap();
+                counter.get()
+            },
+        }
+    }
+}
+
 impl<'a, T: Ord> IntoIterator for Union<'a, T> {
     type Item = &'a T;
     type IntoIter = UnionIter<'a, T>;
     fn into_iter(self) -> Self::IntoIter {
-        UnionIter {
-            slices: self.slices,
-        }
+        UnionIter::new(self.slices)
     }
 }
 
@@ -115,37 +156,77 @@ impl<'a, T: Ord> IntoIterator for &'a Union<'a, T> {
 }
 
 pub struct UnionIter<'a, T> {
-    slices: Vec<&'a [T]>,
+    inner: UnionIterInner<'a, T>,
+}
+
+enum UnionIterInner<'a, T> {
+    Minimums(Vec<&'a [T]>),
+    Heap(HeapMerge<'a, T>),
+}
+
+impl<'a, T: Ord> UnionIter<'a, T> {
+    fn new(slices: Vec<&'a [T]>) -> Self {
+        let inner = if slices.len() >= HEAP_MERGE_THRESHOLD {
+            UnionIterInner::Heap(HeapMerge::new(slices))
+        } else {
+            UnionIterInner::Minimums(slices)
+        };
+        UnionIter { inner }
+    }
 }
 
 impl<'a, T: Ord> Iterator for UnionIter<'a, T> {
     type Item = &'a T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        loop {
-            match two_minimums(&self.slices) {
-                Two((i, f), (_, s)) => {
-                    if f != s {
-                        let result = &self.slices[i][0];
-                        self.slices[i] = &self.slices[i][1..];
-                        return Some(result);
-                    } else {
-                        for slice in &mut self.slices {
-                            if slice.first() == Some(s) {
-                                *slice = &slice[1..];
-                            }
+        let slices = match &mut self.inner {
+            UnionIterInner::Minimums(slices) => slices,
+            UnionIterInner::Heap(merge) => return merge.next(),
+        };
+
+        match two_minimums(slices) {
+            Two((i, f), (_, s)) => {
+                if f != s {
+                    let result = &slices[i][0];
+                    slices[i] = &slices[i][1..];
+                    Some(result)
+                } else {
+                    for slice in slices.iter_mut() {
+                        if slice.first() == Some(s) {
+                            *slice = &slice[1..];
                         }
-                        return Some(s);
                     }
-                    
-                },
-                One((i, _)) => {
-                    let result = &self.slices[i][0];
-                    self.slices[i] = &self.slices[i][1..];
-                    return Some(result);
-                },
-                Nothing => { return None; },
-            }
+                    Some(s)
+                }
+            },
+            One((i, _)) => {
+                let result = &slices[i][0];
+                slices[i] = &slices[i][1..];
+                Some(result)
+            },
+            Nothing => None,
+        }
+    }
+}
+
+impl<'a, T: Ord> DoubleEndedIterator for UnionIter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        match &mut self.inner {
+            UnionIterInner::Minimums(slices) => next_back_with_sources(slices, |_| ()),
+            UnionIterInner::Heap(merge) => merge.next_back(),
+        }
+    }
+}
+
+impl<'a, T: Ord> SeekableIterator<T> for UnionIter<'a, T> {
+    fn advance_to(&mut self, target: &T) {
+        match &mut self.inner {
+            UnionIterInner::Minimums(slices) => {
+                for slice in slices {
+                    *slice = exponential_offset_ge(slice, target);
+                }
+            },
+            UnionIterInner::Heap(merge) => merge.advance_to(target),
         }
     }
 }
@@ -216,6 +297,23 @@ mod tests {
         }
 
         quickcheck! {
+            fn qc_count(xss: Vec<Vec<i32>>) -> bool {
+                use crate::{Cardinality, Counter};
+
+                let mut xss = xss;
+
+                for xs in &mut xss {
+                    sort_dedup_vec(xs);
+                }
+
+                let slices: Vec<_> = xss.iter().map(|xs| xs.as_slice()).collect();
+
+                let mut counter = Counter::<i32>::new();
+                SetOperation::extend_collection(Union { slices: slices.clone() }, &mut counter).unwrap();
+
+                Union { slices }.count() == counter.get()
+            }
+
             fn qc_union(xss: Vec<Vec<i32>>) -> bool {
                 use std::collections::BTreeSet;
                 use std::iter::FromIterator;
@@ -354,6 +452,66 @@ mod tests {
 
                 x.as_slice() == y.as_slice()
             }
+
+            fn qc_rev_union(xss: Vec<Vec<i32>>) -> bool {
+                use std::collections::BTreeSet;
+                use std::iter::FromIterator;
+
+                // FIXME temporary hack (can have mutable parameters!)
+                let mut xss = xss;
+
+                // small values so that the slices share elements
+                for xs in &mut xss {
+                    xs.iter_mut().for_each(|x| *x %= 16);
+                    sort_dedup_vec(xs);
+                }
+
+                let x: Vec<i32> = {
+                    let xss = xss.iter().map(|xs| xs.as_slice()).collect();
+                    Union { slices: xss }.into_iter().rev().cloned().collect()
+                };
+
+                let mut y = BTreeSet::new();
+                for v in xss {
+                    let x = BTreeSet::from_iter(v.iter().cloned());
+                    y = y.union(&x).cloned().collect();
+                }
+                let y: Vec<_> = y.into_iter().collect();
+
+                x.iter().eq(y.iter().rev())
+            }
+
+            fn qc_advance_to(xss: Vec<Vec<i32>>, target: i32) -> bool {
+                use std::collections::BTreeSet;
+                use std::iter::FromIterator;
+                use crate::SeekableIterator;
+
+                // FIXME temporary hack (can have mutable parameters!)
+                let mut xss = xss;
+
+                // small values so that the slices share elements
+                for xs in &mut xss {
+                    xs.iter_mut().for_each(|x| *x %= 16);
+                    sort_dedup_vec(xs);
+                }
+
+                let target = target % 16;
+                let x: Vec<i32> = {
+                    let xss = xss.iter().map(|xs| xs.as_slice()).collect();
+                    let mut iter = Union { slices: xss }.into_iter();
+                    iter.advance_to(&target);
+                    iter.cloned().collect()
+                };
+
+                let mut y = BTreeSet::new();
+                for v in xss {
+                    let x = BTreeSet::from_iter(v.iter().cloned());
+                    y = y.union(&x).cloned().collect();
+                }
+                let y: Vec<_> = y.into_iter().filter(|&y| y >= target).collect();
+
+                x.as_slice() == y.as_slice()
+            }
         }
     }
 }
@@ -433,4 +591,91 @@ mod bench {
             test::black_box(|| union_);
         });
     }
+
+    fn random_sets(count: usize, len: usize) -> Vec<Vec<i32>> {
+        let mut x: u64 = 42;
+        (0..count).map(|_| {
+            let mut v: Vec<i32> = (0..len).map(|_| {
+                x = x.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                ((x >> 33) % (count * len * 4) as u64) as i32
+            }).collect();
+            v.sort_unstable();
+            v.dedup();
+            v
+        }).collect()
+    }
+
+    fn minimums_bench(bench: &mut Bencher, count: usize) {
+        let sets = random_sets(count, 100);
+        let slices: Vec<_> = sets.iter().map(|s| s.as_slice()).collect();
+
+        bench.iter(|| {
+            let mut union_ = Vec::new();
+            let union = Union { slices: slices.clone() };
+            union.extend_collection_minimums(&mut union_, Collection::extend_from_slice, |v, x| Collection::push(v, *x)).unwrap();
+            test::black_box(|| union_);
+        });
+    }
+
+    fn heap_bench(bench: &mut Bencher, count: usize) {
+        let sets = random_sets(count, 100);
+        let slices: Vec<_> = sets.iter().map(|s| s.as_slice()).collect();
+
+        bench.iter(|| {
+            let mut union_ = Vec::new();
+            let union = Union { slices: slices.clone() };
+            union.extend_collection_heap(&mut union_, Collection::extend_from_slice).unwrap();
+            test::black_box(|| union_);
+        });
+    }
+
+    #[bench]
+    fn four_slices_minimums(bench: &mut Bencher) {
+        minimums_bench(bench, 4);
+    }
+
+    #[bench]
+    fn four_slices_heap(bench: &mut Bencher) {
+        heap_bench(bench, 4);
+    }
+
+    #[bench]
+    fn sixteen_slices_minimums(bench: &mut Bencher) {
+        minimums_bench(bench, 16);
+    }
+
+    #[bench]
+    fn sixteen_slices_heap(bench: &mut Bencher) {
+        heap_bench(bench, 16);
+    }
+
+    #[bench]
+    fn sixty_four_slices_minimums(bench: &mut Bencher) {
+        minimums_bench(bench, 64);
+    }
+
+    #[bench]
+    fn sixty_four_slices_heap(bench: &mut Bencher) {
+        heap_bench(bench, 64);
+    }
+
+    #[bench]
+    fn thirty_two_slices_minimums(bench: &mut Bencher) {
+        minimums_bench(bench, 32);
+    }
+
+    #[bench]
+    fn thirty_two_slices_heap(bench: &mut Bencher) {
+        heap_bench(bench, 32);
+    }
+
+    #[bench]
+    fn many_slices_minimums(bench: &mut Bencher) {
+        minimums_bench(bench, 256);
+    }
+
+    #[bench]
+    fn many_slices_heap(bench: &mut Bencher) {
+        heap_bench(bench, 256);
+    }
 }
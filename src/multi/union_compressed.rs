@@ -0,0 +1,124 @@
+use crate::codec::CompressedSet;
+use crate::cursor::Cursor;
+use crate::{SetOperation, SeekableIterator, Collection};
+
+/// Represent the _union_ set operation that will be applied to compressed sets.
+///
+/// # Examples
+/// ```
+/// # use sdset::Error;
+/// # fn try_main() -> Result<(), Error> {
+/// use sdset::codec::BlockSet;
+/// use sdset::multi::OpBuilderCompressed;
+/// use sdset::{SetOperation, Set, SetBuf};
+///
+/// let a = BlockSet::new(Set::new(&[1u32, 2, 4])?);
+/// let b = BlockSet::new(Set::new(&[2u32, 3, 5, 7])?);
+/// let c = BlockSet::new(Set::new(&[4u32, 6, 7])?);
+///
+/// let op = OpBuilderCompressed::from_vec(vec![&a, &b, &c]).union();
+///
+/// let res: SetBuf<u32> = op.into_set_buf();
+/// assert_eq!(&res[..], &[1, 2, 3, 4, 5, 6, 7]);
+/// # Ok(()) }
+/// # try_main().unwrap();
+/// ```
+#[derive(Clone)]
+pub struct UnionCompressed<'a, S: 'a> {
+    sets: Vec<&'a S>,
+}
+
+impl<'a, S> UnionCompressed<'a, S> {
+    /// Construct one with compressed sets.
+    pub fn new(sets: Vec<&'a S>) -> Self {
+        Self { sets }
+    }
+}
+
+impl<'a, S: CompressedSet> UnionCompressed<'a, S> {
+    fn iter(&self) -> UnionCompressedIter<'a, S> {
+        UnionCompressedIter {
+            iters: self.sets.iter().map(|set| Cursor::new(set.iter())).collect(),
+        }
+    }
+}
+
+impl<'a, S: CompressedSet> SetOperation<S::Item> for UnionCompressed<'a, S> {
+    fn extend_collection<C>(self, output: &mut C) -> Result<(), C::Error>
+    where C: Collection<S::Item>,
+    {
+        output.reserve(self.sets.iter().map(|set| set.len()).sum())?;
+        output.extend(self)
+    }
+}
+
+impl<'a, S: CompressedSet> IntoIterator for UnionCompressed<'a, S> {
+    type Item = S::Item;
+    type IntoIter = UnionCompressedIter<'a, S>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, S: CompressedSet> IntoIterator for &'a UnionCompressed<'a, S> {
+    type Item = S::Item;
+    type IntoIter = UnionCompressedIter<'a, S>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct UnionCompressedIter<'a, S: CompressedSet + 'a> {
+    iters: Vec<Cursor<S::Iter<'a>>>,
+}
+
+impl<'a, S: CompressedSet> Iterator for UnionCompressedIter<'a, S> {
+    type Item = S::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let minimum = self.iters.iter_mut().filter_map(|iter| iter.peek().cloned()).min()?;
+
+        for iter in &mut self.iters {
+            if iter.peek() == Some(&minimum) {
+                iter.bump();
+            }
+        }
+
+        Some(minimum)
+    }
+}
+
+impl<'a, S: CompressedSet> SeekableIterator<S::Item> for UnionCompressedIter<'a, S> {
+    fn advance_to(&mut self, target: &S::Item) {
+        self.iters.iter_mut().for_each(|iter| iter.advance_to(target));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec::BlockSet;
+    use crate::set::{sort_dedup_vec, Set};
+
+    quickcheck! {
+        fn qc_union(xss: Vec<Vec<u32>>) -> bool {
+            use std::collections::BTreeSet;
+            use std::iter::FromIterator;
+
+            let mut xss = xss;
+            for xs in &mut xss {
+                sort_dedup_vec(xs);
+            }
+
+            let x: Vec<u32> = {
+                let sets: Vec<_> = xss.iter().map(|xs| BlockSet::new(Set::new_unchecked(xs))).collect();
+                UnionCompressed::new(sets.iter().collect()).into_set_buf().into_vec()
+            };
+
+            let y = BTreeSet::from_iter(xss.into_iter().flatten());
+            let y: Vec<_> = y.into_iter().collect();
+
+            x.as_slice() == y.as_slice()
+        }
+    }
+}
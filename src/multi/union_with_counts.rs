@@ -0,0 +1,280 @@
+use crate::set::{Set, vec_sets_into_slices, vec_slices_into_sets_unchecked};
+use crate::two_minimums::{two_minimums, Minimums::*};
+use crate::{SetOperation, SeekableIterator, Collection, Cardinality, exponential_offset_ge};
+use super::Union;
+
+/// Represent the _union_ set operation that will be applied to the slices,
+/// each element is associated with the number of slices it is part of.
+///
+/// # Examples
+/// ```
+/// # use sdset::Error;
+/// # fn try_main() -> Result<(), Error> {
+/// use sdset::multi::OpBuilder;
+/// use sdset::{SetOperation, Set, SetBuf};
+///
+/// let a = Set::new(&[1, 2, 4])?;
+/// let b = Set::new(&[2, 3, 5, 7])?;
+/// let c = Set::new(&[4, 6, 7])?;
+///
+/// let op = OpBuilder::from_vec(vec![a, b, c]).union_with_counts();
+///
+/// let res: SetBuf<(i32, usize)> = op.into_set_buf();
+/// assert_eq!(&res[..], &[(1, 1), (2, 2), (3, 1), (4, 2), (5, 1), (6, 1), (7, 2)]);
+/// # Ok(()) }
+/// # try_main().unwrap();
+/// ```
+#[derive(Clone)]
+pub struct UnionWithCounts<'a, T: 'a> {
+    slices: Vec<&'a [T]>,
+}
+
+impl<'a, T> UnionWithCounts<'a, T> {
+    /// Construct one with slices checked to be sorted and deduplicated.
+    pub fn new(slices: Vec<&'a Set<T>>) -> Self {
+        Self {
+            slices: vec_sets_into_slices(slices),
+        }
+    }
+}
+
+/// Returns the minimum of the slices and remove it from them,
+/// `source` is called with the index of each slice it was part of.
+#[inline]
+pub(super) fn next_with_sources<'a, T, F>(slices: &mut [&'a [T]], mut source: F) -> Option<&'a T>
+where T: Ord,
+      F: FnMut(usize),
+{
+    let min = match two_minimums(slices) {
+        Two((i, f), (_, s)) if f != s => {
+            source(i);
+            slices[i] = &slices[i][1..];
+            return Some(f);
+        },
+        One((i, f)) => {
+            source(i);
+            slices[i] = &slices[i][1..];
+            return Some(f);
+        },
+        Two((_, f), _) => f,
+        Nothing => return None,
+    };
+
+    for (i, slice) in slices.iter_mut().enumerate() {
+        if slice.first() == Some(min) {
+            source(i);
+            *slice = &slice[1..];
+        }
+    }
+
+    Some(min)
+}
+
+/// Returns the maximum of the slices and remove it from them,
+/// `source` is called with the index of each slice it was part of.
+#[inline]
+pub(super) fn next_back_with_sources<'a, T, F>(slices: &mut [&'a [T]], mut source: F) -> Option<&'a T>
+where T: Ord,
+      F: FnMut(usize),
+{
+    let max = slices.iter().filter_map(|s| s.last()).max()?;
+
+    for (i, slice) in slices.iter_mut().enumerate() {
+        if slice.last() == Some(max) {
+            source(i);
+            *slice = &slice[..slice.len() - 1];
+        }
+    }
+
+    Some(max)
+}
+
+impl<'a, T: Ord> UnionWithCounts<'a, T> {
+    #[inline]
+    fn extend_collection<C, U, F>(mut self, output: &mut C, push: F) -> Result<(), C::Error>
+    where C: Collection<U>,
+          F: Fn(&mut C, (&'a T, usize)) -> Result<(), C::Error>,
+    {
+        if let Some(slice) = self.slices.first() {
+            output.reserve(slice.len())?;
+        }
+
+        loop {
+            let mut count = 0;
+            match next_with_sources(&mut self.slices, |_| count += 1) {
+                Some(x) => push(output, (x, count))?,
+                None => break,
+            }
+        }
+        Ok(())
+    }
+
+    fn iter(&self) -> UnionWithCountsIter<'a, T>
+    {
+        UnionWithCountsIter {
+            slices: self.slices.clone(),
+        }
+    }
+}
+
+impl<'a, T: Ord + Clone> SetOperation<(T, usize)> for UnionWithCounts<'a, T> {
+    fn extend_collection<C>(self, output: &mut C) -> Result<(), C::Error>
+    where C: Collection<(T, usize)>,
+    {
+        self.extend_collection(output, |v, (x, c)| v.push((x.clone(), c)))
+    }
+}
+
+impl<'a, T: Ord> SetOperation<(&'a T, usize)> for UnionWithCounts<'a, T> {
+    fn extend_collection<C>(self, output: &mut C) -> Result<(), C::Error>
+    where C: Collection<(&'a T, usize)>,
+    {
+        self.extend_collection(output, Collection::push)
+    }
+}
+
+impl<'a, T: Ord> Cardinality for UnionWithCounts<'a, T> {
+    fn count(self) -> usize {
+        Union::new(vec_slices_into_sets_unchecked(self.slices)).count()
+    }
+}
+
+impl<'a, T: Ord> IntoIterator for UnionWithCounts<'a, T> {
+    type Item = (&'a T, usize);
+    type IntoIter = UnionWithCountsIter<'a, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        UnionWithCountsIter {
+            slices: self.slices,
+        }
+    }
+}
+
+impl<'a, T: Ord> IntoIterator for &'a UnionWithCounts<'a, T> {
+    type Item = (&'a T, usize);
+    type IntoIter = UnionWithCountsIter<'a, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct UnionWithCountsIter<'a, T> {
+    slices: Vec<&'a [T]>,
+}
+
+impl<'a, T: Ord> Iterator for UnionWithCountsIter<'a, T> {
+    type Item = (&'a T, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut count = 0;
+        next_with_sources(&mut self.slices, |_| count += 1).map(|x| (x, count))
+    }
+}
+
+impl<'a, T: Ord> DoubleEndedIterator for UnionWithCountsIter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let mut count = 0;
+        next_back_with_sources(&mut self.slices, |_| count += 1).map(|x| (x, count))
+    }
+}
+
+impl<'a, T: Ord> SeekableIterator<T> for UnionWithCountsIter<'a, T> {
+    fn advance_to(&mut self, target: &T) {
+        for slice in &mut self.slices {
+            *slice = exponential_offset_ge(slice, target);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    fn union_with_counts_btree(xss: &[Vec<i32>]) -> Vec<(i32, usize)> {
+        use std::collections::BTreeMap;
+
+        let mut counts = BTreeMap::new();
+        for x in xss.iter().flatten() {
+            *counts.entry(*x).or_insert(0) += 1;
+        }
+        counts.into_iter().collect()
+    }
+
+    mod set_to_set {
+        use super::super::*;
+        use super::union_with_counts_btree;
+        use crate::set::{sort_dedup_vec, SetBuf};
+
+        #[test]
+        fn no_slice() {
+            let union_: SetBuf<(i32, usize)> = UnionWithCounts { slices: vec![] }.into_set_buf();
+            assert_eq!(&union_[..], &[]);
+        }
+
+        #[test]
+        fn three_slices() {
+            let a = &[1, 2, 3];
+            let b = &[2, 3, 4];
+            let c = &[3, 4, 5];
+
+            let union_: SetBuf<(i32, usize)> = UnionWithCounts { slices: vec![a, b, c] }.into_set_buf();
+            assert_eq!(&union_[..], &[(1, 1), (2, 2), (3, 3), (4, 2), (5, 1)]);
+        }
+
+        quickcheck! {
+            fn qc_union_with_counts(xss: Vec<Vec<i32>>) -> bool {
+                let mut xss = xss;
+
+                // small values so that the slices share elements
+                for xs in &mut xss {
+                    xs.iter_mut().for_each(|x| *x %= 16);
+                    sort_dedup_vec(xs);
+                }
+
+                let x: SetBuf<(i32, usize)> = {
+                    let xss = xss.iter().map(|xs| xs.as_slice()).collect();
+                    UnionWithCounts { slices: xss }.into_set_buf()
+                };
+
+                x.as_slice() == union_with_counts_btree(&xss).as_slice()
+            }
+        }
+    }
+
+    mod set_to_iter {
+        use super::super::*;
+        use super::union_with_counts_btree;
+        use crate::set::sort_dedup_vec;
+
+        #[test]
+        fn three_slices() {
+            let a = &[1, 2, 3];
+            let b = &[2, 3, 4];
+            let c = &[3, 4, 5];
+
+            let union = UnionWithCounts { slices: vec![a, b, c] };
+            let union_ref: Vec<_> = union.iter().map(|(x, c)| (*x, c)).collect();
+            assert_eq!(&union_ref[..], &[(1, 1), (2, 2), (3, 3), (4, 2), (5, 1)]);
+            let union_rev: Vec<_> = union.iter().rev().map(|(x, c)| (*x, c)).collect();
+            assert_eq!(&union_rev[..], &[(5, 1), (4, 2), (3, 3), (2, 2), (1, 1)]);
+            let union_own: Vec<_> = union.into_iter().map(|(x, c)| (*x, c)).collect();
+            assert_eq!(&union_own[..], &[(1, 1), (2, 2), (3, 3), (4, 2), (5, 1)]);
+        }
+
+        quickcheck! {
+            fn qc_union_with_counts(xss: Vec<Vec<i32>>) -> bool {
+                let mut xss = xss;
+
+                // small values so that the slices share elements
+                for xs in &mut xss {
+                    xs.iter_mut().for_each(|x| *x %= 16);
+                    sort_dedup_vec(xs);
+                }
+
+                let x: Vec<(i32, usize)> = {
+                    let xss = xss.iter().map(|xs| xs.as_slice()).collect();
+                    UnionWithCounts { slices: xss }.into_iter().map(|(x, c)| (*x, c)).collect()
+                };
+
+                x.as_slice() == union_with_counts_btree(&xss).as_slice()
+            }
+        }
+    }
+}
@@ -0,0 +1,253 @@
+use crate::set::{Set, vec_sets_into_slices, vec_slices_into_sets_unchecked};
+use crate::{SetOperation, SeekableIterator, Collection, Cardinality, exponential_offset_ge};
+use super::Union;
+use super::union_with_counts::{next_with_sources, next_back_with_sources};
+
+/// Represent the _union_ set operation that will be applied to the slices,
+/// each element is associated with a bitmask of the slices it is part of.
+///
+/// The bit at the index of a slice is set when the element is part of this slice,
+/// this is why the number of slices is limited to 64,
+/// the [`UnionWithSources`](super::UnionWithSources) reports the indexes of any number of slices.
+///
+/// # Examples
+/// ```
+/// # use sdset::Error;
+/// # fn try_main() -> Result<(), Error> {
+/// use sdset::multi::OpBuilder;
+/// use sdset::{SetOperation, Set, SetBuf};
+///
+/// let a = Set::new(&[1, 2, 4])?;
+/// let b = Set::new(&[2, 3, 5, 7])?;
+/// let c = Set::new(&[4, 6, 7])?;
+///
+/// let op = OpBuilder::from_vec(vec![a, b, c]).union_with_mask();
+///
+/// let res: SetBuf<(i32, u64)> = op.into_set_buf();
+/// assert_eq!(&res[..], &[
+///     (1, 0b001),
+///     (2, 0b011),
+///     (3, 0b010),
+///     (4, 0b101),
+///     (5, 0b010),
+///     (6, 0b100),
+///     (7, 0b110),
+/// ]);
+/// # Ok(()) }
+/// # try_main().unwrap();
+/// ```
+#[derive(Clone)]
+pub struct UnionWithMask<'a, T: 'a> {
+    slices: Vec<&'a [T]>,
+}
+
+impl<'a, T> UnionWithMask<'a, T> {
+    /// Construct one with slices checked to be sorted and deduplicated.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is more than 64 slices,
+    /// use a [`UnionWithSources`](super::UnionWithSources) for more.
+    pub fn new(slices: Vec<&'a Set<T>>) -> Self {
+        assert!(slices.len() <= 64, "a union with mask cannot be made on more than 64 slices");
+        Self {
+            slices: vec_sets_into_slices(slices),
+        }
+    }
+}
+
+impl<'a, T: Ord> UnionWithMask<'a, T> {
+    #[inline]
+    fn extend_collection<C, U, F>(mut self, output: &mut C, push: F) -> Result<(), C::Error>
+    where C: Collection<U>,
+          F: Fn(&mut C, (&'a T, u64)) -> Result<(), C::Error>,
+    {
+        if let Some(slice) = self.slices.first() {
+            output.reserve(slice.len())?;
+        }
+
+        loop {
+            let mut mask = 0;
+            match next_with_sources(&mut self.slices, |i| mask |= 1 << i) {
+                Some(x) => push(output, (x, mask))?,
+                None => break,
+            }
+        }
+        Ok(())
+    }
+
+    fn iter(&self) -> UnionWithMaskIter<'a, T>
+    {
+        UnionWithMaskIter {
+            slices: self.slices.clone(),
+        }
+    }
+}
+
+impl<'a, T: Ord + Clone> SetOperation<(T, u64)> for UnionWithMask<'a, T> {
+    fn extend_collection<C>(self, output: &mut C) -> Result<(), C::Error>
+    where C: Collection<(T, u64)>,
+    {
+        self.extend_collection(output, |v, (x, m)| v.push((x.clone(), m)))
+    }
+}
+
+impl<'a, T: Ord> SetOperation<(&'a T, u64)> for UnionWithMask<'a, T> {
+    fn extend_collection<C>(self, output: &mut C) -> Result<(), C::Error>
+    where C: Collection<(&'a T, u64)>,
+    {
+        self.extend_collection(output, Collection::push)
+    }
+}
+
+impl<'a, T: Ord> Cardinality for UnionWithMask<'a, T> {
+    fn count(self) -> usize {
+        Union::new(vec_slices_into_sets_unchecked(self.slices)).count()
+    }
+}
+
+impl<'a, T: Ord> IntoIterator for UnionWithMask<'a, T> {
+    type Item = (&'a T, u64);
+    type IntoIter = UnionWithMaskIter<'a, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        UnionWithMaskIter {
+            slices: self.slices,
+        }
+    }
+}
+
+impl<'a, T: Ord> IntoIterator for &'a UnionWithMask<'a, T> {
+    type Item = (&'a T, u64);
+    type IntoIter = UnionWithMaskIter<'a, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct UnionWithMaskIter<'a, T> {
+    slices: Vec<&'a [T]>,
+}
+
+impl<'a, T: Ord> Iterator for UnionWithMaskIter<'a, T> {
+    type Item = (&'a T, u64);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut mask = 0;
+        next_with_sources(&mut self.slices, |i| mask |= 1 << i).map(|x| (x, mask))
+    }
+}
+
+impl<'a, T: Ord> DoubleEndedIterator for UnionWithMaskIter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let mut mask = 0;
+        next_back_with_sources(&mut self.slices, |i| mask |= 1 << i).map(|x| (x, mask))
+    }
+}
+
+impl<'a, T: Ord> SeekableIterator<T> for UnionWithMaskIter<'a, T> {
+    fn advance_to(&mut self, target: &T) {
+        for slice in &mut self.slices {
+            *slice = exponential_offset_ge(slice, target);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    fn union_with_mask_btree(xss: &[Vec<i32>]) -> Vec<(i32, u64)> {
+        use std::collections::BTreeMap;
+
+        let mut masks = BTreeMap::new();
+        for (i, xs) in xss.iter().enumerate() {
+            for x in xs {
+                *masks.entry(*x).or_insert(0) |= 1 << i;
+            }
+        }
+        masks.into_iter().collect()
+    }
+
+    mod set_to_set {
+        use super::super::*;
+        use super::union_with_mask_btree;
+        use crate::set::{sort_dedup_vec, SetBuf};
+
+        #[test]
+        fn three_slices() {
+            let a = &[1, 2, 3];
+            let b = &[2, 3, 4];
+            let c = &[3, 4, 5];
+
+            let union_: SetBuf<(i32, u64)> = UnionWithMask { slices: vec![a, b, c] }.into_set_buf();
+            assert_eq!(&union_[..], &[(1, 0b001), (2, 0b011), (3, 0b111), (4, 0b110), (5, 0b100)]);
+        }
+
+        #[test]
+        #[should_panic]
+        fn too_many_slices() {
+            let a = Set::new_unchecked(&[1, 2, 3]);
+            UnionWithMask::new(vec![a; 65]);
+        }
+
+        quickcheck! {
+            fn qc_union_with_mask(xss: Vec<Vec<i32>>) -> bool {
+                let mut xss = xss;
+                xss.truncate(64);
+
+                // small values so that the slices share elements
+                for xs in &mut xss {
+                    xs.iter_mut().for_each(|x| *x %= 16);
+                    sort_dedup_vec(xs);
+                }
+
+                let x: SetBuf<(i32, u64)> = {
+                    let xss = xss.iter().map(|xs| xs.as_slice()).collect();
+                    UnionWithMask { slices: xss }.into_set_buf()
+                };
+
+                x.as_slice() == union_with_mask_btree(&xss).as_slice()
+            }
+        }
+    }
+
+    mod set_to_iter {
+        use super::super::*;
+        use super::union_with_mask_btree;
+        use crate::set::sort_dedup_vec;
+
+        #[test]
+        fn three_slices() {
+            let a = &[1, 2, 3];
+            let b = &[2, 3, 4];
+            let c = &[3, 4, 5];
+
+            let expected = &[(1, 0b001), (2, 0b011), (3, 0b111), (4, 0b110), (5, 0b100)];
+            let union = UnionWithMask { slices: vec![a, b, c] };
+            let union_ref: Vec<_> = union.iter().map(|(x, m)| (*x, m)).collect();
+            assert_eq!(&union_ref[..], expected);
+            let union_rev: Vec<_> = union.iter().rev().map(|(x, m)| (*x, m)).collect();
+            assert!(union_rev.iter().eq(expected.iter().rev()));
+            let union_own: Vec<_> = union.into_iter().map(|(x, m)| (*x, m)).collect();
+            assert_eq!(&union_own[..], expected);
+        }
+
+        quickcheck! {
+            fn qc_union_with_mask(xss: Vec<Vec<i32>>) -> bool {
+                let mut xss = xss;
+                xss.truncate(64);
+
+                // small values so that the slices share elements
+                for xs in &mut xss {
+                    xs.iter_mut().for_each(|x| *x %= 16);
+                    sort_dedup_vec(xs);
+                }
+
+                let x: Vec<(i32, u64)> = {
+                    let xss = xss.iter().map(|xs| xs.as_slice()).collect();
+                    UnionWithMask { slices: xss }.into_iter().map(|(x, m)| (*x, m)).collect()
+                };
+
+                x.as_slice() == union_with_mask_btree(&xss).as_slice()
+            }
+        }
+    }
+}
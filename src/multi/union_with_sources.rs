@@ -0,0 +1,191 @@
+use crate::set::{Set, vec_sets_into_slices, vec_slices_into_sets_unchecked};
+use crate::{SetOperation, Collection, Cardinality, exponential_offset_ge};
+use super::Union;
+use super::union_with_counts::{next_with_sources, next_back_with_sources};
+
+/// Represent the _union_ set operation that will be applied to the slices,
+/// each element is associated with the indexes of the slices it is part of.
+///
+/// Unlike the [`UnionWithMask`](super::UnionWithMask) there is no limit on the number of slices.
+/// The indexes are reported in a buffer reused between the elements,
+/// the [`pop`](UnionWithSources::pop) method avoids allocating one `Vec` by element.
+///
+/// # Examples
+/// ```
+/// # use sdset::Error;
+/// # fn try_main() -> Result<(), Error> {
+/// use sdset::multi::OpBuilder;
+/// use sdset::{SetOperation, Set, SetBuf};
+///
+/// let a = Set::new(&[1, 2, 4])?;
+/// let b = Set::new(&[2, 3, 5, 7])?;
+/// let c = Set::new(&[4, 6, 7])?;
+///
+/// let mut op = OpBuilder::from_vec(vec![a, b, c]).union_with_sources();
+///
+/// assert_eq!(op.pop(), Some((&1, &[0][..])));
+/// assert_eq!(op.pop(), Some((&2, &[0, 1][..])));
+/// assert_eq!(op.pop_back(), Some((&7, &[1, 2][..])));
+///
+/// let res: SetBuf<(i32, Vec<usize>)> = op.into_set_buf();
+/// assert_eq!(&res[..], &[
+///     (3, vec![1]),
+///     (4, vec![0, 2]),
+///     (5, vec![1]),
+///     (6, vec![2]),
+/// ]);
+/// # Ok(()) }
+/// # try_main().unwrap();
+/// ```
+#[derive(Clone)]
+pub struct UnionWithSources<'a, T: 'a> {
+    slices: Vec<&'a [T]>,
+    sources: Vec<usize>,
+}
+
+impl<'a, T> UnionWithSources<'a, T> {
+    /// Construct one with slices checked to be sorted and deduplicated.
+    pub fn new(slices: Vec<&'a Set<T>>) -> Self {
+        Self {
+            slices: vec_sets_into_slices(slices),
+            sources: Vec::new(),
+        }
+    }
+}
+
+impl<'a, T: Ord> UnionWithSources<'a, T> {
+    /// Pop the minimum of the slices and returns it
+    /// along with the indexes of the slices it is part of.
+    pub fn pop(&mut self) -> Option<(&'a T, &[usize])> {
+        let sources = &mut self.sources;
+        sources.clear();
+        let x = next_with_sources(&mut self.slices, |i| sources.push(i))?;
+        Some((x, sources))
+    }
+
+    /// Pop the maximum of the slices and returns it
+    /// along with the indexes of the slices it is part of.
+    pub fn pop_back(&mut self) -> Option<(&'a T, &[usize])> {
+        let sources = &mut self.sources;
+        sources.clear();
+        let x = next_back_with_sources(&mut self.slices, |i| sources.push(i))?;
+        Some((x, sources))
+    }
+
+    /// Skip the elements of the slices that are less than `target`.
+    pub fn advance_to(&mut self, target: &T) {
+        for slice in &mut self.slices {
+            *slice = exponential_offset_ge(slice, target);
+        }
+    }
+
+    #[inline]
+    fn extend_collection<C, U, F>(mut self, output: &mut C, push: F) -> Result<(), C::Error>
+    where C: Collection<U>,
+          F: Fn(&mut C, (&'a T, Vec<usize>)) -> Result<(), C::Error>,
+    {
+        if let Some(slice) = self.slices.first() {
+            output.reserve(slice.len())?;
+        }
+
+        while let Some((x, sources)) = self.pop() {
+            let sources = sources.to_vec();
+            push(output, (x, sources))?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a, T: Ord + Clone> SetOperation<(T, Vec<usize>)> for UnionWithSources<'a, T> {
+    fn extend_collection<C>(self, output: &mut C) -> Result<(), C::Error>
+    where C: Collection<(T, Vec<usize>)>,
+    {
+        self.extend_collection(output, |v, (x, s)| v.push((x.clone(), s)))
+    }
+}
+
+impl<'a, T: Ord> SetOperation<(&'a T, Vec<usize>)> for UnionWithSources<'a, T> {
+    fn extend_collection<C>(self, output: &mut C) -> Result<(), C::Error>
+    where C: Collection<(&'a T, Vec<usize>)>,
+    {
+        self.extend_collection(output, Collection::push)
+    }
+}
+
+impl<'a, T: Ord> Cardinality for UnionWithSources<'a, T> {
+    fn count(self) -> usize {
+        Union::new(vec_slices_into_sets_unchecked(self.slices)).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::set::{sort_dedup_vec, SetBuf};
+
+    fn union_with_sources_btree(xss: &[Vec<i32>]) -> Vec<(i32, Vec<usize>)> {
+        use std::collections::BTreeMap;
+
+        let mut sources = BTreeMap::new();
+        for (i, xs) in xss.iter().enumerate() {
+            for x in xs {
+                sources.entry(*x).or_insert_with(Vec::new).push(i);
+            }
+        }
+        sources.into_iter().collect()
+    }
+
+    #[test]
+    fn three_slices() {
+        let a = &[1, 2, 3];
+        let b = &[2, 3, 4];
+        let c = &[3, 4, 5];
+
+        let union_: SetBuf<(i32, Vec<usize>)> = UnionWithSources { slices: vec![a, b, c], sources: Vec::new() }.into_set_buf();
+        assert_eq!(&union_[..], &[(1, vec![0]), (2, vec![0, 1]), (3, vec![0, 1, 2]), (4, vec![1, 2]), (5, vec![2])]);
+    }
+
+    #[test]
+    fn more_than_64_slices() {
+        let a = Set::new_unchecked(&[1, 2, 3]);
+        let b = Set::new_unchecked(&[3]);
+
+        let mut sets = vec![a; 70];
+        sets.push(b);
+
+        let mut union = UnionWithSources::new(sets);
+        union.advance_to(&2);
+
+        let (x, sources) = union.pop().unwrap();
+        assert_eq!((x, sources.len()), (&2, 70));
+
+        let (x, sources) = union.pop_back().unwrap();
+        assert_eq!((x, sources.len(), sources.last()), (&3, 71, Some(&70)));
+
+        assert_eq!(union.pop(), None);
+    }
+
+    quickcheck! {
+        fn qc_union_with_sources(xss: Vec<Vec<i32>>) -> bool {
+            let mut xss = xss;
+
+            // small values so that the slices share elements
+            for xs in &mut xss {
+                xs.iter_mut().for_each(|x| *x %= 16);
+                sort_dedup_vec(xs);
+            }
+
+            let slices: Vec<_> = xss.iter().map(|xs| xs.as_slice()).collect();
+            let x: SetBuf<(i32, Vec<usize>)> = UnionWithSources { slices: slices.clone(), sources: Vec::new() }.into_set_buf();
+
+            let mut union = UnionWithSources { slices, sources: Vec::new() };
+            let mut y = Vec::new();
+            while let Some((x, sources)) = union.pop() {
+                y.push((*x, sources.to_vec()));
+            }
+
+            let expected = union_with_sources_btree(&xss);
+            x.as_slice() == expected.as_slice() && y == expected
+        }
+    }
+}
@@ -1,21 +1,25 @@
 //! All the methods and types associated to [`Set`]s.
 
-use std::cmp::Ordering;
+use std::cmp::{self, Ordering};
 use std::borrow::Borrow;
 use std::ops::{Deref, RangeBounds, Bound};
+use std::ops::{BitOr, BitAnd, Sub, BitXor};
+use std::ops::{BitOrAssign, BitAndAssign, SubAssign, BitXorAssign};
 use std::{error, fmt, mem};
 
 #[cfg(feature="serde")]
-use serde::{Serialize, Deserialize};
+use serde::{Serialize, Serializer, Deserialize};
 
-use crate::{exponential_search, exponential_search_by, exponential_search_by_key};
+use crate::codec::Integer;
+use crate::duo::{Difference, OpBuilder};
+use crate::{exponential_search, exponential_search_by, exponential_search_by_key, exponential_offset_ge};
+use crate::{Counter, SetOperation};
 
 /// Represent a slice which contains types that are sorted and deduplicated (akin to [`str`]).
 ///
 /// This is an *unsized* type, meaning that it must always be used behind a
 /// pointer like `&` or [`Box`]. For an owned version of this type,
 /// see [`SetBuf`].
-#[cfg_attr(feature="serde", derive(Serialize))]
 #[repr(transparent)]
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Set<T>([T]);
@@ -89,6 +93,18 @@ impl<T> Set<T> {
     /// ```
     #[inline]
     pub fn range<K, R>(&self, range: R) -> &Self
+    where K: Ord + ?Sized,
+          R: RangeBounds<K>,
+          T: Borrow<K>,
+    {
+        let (left, right) = self.range_indexes(range);
+        Self::new_unchecked(&self[left..right])
+    }
+
+    /// Returns the indexes of the first element in the range
+    /// and the one following the last element in the range.
+    #[inline]
+    fn range_indexes<K, R>(&self, range: R) -> (usize, usize)
     where K: Ord + ?Sized,
           R: RangeBounds<K>,
           T: Borrow<K>,
@@ -117,7 +133,7 @@ impl<T> Set<T> {
             Bound::Unbounded => self.len(),
         };
 
-        Self::new_unchecked(&self[left..right])
+        (left, right)
     }
 
     /// Exponential searches this sorted slice for a given element.
@@ -192,6 +208,155 @@ impl<T> Set<T> {
         self.exponential_search(x).is_ok()
     }
 
+    /// Returns `true` if the set is a subset of another,
+    /// i.e., `other` contains at least all the values in `self`.
+    ///
+    /// This function uses exponential searching internally
+    /// to skip the values of `other` that are not in `self`.
+    ///
+    /// ```
+    /// use sdset::{Set, Error};
+    /// # fn try_main() -> Result<(), Error> {
+    ///
+    /// let set = Set::new(&[2, 4])?;
+    ///
+    /// assert!(set.is_subset(Set::new(&[1, 2, 3, 4])?));
+    /// assert!(!set.is_subset(Set::new(&[1, 2, 3])?));
+    /// # Ok(()) }
+    /// # try_main().unwrap();
+    /// ```
+    pub fn is_subset(&self, other: &Set<T>) -> bool
+    where T: Ord,
+    {
+        if self.len() > other.len() {
+            return false
+        }
+
+        let mut other = other.as_slice();
+        for x in self {
+            other = exponential_offset_ge(other, x);
+            match other.split_first() {
+                Some((first, rest)) if first == x => other = rest,
+                _ => return false,
+            }
+        }
+
+        true
+    }
+
+    /// Returns `true` if the set is a superset of another,
+    /// i.e., `self` contains at least all the values in `other`.
+    ///
+    /// ```
+    /// use sdset::{Set, Error};
+    /// # fn try_main() -> Result<(), Error> {
+    ///
+    /// let set = Set::new(&[1, 2, 3, 4])?;
+    ///
+    /// assert!(set.is_superset(Set::new(&[2, 4])?));
+    /// assert!(!set.is_superset(Set::new(&[2, 5])?));
+    /// # Ok(()) }
+    /// # try_main().unwrap();
+    /// ```
+    pub fn is_superset(&self, other: &Set<T>) -> bool
+    where T: Ord,
+    {
+        other.is_subset(self)
+    }
+
+    /// Returns `true` if `self` has no elements in common with `other`.
+    ///
+    /// This function uses exponential searching internally and stops
+    /// at the first element found in both sets.
+    ///
+    /// ```
+    /// use sdset::{Set, Error};
+    /// # fn try_main() -> Result<(), Error> {
+    ///
+    /// let set = Set::new(&[1, 3, 5])?;
+    ///
+    /// assert!(set.is_disjoint(Set::new(&[2, 4, 6])?));
+    /// assert!(!set.is_disjoint(Set::new(&[4, 5, 6])?));
+    /// # Ok(()) }
+    /// # try_main().unwrap();
+    /// ```
+    pub fn is_disjoint(&self, other: &Set<T>) -> bool
+    where T: Ord,
+    {
+        let (mut a, mut b) = (self.as_slice(), other.as_slice());
+
+        while let (Some(first_a), Some(first_b)) = (a.first(), b.first()) {
+            match first_a.cmp(first_b) {
+                Ordering::Less => a = exponential_offset_ge(a, first_b),
+                Ordering::Equal => return false,
+                Ordering::Greater => b = exponential_offset_ge(b, first_a),
+            }
+        }
+
+        true
+    }
+
+    /// Returns the [`SetRelation`] of `self` relative to `other`.
+    ///
+    /// The two sets are only walked once, using exponential searching
+    /// to skip the values that are not in both sets, and the walk
+    /// stops as soon as the sets are known to be overlapping.
+    ///
+    /// ```
+    /// use sdset::{Set, SetRelation, Error};
+    /// # fn try_main() -> Result<(), Error> {
+    ///
+    /// let set = Set::new(&[1, 2, 3])?;
+    ///
+    /// assert_eq!(set.relation(Set::new(&[1, 2, 3])?), SetRelation::Equal);
+    /// assert_eq!(set.relation(Set::new(&[1, 2, 3, 4])?), SetRelation::Subset);
+    /// assert_eq!(set.relation(Set::new(&[1, 3])?), SetRelation::Superset);
+    /// assert_eq!(set.relation(Set::new(&[4, 5])?), SetRelation::Disjoint);
+    /// assert_eq!(set.relation(Set::new(&[3, 4])?), SetRelation::Overlapping);
+    /// # Ok(()) }
+    /// # try_main().unwrap();
+    /// ```
+    pub fn relation(&self, other: &Set<T>) -> SetRelation
+    where T: Ord,
+    {
+        let (mut a, mut b) = (self.as_slice(), other.as_slice());
+        let (mut only_a, mut only_b, mut common) = (false, false, false);
+
+        while let (Some(first_a), Some(first_b)) = (a.first(), b.first()) {
+            match first_a.cmp(first_b) {
+                Ordering::Less => {
+                    only_a = true;
+                    a = exponential_offset_ge(a, first_b);
+                },
+                Ordering::Equal => {
+                    common = true;
+                    let off = a.iter().zip(b.iter()).take_while(|(a, b)| a == b).count();
+                    a = &a[off..];
+                    b = &b[off..];
+                },
+                Ordering::Greater => {
+                    only_b = true;
+                    b = exponential_offset_ge(b, first_a);
+                },
+            }
+
+            if only_a && only_b && common {
+                return SetRelation::Overlapping
+            }
+        }
+
+        only_a |= !a.is_empty();
+        only_b |= !b.is_empty();
+
+        match (only_a, only_b) {
+            (false, false) => SetRelation::Equal,
+            (false, true) => SetRelation::Subset,
+            (true, false) => SetRelation::Superset,
+            (true, true) if common => SetRelation::Overlapping,
+            (true, true) => SetRelation::Disjoint,
+        }
+    }
+
     /// Construct the owning version of the [`Set`].
     ///
     /// ```
@@ -242,9 +407,106 @@ impl<T> Set<T> {
     /// assert_eq!(iterator.next(), None);
     /// ```
     #[inline]
-    pub fn iter(&self) -> std::slice::Iter<T> {
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
         self.0.iter()
     }
+
+    /// Returns an iterator over this set that checks, while iterating,
+    /// that the elements are sorted and deduplicated.
+    ///
+    /// It is useful for a [`Set`] constructed without any check, only the elements
+    /// that are reached are read and the iterator stops after an error.
+    ///
+    /// ```
+    /// use sdset::{Set, Error};
+    ///
+    /// let x = Set::new_unchecked(&[1, 2, 4, 3]);
+    /// let mut iterator = x.checked_iter();
+    ///
+    /// assert_eq!(iterator.next(), Some(Ok(&1)));
+    /// assert_eq!(iterator.next(), Some(Ok(&2)));
+    /// assert_eq!(iterator.next(), Some(Ok(&4)));
+    /// assert_eq!(iterator.next(), Some(Err(Error::NotSort)));
+    /// assert_eq!(iterator.next(), None);
+    /// ```
+    pub fn checked_iter(&self) -> CheckedIter<'_, T> {
+        CheckedIter { iter: self.0.iter(), previous: None }
+    }
+}
+
+impl<T: Integer> Set<T> {
+    /// Construct a [`Set`] from little-endian fixed-width integers without copying them,
+    /// only if they are sorted and deduplicated.
+    ///
+    /// The bytes can come from a memory-mapped file and the [`Set`] can then be given
+    /// to the [`duo`](crate::duo) and [`multi`](crate::multi) set operations directly.
+    ///
+    /// The bytes must be aligned for the integer type and their length a multiple of its size,
+    /// big-endian targets are not supported as the integers would need to be swapped.
+    ///
+    /// ```
+    /// use sdset::set::BytesError;
+    /// use sdset::{Set, Error};
+    /// # fn try_main() -> Result<(), BytesError> {
+    ///
+    /// let set = Set::new(&[1u64, 2, 4, 6, 7])?;
+    /// let bytes = set.as_bytes();
+    /// assert_eq!(bytes.len(), 40);
+    ///
+    /// let set = Set::<u64>::from_bytes(bytes)?;
+    /// assert_eq!(set.as_slice(), &[1, 2, 4, 6, 7]);
+    ///
+    /// // these bytes do not contain a whole number of integers
+    /// let set = Set::<u64>::from_bytes(&bytes[..39]);
+    /// assert_eq!(set, Err(BytesError::InvalidLength));
+    ///
+    /// // these integers are not sorted
+    /// let unsorted = Set::new_unchecked(&[1u64, 4, 2]).as_bytes();
+    /// let set = Set::<u64>::from_bytes(unsorted);
+    /// assert_eq!(set, Err(BytesError::Set(Error::NotSort)));
+    /// # Ok(()) }
+    /// # try_main().unwrap();
+    /// ```
+    pub fn from_bytes(bytes: &[u8]) -> Result<&Self, BytesError> {
+        let set = Self::from_bytes_unchecked(bytes)?;
+        is_sort_dedup(set.as_slice())?;
+        Ok(set)
+    }
+
+    /// Construct a [`Set`] from little-endian fixed-width integers without copying them
+    /// and without checking that they are sorted and deduplicated.
+    ///
+    /// Only the alignment, the length and the endianness are checked, the bytes are not read.
+    /// A memory-mapped file is therefore not entirely loaded, the elements can later be checked
+    /// with [`is_sort_dedup`] on the part of the set that is used, or while they are read
+    /// with [`Set::checked_iter`], if needed.
+    pub fn from_bytes_unchecked(bytes: &[u8]) -> Result<&Self, BytesError> {
+        let size = mem::size_of::<T>();
+
+        if cfg!(target_endian = "big") && size > 1 {
+            return Err(BytesError::BigEndian);
+        }
+        if bytes.is_empty() {
+            return Ok(Self::new_unchecked(&[]));
+        }
+        if bytes.len() % size != 0 {
+            return Err(BytesError::InvalidLength);
+        }
+        if bytes.as_ptr() as usize % mem::align_of::<T>() != 0 {
+            return Err(BytesError::Misaligned);
+        }
+
+        // the integers can be represented by any bit pattern
+        let slice = unsafe { std::slice::from_raw_parts(bytes.as_ptr() as *const T, bytes.len() / size) };
+        Ok(Self::new_unchecked(slice))
+    }
+
+    /// Returns the bytes of the integers of the set in the native endianness,
+    /// which can be read back with [`Set::from_bytes`] on little-endian targets.
+    pub fn as_bytes(&self) -> &[u8] {
+        let len = mem::size_of_val(&self.0);
+        unsafe { std::slice::from_raw_parts(self.0.as_ptr() as *const u8, len) }
+    }
 }
 
 impl<T: Clone> ToOwned for Set<T> {
@@ -291,7 +553,6 @@ impl<'a, T> IntoIterator for &'a Set<T> {
 }
 
 /// An owned, set (akin to [`String`]).
-#[cfg_attr(feature="serde", derive(Serialize))]
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct SetBuf<T>(Vec<T>);
 
@@ -412,9 +673,198 @@ impl<T> SetBuf<T> {
     /// assert_eq!(iterator.next(), None);
     /// ```
     #[inline]
-    pub fn iter(&self) -> std::slice::Iter<T> {
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
         self.0.iter()
     }
+
+    /// Adds a value to the set, keeping it sorted and deduplicated.
+    ///
+    /// If the set did not have this value present, `true` is returned.
+    ///
+    /// This function uses exponential searching internally
+    /// to find the place where the value must be inserted.
+    ///
+    /// ```
+    /// use sdset::SetBuf;
+    ///
+    /// let mut set = SetBuf::new_unchecked(vec![1, 2, 4]);
+    ///
+    /// assert_eq!(set.insert(3), true);
+    /// assert_eq!(set.insert(3), false);
+    /// assert_eq!(set.as_slice(), &[1, 2, 3, 4]);
+    /// ```
+    pub fn insert(&mut self, value: T) -> bool
+    where T: Ord,
+    {
+        match self.exponential_search(&value) {
+            Ok(_) => false,
+            Err(index) => {
+                self.0.insert(index, value);
+                true
+            },
+        }
+    }
+
+    /// Removes a value from the set. Returns whether the value was present in the set.
+    ///
+    /// This function uses exponential searching internally
+    /// because it is verified that the elements are ordered.
+    ///
+    /// ```
+    /// use sdset::SetBuf;
+    ///
+    /// let mut set = SetBuf::new_unchecked(vec![1, 2, 4]);
+    ///
+    /// assert_eq!(set.remove(&2), true);
+    /// assert_eq!(set.remove(&2), false);
+    /// assert_eq!(set.as_slice(), &[1, 4]);
+    /// ```
+    pub fn remove(&mut self, value: &T) -> bool
+    where T: Ord,
+    {
+        match self.exponential_search(value) {
+            Ok(index) => {
+                self.0.remove(index);
+                true
+            },
+            Err(_) => false,
+        }
+    }
+
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// ```
+    /// use sdset::SetBuf;
+    ///
+    /// let mut set = SetBuf::new_unchecked(vec![1, 2, 3, 4, 5, 6]);
+    /// set.retain(|x| x % 2 == 0);
+    ///
+    /// assert_eq!(set.as_slice(), &[2, 4, 6]);
+    /// ```
+    pub fn retain<F>(&mut self, f: F)
+    where F: FnMut(&T) -> bool,
+    {
+        self.0.retain(f)
+    }
+
+    /// Removes the first element from the set and returns it,
+    /// or [`None`] if it is empty.
+    ///
+    /// ```
+    /// use sdset::SetBuf;
+    ///
+    /// let mut set = SetBuf::new_unchecked(vec![1, 2]);
+    ///
+    /// assert_eq!(set.pop_first(), Some(1));
+    /// assert_eq!(set.pop_first(), Some(2));
+    /// assert_eq!(set.pop_first(), None);
+    /// ```
+    pub fn pop_first(&mut self) -> Option<T> {
+        if self.0.is_empty() {
+            None
+        } else {
+            Some(self.0.remove(0))
+        }
+    }
+
+    /// Removes the last element from the set and returns it,
+    /// or [`None`] if it is empty.
+    ///
+    /// ```
+    /// use sdset::SetBuf;
+    ///
+    /// let mut set = SetBuf::new_unchecked(vec![1, 2]);
+    ///
+    /// assert_eq!(set.pop_last(), Some(2));
+    /// assert_eq!(set.pop_last(), Some(1));
+    /// assert_eq!(set.pop_last(), None);
+    /// ```
+    pub fn pop_last(&mut self) -> Option<T> {
+        self.0.pop()
+    }
+
+    /// Moves all the elements of `other` that are not already
+    /// in this set into it, keeping it sorted and deduplicated.
+    ///
+    /// The merge is done in place, starting from the end of the set,
+    /// no other allocation than the growth of the underlying [`Vec`] is needed.
+    ///
+    /// ```
+    /// use sdset::{Set, SetBuf, Error};
+    /// # fn try_main() -> Result<(), Error> {
+    ///
+    /// let mut set = SetBuf::new(vec![1, 3, 5])?;
+    /// let other = Set::new(&[2, 3, 4, 6])?;
+    ///
+    /// set.extend_sorted(other);
+    ///
+    /// assert_eq!(set.as_slice(), &[1, 2, 3, 4, 5, 6]);
+    /// # Ok(()) }
+    /// # try_main().unwrap();
+    /// ```
+    pub fn extend_sorted(&mut self, other: &Set<T>)
+    where T: Ord + Clone,
+    {
+        let (first, last) = match (other.first(), self.0.last()) {
+            (None, _) => return,
+            (Some(_), None) => {
+                self.0.extend_from_slice(other);
+                return
+            },
+            (Some(first), Some(last)) if last < first => {
+                self.0.extend_from_slice(other);
+                return
+            },
+            (Some(first), Some(last)) => (first, last),
+        };
+
+        // only the part of other overlapping this set can contain duplicates
+        let overlap = other.range(..=last);
+        let mut counter = Counter::<&T>::new();
+        Difference::new(overlap, self).extend_collection(&mut counter).unwrap();
+
+        let old_len = self.0.len();
+        let missing = counter.get() + (other.len() - overlap.len());
+        self.0.resize(old_len + missing, first.clone());
+
+        // merge backward, the vacant slots are always before the write index
+        let (mut i, mut j, mut k) = (old_len, other.len(), old_len + missing);
+        while j > 0 {
+            if i > 0 && self.0[i - 1] >= other[j - 1] {
+                if self.0[i - 1] == other[j - 1] { j -= 1 }
+                self.0.swap(i - 1, k - 1);
+                i -= 1;
+            } else {
+                self.0[k - 1] = other[j - 1].clone();
+                j -= 1;
+            }
+            k -= 1;
+        }
+    }
+
+    /// Shortens the set, keeping only the elements that are in the given range.
+    ///
+    /// This function uses exponential searching internally
+    /// because it is verified that the elements are ordered.
+    ///
+    /// ```
+    /// use sdset::SetBuf;
+    ///
+    /// let mut set = SetBuf::new_unchecked(vec![1, 2, 4, 6, 7]);
+    /// set.truncate_range(2..7);
+    ///
+    /// assert_eq!(set.as_slice(), &[2, 4, 6]);
+    /// ```
+    pub fn truncate_range<K, R>(&mut self, range: R)
+    where K: Ord + ?Sized,
+          R: RangeBounds<K>,
+          T: Borrow<K>,
+    {
+        let (left, right) = self.range_indexes(range);
+        self.0.truncate(right);
+        let left = cmp::min(left, self.0.len());
+        self.0.drain(..left);
+    }
 }
 
 impl<T> Borrow<Set<T>> for SetBuf<T> {
@@ -458,9 +908,121 @@ impl<T> IntoIterator for SetBuf<T> {
     }
 }
 
+macro_rules! impl_set_operator {
+    ($trait:ident, $method:ident, $builder:ident) => {
+        impl<'a, T, S> $trait<S> for &'a Set<T>
+        where T: Ord + Clone,
+              S: AsRef<Set<T>>,
+        {
+            type Output = SetBuf<T>;
+
+            fn $method(self, other: S) -> Self::Output {
+                OpBuilder::new(self, other.as_ref()).$builder().into_set_buf()
+            }
+        }
+
+        impl<'a, T, S> $trait<S> for &'a SetBuf<T>
+        where T: Ord + Clone,
+              S: AsRef<Set<T>>,
+        {
+            type Output = SetBuf<T>;
+
+            fn $method(self, other: S) -> Self::Output {
+                OpBuilder::new(self.as_set(), other.as_ref()).$builder().into_set_buf()
+            }
+        }
+    };
+}
+
+impl_set_operator!(BitOr, bitor, union);
+impl_set_operator!(BitAnd, bitand, intersection);
+impl_set_operator!(Sub, sub, difference);
+impl_set_operator!(BitXor, bitxor, symmetric_difference);
+
+impl<T, S> BitOrAssign<S> for SetBuf<T>
+where T: Ord + Clone,
+      S: AsRef<Set<T>>,
+{
+    fn bitor_assign(&mut self, other: S) {
+        self.extend_sorted(other.as_ref());
+    }
+}
+
+impl<T, S> BitAndAssign<S> for SetBuf<T>
+where T: Ord + Clone,
+      S: AsRef<Set<T>>,
+{
+    fn bitand_assign(&mut self, other: S) {
+        let mut other = other.as_ref().as_slice();
+        self.retain(|x| {
+            other = exponential_offset_ge(other, x);
+            other.first() == Some(x)
+        });
+    }
+}
+
+impl<T, S> SubAssign<S> for SetBuf<T>
+where T: Ord + Clone,
+      S: AsRef<Set<T>>,
+{
+    fn sub_assign(&mut self, other: S) {
+        let mut other = other.as_ref().as_slice();
+        self.retain(|x| {
+            other = exponential_offset_ge(other, x);
+            other.first() != Some(x)
+        });
+    }
+}
+
+impl<T, S> BitXorAssign<S> for SetBuf<T>
+where T: Ord + Clone,
+      S: AsRef<Set<T>>,
+{
+    fn bitxor_assign(&mut self, other: S) {
+        *self = &*self ^ other;
+    }
+}
+
 #[cfg(feature="serde")]
 use serde::de::{Deserializer, Error as SerdeError};
 
+/// Serialized as a sequence, like the slice it wraps.
+#[cfg(feature="serde")]
+impl<T: Serialize> Serialize for Set<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.0.serialize(serializer)
+    }
+}
+
+/// Serialized as a sequence, like the [`Vec`] it wraps.
+#[cfg(feature="serde")]
+impl<T: Serialize> Serialize for SetBuf<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.0.serialize(serializer)
+    }
+}
+
+/// Deserialize a [`Set`] of bytes without copying them, they are checked
+/// to be sorted and deduplicated.
+///
+/// The format must be able to borrow bytes from the input, like `bincode` does.
+#[cfg(feature="serde")]
+impl<'de: 'a, 'a> Deserialize<'de> for &'a Set<u8> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let bytes = <&'de [u8]>::deserialize(deserializer)?;
+        Set::new(bytes).map_err(D::Error::custom)
+    }
+}
+
 #[cfg(feature="serde")]
 impl<'de, T> Deserialize<'de> for SetBuf<T>
 where
@@ -488,6 +1050,26 @@ where
     }
 }
 
+/// Represent the relation between two [`Set`]s, see [`Set::relation`].
+///
+/// Note that an empty set is a [`Subset`](SetRelation::Subset)
+/// of any non-empty set, not a [`Disjoint`](SetRelation::Disjoint) one.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum SetRelation {
+    /// Both sets contain exactly the same values.
+    Equal,
+    /// All the values of the first set are in the second one,
+    /// which contains other values.
+    Subset,
+    /// All the values of the second set are in the first one,
+    /// which contains other values.
+    Superset,
+    /// The sets have no value in common.
+    Disjoint,
+    /// The sets have some values in common and some that are not.
+    Overlapping,
+}
+
 /// Represent the possible errors when creating a [`Set`].
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Error {
@@ -509,6 +1091,46 @@ impl fmt::Display for Error {
 
 impl error::Error for Error {}
 
+/// Represent the possible errors when creating a [`Set`] from bytes,
+/// see [`Set::from_bytes`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum BytesError {
+    /// Define that the integers are not sorted or not deduplicated.
+    Set(Error),
+    /// Define that the bytes are not aligned for the integer type.
+    Misaligned,
+    /// Define that the length of the bytes is not a multiple of the integer size.
+    InvalidLength,
+    /// Define that the target is big-endian and cannot read little-endian integers in place.
+    BigEndian,
+}
+
+impl From<Error> for BytesError {
+    fn from(error: Error) -> BytesError {
+        BytesError::Set(error)
+    }
+}
+
+impl fmt::Display for BytesError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            BytesError::Set(e) => fmt::Display::fmt(e, f),
+            BytesError::Misaligned => f.write_str("bytes are not aligned for the integer type."),
+            BytesError::InvalidLength => f.write_str("bytes length is not a multiple of the integer size."),
+            BytesError::BigEndian => f.write_str("big-endian targets cannot read little-endian integers in place."),
+        }
+    }
+}
+
+impl error::Error for BytesError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            BytesError::Set(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
 /// The list of all [`Error`]s that can occur
 /// while trying to convert a [`slice`](std::slice) to a [`Set`].
 pub type Errors = Vec<Option<Error>>;
@@ -528,6 +1150,7 @@ pub type Errors = Vec<Option<Error>>;
 ///
 /// let sets = vec_slices_into_sets(slices).unwrap();
 /// ```
+#[allow(clippy::type_complexity)]
 pub fn vec_slices_into_sets<T: Ord>(vec: Vec<&[T]>) -> Result<Vec<&Set<T>>, (Vec<&[T]>, Errors)> {
     let mut has_error = false;
     let mut errors = Errors::with_capacity(vec.len());
@@ -604,6 +1227,35 @@ pub fn is_sort_dedup<T: Ord>(slice: &[T]) -> Result<(), Error> {
     Ok(())
 }
 
+/// An iterator over a [`Set`] checking that the elements are sorted and deduplicated,
+/// see [`Set::checked_iter`].
+pub struct CheckedIter<'a, T> {
+    iter: std::slice::Iter<'a, T>,
+    previous: Option<&'a T>,
+}
+
+impl<'a, T: Ord> Iterator for CheckedIter<'a, T> {
+    type Item = Result<&'a T, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let x = self.iter.next()?;
+        if let Some(previous) = self.previous {
+            let error = match previous.cmp(x) {
+                Ordering::Less => None,
+                Ordering::Equal => Some(Error::NotDedup),
+                Ordering::Greater => Some(Error::NotSort),
+            };
+            if let Some(error) = error {
+                // the rest of the elements are not reliable
+                self.iter = [].iter();
+                return Some(Err(error));
+            }
+        }
+        self.previous = Some(x);
+        Some(Ok(x))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -627,6 +1279,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::suspicious_to_owned)]
     fn cow_set_setbuf() {
         use std::borrow::Cow;
 
@@ -637,4 +1290,186 @@ mod tests {
 
         assert_eq!(&*owned_cow, set);
     }
+
+    #[test]
+    fn truncate_range_setbuf() {
+        let mut set = SetBuf::new(vec![1, 2, 4, 6, 7]).unwrap();
+        set.truncate_range((Excluded(1), Included(6)));
+        assert_eq!(set.as_slice(), &[2, 4, 6]);
+
+        let mut set = SetBuf::new(vec![1, 2, 4, 6, 7]).unwrap();
+        set.truncate_range(8..);
+        assert_eq!(set.as_slice(), &[]);
+    }
+
+    #[test]
+    fn extend_sorted_setbuf() {
+        let mut set = SetBuf::new(vec![3, 4, 8]).unwrap();
+        set.extend_sorted(Set::new(&[1, 4, 5, 9, 10]).unwrap());
+        assert_eq!(set.as_slice(), &[1, 3, 4, 5, 8, 9, 10]);
+
+        let mut set = SetBuf::new(vec![3, 4, 8]).unwrap();
+        set.extend_sorted(Set::new(&[3, 4]).unwrap());
+        assert_eq!(set.as_slice(), &[3, 4, 8]);
+    }
+
+    #[test]
+    fn operators_set_setbuf() {
+        let a = Set::new(&[1, 2, 4, 6, 7]).unwrap();
+        let b = SetBuf::new(vec![2, 3, 4, 5, 6, 7]).unwrap();
+
+        assert_eq!((a | &b).as_slice(), &[1, 2, 3, 4, 5, 6, 7]);
+        assert_eq!((a & &b).as_slice(), &[2, 4, 6, 7]);
+        assert_eq!((a - &b).as_slice(), &[1]);
+        assert_eq!((&b - a).as_slice(), &[3, 5]);
+        assert_eq!((&b ^ a).as_slice(), &[1, 3, 5]);
+    }
+
+    quickcheck! {
+        fn qc_relation(a: Vec<i32>, b: Vec<i32>) -> bool {
+            use std::collections::BTreeSet;
+
+            let a = SetBuf::from_dirty(a);
+            let b = SetBuf::from_dirty(b);
+            let x: BTreeSet<_> = a.iter().collect();
+            let y: BTreeSet<_> = b.iter().collect();
+
+            let expected = if x == y {
+                SetRelation::Equal
+            } else if x.is_subset(&y) {
+                SetRelation::Subset
+            } else if x.is_superset(&y) {
+                SetRelation::Superset
+            } else if x.is_disjoint(&y) {
+                SetRelation::Disjoint
+            } else {
+                SetRelation::Overlapping
+            };
+
+            a.is_subset(&b) == x.is_subset(&y)
+                && a.is_superset(&b) == x.is_superset(&y)
+                && a.is_disjoint(&b) == x.is_disjoint(&y)
+                && a.relation(&b) == expected
+        }
+
+        fn qc_assign_operators(a: Vec<i32>, b: Vec<i32>) -> bool {
+            let a = SetBuf::from_dirty(a);
+            let b = SetBuf::from_dirty(b);
+
+            let mut union = a.clone();
+            union |= &b;
+            let mut intersection = a.clone();
+            intersection &= &b;
+            let mut difference = a.clone();
+            difference -= &b;
+            let mut symmetric_difference = a.clone();
+            symmetric_difference ^= &b;
+
+            union == &a | &b
+                && intersection == &a & &b
+                && difference == &a - &b
+                && symmetric_difference == &a ^ &b
+        }
+
+        fn qc_insert_remove(a: Vec<i32>, b: Vec<i32>) -> bool {
+            use std::collections::BTreeSet;
+
+            let mut set = SetBuf::from_dirty(a.clone());
+            let mut btree: BTreeSet<_> = a.into_iter().collect();
+
+            for x in b {
+                if x % 2 == 0 {
+                    if set.insert(x) != btree.insert(x) { return false }
+                } else {
+                    if set.remove(&x) != btree.remove(&x) { return false }
+                }
+            }
+
+            is_sort_dedup(&set).is_ok() && set.iter().eq(btree.iter())
+        }
+
+        fn qc_extend_sorted(a: Vec<i32>, b: Vec<i32>) -> bool {
+            use std::collections::BTreeSet;
+
+            let mut set = SetBuf::from_dirty(a.clone());
+            let other = SetBuf::from_dirty(b.clone());
+            set.extend_sorted(&other);
+
+            let btree: BTreeSet<_> = a.into_iter().chain(b).collect();
+
+            set.iter().eq(btree.iter())
+        }
+    }
+
+    #[test]
+    #[cfg(target_endian = "little")]
+    fn from_bytes() {
+        let vec: Vec<u64> = vec![1, 2, 300, u64::MAX];
+        let bytes = Set::new_unchecked(&vec[..]).as_bytes();
+
+        let set = Set::<u64>::from_bytes(bytes).unwrap();
+        assert_eq!(set.as_slice(), &vec[..]);
+        assert_eq!(&bytes[16..24], &300u64.to_le_bytes());
+
+        let set = Set::<u32>::from_bytes_unchecked(&bytes[..8]).unwrap();
+        assert_eq!(set.as_slice(), &[1, 0]);
+        assert_eq!(Set::<u32>::from_bytes(&bytes[..16]), Err(BytesError::Set(Error::NotSort)));
+
+        assert_eq!(Set::<u64>::from_bytes(&bytes[1..9]), Err(BytesError::Misaligned));
+        assert_eq!(Set::<u64>::from_bytes(&bytes[..12]), Err(BytesError::InvalidLength));
+        assert_eq!(Set::<u64>::from_bytes(&[]), Ok(Set::new_unchecked(&[][..])));
+
+        let bytes = Set::new_unchecked(&[1u64, 1][..]).as_bytes();
+        assert_eq!(Set::<u64>::from_bytes(bytes), Err(BytesError::Set(Error::NotDedup)));
+        assert_eq!(Set::<u64>::from_bytes_unchecked(bytes).unwrap().as_slice(), &[1, 1]);
+    }
+
+    #[test]
+    #[cfg(target_endian = "little")]
+    fn from_bytes_checked_iter() {
+        let mut vec: Vec<u64> = (0..10_000).collect();
+        vec[9_998] = 9_999;
+        let bytes = Set::new_unchecked(&vec[..]).as_bytes();
+
+        let set = Set::<u64>::from_bytes_unchecked(bytes).unwrap();
+        let mut iter = set.checked_iter();
+        assert!(iter.by_ref().take(9_999).all(|x| x.is_ok()));
+        assert_eq!(iter.next(), Some(Err(Error::NotDedup)));
+        assert_eq!(iter.next(), None);
+
+        vec[9_998] = 10_000;
+        let bytes = Set::new_unchecked(&vec[..]).as_bytes();
+
+        let set = Set::<u64>::from_bytes_unchecked(bytes).unwrap();
+        let res: Result<Vec<_>, _> = set.checked_iter().collect();
+        assert_eq!(res, Err(Error::NotSort));
+    }
+}
+
+#[cfg(all(feature = "serde", test))]
+mod serde_tests {
+    use super::*;
+    use serde_test::{assert_de_tokens, assert_de_tokens_error, assert_ser_tokens, Token};
+
+    #[test]
+    fn serialize() {
+        let set = Set::new_unchecked(&[1, 2, 4]);
+        let tokens = &[Token::Seq { len: Some(3) }, Token::I32(1), Token::I32(2), Token::I32(4), Token::SeqEnd];
+
+        assert_ser_tokens(&set, tokens);
+        assert_ser_tokens(&set.to_set_buf(), tokens);
+        assert_de_tokens(&set.to_set_buf(), tokens);
+    }
+
+    #[test]
+    fn deserialize_borrowed_bytes() {
+        let set = Set::new_unchecked(&[1u8, 2, 4]);
+        assert_de_tokens(&set, &[Token::BorrowedBytes(&[1, 2, 4])]);
+
+        let error = Error::NotSort.to_string();
+        assert_de_tokens_error::<&Set<u8>>(&[Token::BorrowedBytes(&[1, 4, 2])], &error);
+
+        let error = Error::NotDedup.to_string();
+        assert_de_tokens_error::<&Set<u8>>(&[Token::BorrowedBytes(&[1, 2, 2])], &error);
+    }
 }
@@ -0,0 +1,260 @@
+//! Contains the [`WriteCollection`] that writes the results of
+//! a set operation into an [`io::Write`] as they are produced.
+//!
+//! The elements are encoded by an [`Encoder`]:
+//!   - [`LittleEndian`] and [`BigEndian`] write fixed-width integers, the little-endian ones
+//!     can be read back with [`Set::from_bytes`](crate::Set::from_bytes) or merged by the
+//!     [`external`](crate::external) set operations.
+//!   - [`Varint`] writes the deltas between consecutive elements as LEB128 varints,
+//!     like [`encode_varint`](crate::codec::encode_varint) without the number of elements.
+//!   - [`Text`] writes every element on its own line.
+//!
+//! # Examples
+//! ```
+//! # fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+//! use sdset::duo::OpBuilder;
+//! use sdset::write::{WriteCollection, Text};
+//! use sdset::{SetOperation, Set};
+//!
+//! let a = Set::new(&[1, 2, 4, 6, 7])?;
+//! let b = Set::new(&[2, 3, 4, 5, 6, 7])?;
+//!
+//! let mut output = WriteCollection::new(Vec::new(), Text);
+//! SetOperation::<&i32>::extend_collection(OpBuilder::new(a, b).intersection(), &mut output)?;
+//!
+//! assert_eq!(output.into_inner(), b"2\n4\n6\n7\n");
+//! # Ok(()) }
+//! # try_main().unwrap();
+//! ```
+
+use std::io::{self, Write};
+use std::{fmt, mem};
+use crate::codec::{Integer, write_varint_array, MAX_VARINT_LEN};
+use crate::Collection;
+
+/// Represent a way to write elements into an [`io::Write`].
+///
+/// The elements are given in order, an encoder can keep a state between them.
+pub trait Encoder<T> {
+    /// Write one element.
+    fn encode<W: Write>(&mut self, writer: &mut W, elem: &T) -> io::Result<()>;
+}
+
+/// An encoder writing fixed-width little-endian integers.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct LittleEndian;
+
+/// An encoder writing fixed-width big-endian integers.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct BigEndian;
+
+/// An encoder writing the deltas between consecutive integers as LEB128 varints,
+/// the first integer being written as is.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct Varint {
+    previous: u64,
+}
+
+/// An encoder writing every element on its own line, using its [`Display`](fmt::Display).
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct Text;
+
+impl LittleEndian {
+    fn write<W: Write, T: Integer>(writer: &mut W, elem: T) -> io::Result<()> {
+        writer.write_all(&elem.to_u64().to_le_bytes()[..mem::size_of::<T>()])
+    }
+}
+
+impl BigEndian {
+    fn write<W: Write, T: Integer>(writer: &mut W, elem: T) -> io::Result<()> {
+        writer.write_all(&elem.to_u64().to_be_bytes()[8 - mem::size_of::<T>()..])
+    }
+}
+
+impl Varint {
+    /// Construct one, the next element will be written as is.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn write<W: Write, T: Integer>(&mut self, writer: &mut W, elem: T) -> io::Result<()> {
+        let x = elem.to_u64();
+        // the elements are sorted and deduplicated, the first one is the only one that can be zero
+        debug_assert!(self.previous == 0 || x > self.previous, "elements are not sorted and deduplicated");
+
+        let mut bytes = [0; MAX_VARINT_LEN];
+        let len = write_varint_array(x - self.previous, &mut bytes);
+        self.previous = x;
+        writer.write_all(&bytes[..len])
+    }
+}
+
+impl<T: Integer> Encoder<T> for LittleEndian {
+    fn encode<W: Write>(&mut self, writer: &mut W, elem: &T) -> io::Result<()> {
+        LittleEndian::write(writer, *elem)
+    }
+}
+
+impl<T: Integer> Encoder<T> for BigEndian {
+    fn encode<W: Write>(&mut self, writer: &mut W, elem: &T) -> io::Result<()> {
+        BigEndian::write(writer, *elem)
+    }
+}
+
+impl<T: Integer> Encoder<T> for Varint {
+    fn encode<W: Write>(&mut self, writer: &mut W, elem: &T) -> io::Result<()> {
+        self.write(writer, *elem)
+    }
+}
+
+// the slices set operations produce references
+macro_rules! impl_integer_ref_encoder {
+    ($($t:ty),*) => {$(
+        impl<'a> Encoder<&'a $t> for LittleEndian {
+            fn encode<W: Write>(&mut self, writer: &mut W, elem: &&'a $t) -> io::Result<()> {
+                LittleEndian::write(writer, **elem)
+            }
+        }
+
+        impl<'a> Encoder<&'a $t> for BigEndian {
+            fn encode<W: Write>(&mut self, writer: &mut W, elem: &&'a $t) -> io::Result<()> {
+                BigEndian::write(writer, **elem)
+            }
+        }
+
+        impl<'a> Encoder<&'a $t> for Varint {
+            fn encode<W: Write>(&mut self, writer: &mut W, elem: &&'a $t) -> io::Result<()> {
+                self.write(writer, **elem)
+            }
+        }
+    )*}
+}
+
+impl_integer_ref_encoder!(u8, u16, u32, u64);
+
+impl<T: fmt::Display> Encoder<T> for Text {
+    fn encode<W: Write>(&mut self, writer: &mut W, elem: &T) -> io::Result<()> {
+        writeln!(writer, "{}", elem)
+    }
+}
+
+/// A [`Collection`] that writes the elements into an [`io::Write`] using an [`Encoder`].
+///
+/// The writer is not buffered, a [`BufWriter`](io::BufWriter) should be used
+/// to write into a file or a socket.
+///
+/// # Examples
+/// ```
+/// # fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+/// use sdset::multi::OpBuilder;
+/// use sdset::write::{WriteCollection, LittleEndian};
+/// use sdset::{SetOperation, Set};
+///
+/// let a = Set::new(&[1u64, 2, 4])?;
+/// let b = Set::new(&[2u64, 3, 5, 7])?;
+///
+/// let mut output = WriteCollection::new(Vec::new(), LittleEndian);
+/// SetOperation::<u64>::extend_collection(OpBuilder::from_vec(vec![a, b]).union(), &mut output)?;
+///
+/// let bytes = output.into_inner();
+/// assert_eq!(bytes.len(), 6 * 8);
+/// assert_eq!(&bytes[8..16], &2u64.to_le_bytes());
+/// # Ok(()) }
+/// # try_main().unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct WriteCollection<W, E> {
+    writer: W,
+    encoder: E,
+}
+
+impl<W, E> WriteCollection<W, E> {
+    /// Construct one writing into the writer with the encoder.
+    pub fn new(writer: W, encoder: E) -> Self {
+        WriteCollection { writer, encoder }
+    }
+
+    /// Returns a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Returns a mutable reference to the underlying writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    /// Returns the underlying writer, it is not flushed.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<T, W: Write, E: Encoder<T>> Collection<T> for WriteCollection<W, E> {
+
+    type Error = io::Error;
+
+    fn push(&mut self, elem: T) -> Result<(), Self::Error> {
+        self.encoder.encode(&mut self.writer, &elem)
+    }
+
+    fn extend_from_slice(&mut self, elems: &[T]) -> Result<(), Self::Error>
+    where T: Clone
+    {
+        elems.iter().try_for_each(|elem| self.encoder.encode(&mut self.writer, elem))
+    }
+
+    fn extend<I>(&mut self, elems: I) -> Result<(), Self::Error>
+    where I: IntoIterator<Item=T>
+    {
+        elems.into_iter().try_for_each(|elem| self.encoder.encode(&mut self.writer, &elem))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec::encode_varint;
+    use crate::set::{Set, sort_dedup_vec};
+
+    #[test]
+    fn fixed_width() {
+        let set = Set::new_unchecked(&[1u16, 0x0203]);
+
+        let mut output = WriteCollection::new(Vec::new(), LittleEndian);
+        output.extend_from_slice(set).unwrap();
+        assert_eq!(output.into_inner(), &[1, 0, 3, 2]);
+
+        let mut output = WriteCollection::new(Vec::new(), BigEndian);
+        output.extend(set.iter()).unwrap();
+        assert_eq!(output.into_inner(), &[0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn write_error() {
+        let mut buffer = [0; 3];
+        let mut output = WriteCollection::new(&mut buffer[..], LittleEndian);
+        output.push(1u16).unwrap();
+        let error = output.push(2u16).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::WriteZero);
+    }
+
+    quickcheck! {
+        fn qc_varint(xs: Vec<u64>) -> bool {
+            let mut xs = xs;
+            sort_dedup_vec(&mut xs);
+            let set = Set::new_unchecked(&xs[..]);
+
+            let mut output = WriteCollection::new(Vec::new(), Varint::new());
+            output.extend(set.iter().cloned()).unwrap();
+
+            // the codec writes the number of elements first
+            let mut expected = Vec::new();
+            encode_varint(set, &mut expected);
+            let mut len = [0; MAX_VARINT_LEN];
+            let len = write_varint_array(xs.len() as u64, &mut len);
+
+            expected[len..] == output.into_inner()[..]
+        }
+    }
+}